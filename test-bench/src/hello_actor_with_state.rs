@@ -1,13 +1,13 @@
 
-use std::sync::Arc;
-
 use virtual_actor_runtime::{prelude::*, errors::BoxedActorError};
 use virtual_actor_persistence::prelude::*;
 
 use super::hello_virtual_actor::HelloVirtualMessage;
 
-#[derive(Actor, VirtualActor)]
+#[derive(Actor, VirtualActor, ActorWithState)]
 #[message(HelloVirtualMessage)]
+#[after_message(save_state)]
+#[state_type(u32)]
 pub struct HelloActorWithState {
     id: u32,
     state: ActorState<Self>,
@@ -17,45 +17,26 @@ impl MessageHandler<HelloVirtualMessage> for HelloActorWithState {
     async fn handle(
         &mut self,
         msg: HelloVirtualMessage,
-        _ctx: &Self::ActorContext,
+        ctx: &Self::ActorContext,
     ) -> <HelloVirtualMessage as Message>::Result {
         *self.state += 1;
         let result = format!("Hello {} {}", msg.msg(), &*self.state);
-        self.state.save().await.map_err(|_| "unable to save state")?;
+        self.state.report_size(ctx);
         Ok(result.to_string())
     }
 }
 
-impl ActorWithState for HelloActorWithState {
-    type State = u32;
-
-    fn state(&self) -> &ActorState<Self> {
-        &self.state
-    }
-}
-
-pub struct HelloActorWithStateFactory {
-    persistence: Arc<dyn ActorPersistence<HelloActorWithState>>,
-}
-
-impl HelloActorWithStateFactory {
-    pub fn new(persistence: Arc<dyn ActorPersistence<HelloActorWithState>>) -> Self {
-        Self { persistence }
+impl HelloActorWithState {
+    async fn save_state(
+        &mut self,
+        ctx: &<Self as Actor>::ActorContext,
+    ) -> Result<(), BoxedActorError> {
+        self.state.after_message(ctx).await
     }
 }
 
-impl ActorFactory for HelloActorWithStateFactory {
-    type Actor = HelloActorWithState;
-}
-
-impl VirtualActorFactory for HelloActorWithStateFactory {
-    type Error = BoxedActorError;
-
-    async fn create_actor(
-        &self,
-        id: &<Self::Actor as VirtualActor>::ActorId,
-    ) -> Result<Self::Actor, Self::Error> {
-        let state = ActorState::load(&self.persistence, id).await?;
-        Ok(Self::Actor { id: *id, state })
+impl PersistentActorConstructor for HelloActorWithState {
+    fn new(id: &u32, state: ActorState<Self>) -> Self {
+        Self { id: *id, state }
     }
 }
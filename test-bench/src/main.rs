@@ -14,12 +14,15 @@ use std::time::{Duration, Instant};
 
 use futures::StreamExt;
 use tokio_util::codec::{FramedRead, LinesCodec};
-use virtual_actor_persistence::InmemoryPersistence;
+use virtual_actor_persistence::{
+    prelude::{PersistentActorFactory, SavePolicy},
+    InmemoryPersistence,
+};
 use virtual_actor_runtime::errors::WaitError;
 use virtual_actor_runtime::GracefulShutdown;
 use virtual_actor_runtime::{prelude::*, LocalAddr};
 
-use crate::hello_actor_with_state::{HelloActorWithStateFactory, HelloActorWithState};
+use crate::hello_actor_with_state::HelloActorWithState;
 use crate::{
     hello_actor::{HelloActor, HelloMessage},
     hello_virtual_actor::{HelloVirtualActor, HelloVirtualMessage},
@@ -274,12 +277,20 @@ async fn bench_infinite_loop_thread_sleep() -> Result<(), Box<dyn std::error::Er
 
     let mut runtime = Runtime::new()?;
     let executor = runtime.create_executor()?;
+    runtime
+        .start_watchdog(WatchdogPreferences {
+            handler_budget: Duration::from_millis(100),
+            heartbeat_budget: Duration::from_millis(100),
+            ..Default::default()
+        })
+        .await?;
 
     let addr: LocalAddr<InfiniteLoopActor> = runtime.spawn_local(&executor).await?;
 
     addr.dispatch(ThreadSleepTask).await?;
 
-    let duration = Duration::from_millis(10);
+    // let watchdog report blocked executor
+    let duration = Duration::from_millis(300);
     tokio::time::sleep(duration).await;
 
     match runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await {
@@ -306,7 +317,8 @@ async fn test_qwe() -> Result<(), Box<dyn std::error::Error>> {
     let executor = runtime.create_executor()?;
 
     let persistence = Arc::new(InmemoryPersistence::new());
-    let factory = HelloActorWithStateFactory::new(persistence);
+    let factory = PersistentActorFactory::<HelloActorWithState>::new(persistence)
+        .with_save_policy(SavePolicy::EveryMessage);
 
     runtime.register_actor_with_factory(factory, &executor)?;
 
@@ -5,8 +5,8 @@ use quote::{quote, quote_spanned, ToTokens};
 use syn::{parse_macro_input, DeriveInput};
 
 use super::{
-    context_attribute::ContextAttribute, message_attribute::MessageAttribute,
-    render_actor_trait_impl, render_internal_mod,
+    context_attribute::ContextAttribute, hook_attribute::AfterMessageAttribute,
+    message_attribute::MessageAttribute, render_actor_trait_impl, render_internal_mod,
 };
 
 /// Implementation of derive macro for [`Message`]
@@ -49,6 +49,18 @@ pub fn actor_derive(input: TokenStream) -> TokenStream {
         None => Box::new(ContextAttribute::default_context_tokens(&ast.ident)),
     };
 
+    let after_message = match AfterMessageAttribute::pase_attribute(&ast.attrs) {
+        Some(Ok(x)) => Some(x.render(&ast.ident)),
+        Some(Err(x)) => {
+            return quote_spanned! {
+                ast.ident.span() =>
+                compile_error!(#x);
+            }
+            .into();
+        }
+        None => None,
+    };
+
     let (internal_mod_name, messages_envelope_name, internal_mod_code) =
         render_internal_mod::render(&messages, name);
 
@@ -58,6 +70,7 @@ pub fn actor_derive(input: TokenStream) -> TokenStream {
         &internal_mod_name,
         &messages_envelope_name,
         &context,
+        after_message.as_ref().map(|x| x as &dyn ToTokens),
     );
 
     quote! {
@@ -0,0 +1,47 @@
+//! Hook attribute parser
+
+use quote::{quote_spanned, ToTokens};
+use syn::{Attribute, MetaList};
+
+/// Parsed `after_message` attribute
+pub struct AfterMessageAttribute {
+    /// Actor method called after message is processed
+    method: syn::Ident,
+}
+
+impl AfterMessageAttribute {
+    /// Loads `after_message` attribute
+    pub fn pase_attribute(attrs: &[Attribute]) -> Option<Result<Self, String>> {
+        attrs.iter().find_map(|attr| match &attr.meta {
+            syn::Meta::List(meta) => {
+                if meta.path.is_ident("after_message") {
+                    Some(Self::parse(meta))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+    }
+
+    /// Render `after_message` hook calling actor method
+    pub fn render(&self, actor_name: &syn::Ident) -> impl ToTokens {
+        let method = &self.method;
+        quote_spanned! {
+            actor_name.span() =>
+            async fn after_message(
+                &mut self,
+                ctx: &Self::ActorContext,
+            ) -> Result<(), ::virtual_actor_runtime::errors::BoxedActorError> {
+                self.#method(ctx).await
+            }
+        }
+    }
+
+    /// Extracts method name from attribute
+    fn parse(attr: &MetaList) -> Result<Self, String> {
+        attr.parse_args::<syn::Ident>()
+            .map(|method| Self { method })
+            .map_err(|e| e.to_string())
+    }
+}
@@ -60,6 +60,17 @@ impl MessageAttribute {
         }
     }
 
+    /// Builds item for `MessageEnvelope::message_name` impl
+    pub fn to_enum_item_name(&self, indent: &syn::Ident) -> impl ToTokens {
+        let index = self.index;
+        let name = format_ident!("Message_{}", index);
+        let msg_type_str = self.type_ident.to_string();
+        quote_spanned! {
+            indent.span() =>
+            Self::#name(_, _) => #msg_type_str
+        }
+    }
+
     /// Builds enum factory for message type
     pub fn to_enum_factory(
         &self,
@@ -2,6 +2,7 @@
 
 mod context_attribute;
 mod derive_impl;
+mod hook_attribute;
 mod message_attribute;
 mod render_actor_trait_impl;
 mod render_internal_mod;
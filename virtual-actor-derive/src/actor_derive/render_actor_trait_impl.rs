@@ -11,6 +11,7 @@ pub fn render(
     internal_mod_name: &syn::Ident,
     messages_envelope_name: &syn::Ident,
     context_attribute: &dyn ToTokens,
+    after_message: Option<&dyn ToTokens>,
 ) -> impl ToTokens {
     let rendered_envelope_handler = if messages.is_empty() {
         quote_spanned! {
@@ -58,6 +59,8 @@ pub fn render(
                 stringify!(#actor_name)
             }
 
+            #after_message
+
             #rendered_envelope_handler
         }
     }
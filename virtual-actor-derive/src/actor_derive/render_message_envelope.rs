@@ -40,6 +40,11 @@ pub fn render(
         .map(|i| i.to_enum_item_debug(&messages_envelope_name))
         .collect::<Vec<_>>();
 
+    let envelope_name_items = messages
+        .iter()
+        .map(|i| i.to_enum_item_name(&messages_envelope_name))
+        .collect::<Vec<_>>();
+
     let envelope_factories = messages
         .iter()
         .map(|i| i.to_enum_factory(&messages_envelope_name, actor_name))
@@ -53,7 +58,13 @@ pub fn render(
                 #(#envelope_items),*
             }
 
-            impl ::virtual_actor_runtime::prelude::MessageEnvelope<#actor_name> for #messages_envelope_name {}
+            impl ::virtual_actor_runtime::prelude::MessageEnvelope<#actor_name> for #messages_envelope_name {
+                fn message_name(&self) -> ::virtual_actor_runtime::prelude::MessageName {
+                    match self {
+                        #(#envelope_name_items),*
+                    }
+                }
+            }
 
             #(#envelope_factories)*
 
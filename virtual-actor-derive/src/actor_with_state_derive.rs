@@ -0,0 +1,105 @@
+//! Derive macro for actor with state
+
+use proc_macro::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{parse_macro_input, Attribute, DeriveInput, Fields};
+
+/// Implementation of derive macro for [`ActorWithState`]
+pub fn actor_with_state_derive(input: TokenStream) -> TokenStream {
+    // Parse the input tokens into a syntax tree
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    let actor_struct: syn::DataStruct = match ast.data {
+        syn::Data::Struct(data) => data,
+        _ => {
+            return quote_spanned! {
+                ast.ident.span() =>
+                compile_error!("Macro expects struct as input");
+            }
+            .into();
+        }
+    };
+
+    let Some(state_type) = get_state_type_attribute(&ast.attrs) else {
+        return quote_spanned! {
+            ast.ident.span() =>
+            compile_error!("Struct must have `state_type` attribute. For ex. #[state_type(u64)]");
+        }
+        .into();
+    };
+    let state_type = match state_type {
+        Ok(state_type) => state_type,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let name = &ast.ident;
+    let state_field = match get_state_field_attribute(&ast.attrs) {
+        Some(Ok(state_field)) => state_field,
+        Some(Err(e)) => return e.to_compile_error().into(),
+        None => syn::Ident::new("state", name.span()),
+    };
+
+    if !has_field(&actor_struct.fields, &state_field) {
+        return syn::Error::new(
+            ast.ident.span(),
+            format!("Struct must have `{state_field}` field"),
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let expanded = quote! {
+        impl ::virtual_actor_persistence::prelude::ActorWithState for #name {
+            type State = #state_type;
+
+            fn state(&self) -> &::virtual_actor_persistence::prelude::ActorState<Self> {
+                &self.#state_field
+            }
+
+            fn state_mut(&mut self) -> &mut ::virtual_actor_persistence::prelude::ActorState<Self> {
+                &mut self.#state_field
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Checks that struct has named field
+fn has_field(fields: &Fields, field_name: &syn::Ident) -> bool {
+    match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .any(|f| f.ident.as_ref() == Some(field_name)),
+        _ => false,
+    }
+}
+
+/// Finds `state_type` attribute required for [`ActorWithState`]
+fn get_state_type_attribute(attrs: &[Attribute]) -> Option<syn::Result<syn::Type>> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        syn::Meta::List(meta) => {
+            if meta.path.is_ident("state_type") {
+                Some(meta.parse_args::<syn::Type>())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    })
+}
+
+/// Finds optional `state_field` attribute
+fn get_state_field_attribute(attrs: &[Attribute]) -> Option<syn::Result<syn::Ident>> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        syn::Meta::List(meta) => {
+            if meta.path.is_ident("state_field") {
+                Some(meta.parse_args::<syn::Ident>())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    })
+}
@@ -2,6 +2,7 @@
 //! Depends on `virtual-actor` and `virtual-actor-runtime` crates
 
 mod actor_derive;
+mod actor_with_state_derive;
 mod local_actor_derive;
 mod message_derive;
 mod virtual_actor_derive;
@@ -17,7 +18,11 @@ use proc_macro::TokenStream;
 /// You can pass actor context using `context` attribute with type of context.
 /// If you don't pass context, `virtual_actor_runtime::RuntimeContext` will be used as default.
 /// For example: `#[context(TestContext)]`
-#[proc_macro_derive(Actor, attributes(message, context))]
+///
+/// You can run actor method after every processed message using `after_message` attribute,
+/// method takes actor context and returns `Result<(), BoxedActorError>`.
+/// For example: `#[after_message(save_state)]`
+#[proc_macro_derive(Actor, attributes(message, context, after_message))]
 pub fn derive_actor(input: TokenStream) -> TokenStream {
     actor_derive::actor_derive(input)
 }
@@ -26,7 +31,9 @@ pub fn derive_actor(input: TokenStream) -> TokenStream {
 ///
 /// Requires `result` attribute with type of result.
 /// For example: `#[result(Result<u64, u8>)]`
-#[proc_macro_derive(Message, attributes(result))]
+///
+/// Use `#[idempotent]` attribute to allow redelivery of message, message must implement `Clone`.
+#[proc_macro_derive(Message, attributes(result, idempotent))]
 pub fn derive_message(input: TokenStream) -> TokenStream {
     message_derive::message_derive(input)
 }
@@ -48,3 +55,15 @@ pub fn derive_local_actor(input: TokenStream) -> TokenStream {
 pub fn derive_virtual_message(input: TokenStream) -> TokenStream {
     virtual_message_derive::virtual_message_derive(input)
 }
+
+/// Derive macro for [`ActorWithState`] trait
+///
+/// Requires `state_type` attribute with type of state.
+/// For example: `#[state_type(u64)]`
+///
+/// State is stored in `state` field of type `ActorState<Self>`,
+/// use `state_field` attribute to pass another field. For example: `#[state_field(counter)]`
+#[proc_macro_derive(ActorWithState, attributes(state_type, state_field))]
+pub fn derive_actor_with_state(input: TokenStream) -> TokenStream {
+    actor_with_state_derive::actor_with_state_derive(input)
+}
@@ -33,12 +33,24 @@ pub fn message_derive(input: TokenStream) -> TokenStream {
 
     let name = &ast.ident;
 
+    let idempotent_copy = if is_idempotent(&ast.attrs) {
+        quote! {
+            fn idempotent_copy(&self) -> Option<Self> {
+                Some(::core::clone::Clone::clone(self))
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     // Build the output, possibly using quasi-quotation
     let expanded = quote! {
         // The generated impl.
 
         impl ::virtual_actor_runtime::prelude::Message for #name {
             type Result = #tokens;
+
+            #idempotent_copy
         }
     };
 
@@ -59,3 +71,8 @@ fn get_result_attribute(attrs: &[Attribute]) -> Option<&MetaList> {
         _ => None,
     })
 }
+
+/// Checks `idempotent` attribute of message
+fn is_idempotent(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("idempotent"))
+}
@@ -1,16 +1,213 @@
-use futures::future::BoxFuture;
-use virtual_actor_runtime::errors::BoxedActorError;
+use std::{
+    borrow::Cow,
+    fmt,
+    marker::PhantomData,
+    sync::{Arc, LazyLock},
+    time::SystemTime,
+};
+
+use dashmap::DashMap;
+use futures::future::{self, BoxFuture};
+use serde::{Deserialize, Serialize};
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::VirtualActor};
 
 use crate::actor_with_state_trait::ActorWithState;
+use crate::transaction::PreparedTransaction;
+
+/// Slot of prepared transactions of default state
+const TRANSACTION_SLOT: SlotName = "__transaction";
+
+/// Version of persisted state, changed on every save
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ETag(pub u64);
+
+impl fmt::Display for ETag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Name of state slot, actor can have several named states stored independently
+pub type SlotName = &'static str;
+
+/// Ids of persisted states with their versions
+pub type VersionedIds<I> = Vec<(I, ETag)>;
+
+/// Slot of prepared transactions of state stored in `slot`
+pub(crate) fn transaction_slot(slot: Option<SlotName>) -> SlotName {
+    // slot names are static, so number of interned names is bounded
+    static SLOTS: LazyLock<DashMap<SlotName, SlotName>> = LazyLock::new(DashMap::new);
+
+    match slot {
+        Some(slot) => *SLOTS
+            .entry(slot)
+            .or_insert_with(|| Box::leak(format!("{slot}.{TRANSACTION_SLOT}").into_boxed_str())),
+        None => TRANSACTION_SLOT,
+    }
+}
+
+/// Namespace of persisted states of actor type, slot name is appended for named states
+pub(crate) fn state_namespace<A: VirtualActor>(slot: Option<SlotName>) -> Cow<'static, str> {
+    match slot {
+        Some(slot) => Cow::Owned(format!("{}.{slot}", A::name())),
+        None => Cow::Borrowed(A::name()),
+    }
+}
+
+/// Persistence error
+#[derive(Debug, thiserror::Error)]
+pub enum PersistenceError {
+    /// State was changed by another writer
+    #[error("Inconsistent state: expected etag {expected:?}, actual {actual:?}")]
+    InconsistentState {
+        /// Version known to writer, `None` if state was not persisted
+        expected: Option<ETag>,
+        /// Version of persisted state, `None` if state is not persisted
+        actual: Option<ETag>,
+    },
+    /// Storage does not track time of last save
+    #[error("Listing of expired states is not supported by storage")]
+    ExpiryNotSupported,
+    /// Storage can not enumerate its states
+    #[error("Listing of states is not supported by storage")]
+    ListingNotSupported,
+    /// Storage can not store prepared transactions
+    #[error("Transactions are not supported by storage")]
+    TransactionsNotSupported,
+}
 
 /// Actor persistence
-pub trait ActorPersistence<A: ActorWithState>: Send + Sync + 'static {
+///
+/// Stores state `S` of actor `A`, by default it is `ActorWithState::State`
+pub trait ActorPersistence<A: VirtualActor, S = <A as ActorWithState>::State>:
+    Send + Sync + 'static
+{
     /// Load state
-    fn load(&self, id: &A::ActorId) -> BoxFuture<Result<Option<A::State>, BoxedActorError>>;
+    fn load(&self, id: &A::ActorId) -> BoxFuture<'_, Result<Option<S>, BoxedActorError>>;
 
     /// Save state
-    fn save(&self, id: &A::ActorId, state: &A::State) -> BoxFuture<Result<(), BoxedActorError>>;
+    fn save(&self, id: &A::ActorId, state: &S) -> BoxFuture<'_, Result<(), BoxedActorError>>;
+
+    /// Load state with its version
+    fn load_versioned(
+        &self,
+        id: &A::ActorId,
+    ) -> BoxFuture<'_, Result<Option<(S, ETag)>, BoxedActorError>>;
+
+    /// Save state if persisted version matches `expected`, `None` if state is not persisted yet.
+    /// Returns new version of state.
+    ///
+    /// Fails with `PersistenceError::InconsistentState` if versions do not match.
+    fn save_versioned(
+        &self,
+        id: &A::ActorId,
+        state: &S,
+        expected: Option<ETag>,
+    ) -> BoxFuture<'_, Result<ETag, BoxedActorError>>;
 
     /// Clear state
-    fn clear(&self, id: &A::ActorId) -> BoxFuture<Result<(), BoxedActorError>>;
+    fn clear(&self, id: &A::ActorId) -> BoxFuture<'_, Result<(), BoxedActorError>>;
+
+    /// Clear state if persisted version matches `expected`
+    ///
+    /// Fails with `PersistenceError::InconsistentState` if versions do not match.
+    fn clear_versioned(
+        &self,
+        id: &A::ActorId,
+        expected: ETag,
+    ) -> BoxFuture<'_, Result<(), BoxedActorError>>;
+
+    /// Ids and versions of at most `limit` states last saved before `saved_before`
+    ///
+    /// Fails with `PersistenceError::ExpiryNotSupported` if storage does not track time of last save.
+    fn expired(
+        &self,
+        _saved_before: SystemTime,
+        _limit: usize,
+    ) -> BoxFuture<'_, Result<VersionedIds<A::ActorId>, BoxedActorError>> {
+        Box::pin(future::ready(Err(BoxedActorError::new(
+            PersistenceError::ExpiryNotSupported,
+        ))))
+    }
+
+    /// Ids of all persisted states
+    ///
+    /// Fails with `PersistenceError::ListingNotSupported` if storage can not enumerate its states.
+    fn ids(&self) -> BoxFuture<'_, Result<Vec<A::ActorId>, BoxedActorError>> {
+        Box::pin(future::ready(Err(BoxedActorError::new(
+            PersistenceError::ListingNotSupported,
+        ))))
+    }
+
+    /// Same storage bound to named state slot,
+    /// states of slot are stored under keys including slot name
+    fn slot(&self, name: SlotName) -> Arc<dyn ActorPersistence<A, S>>;
+
+    /// Same storage bound to prepared transactions of states of this slot,
+    /// see `ActorState::prepare`
+    ///
+    /// Storage returned by default fails with `PersistenceError::TransactionsNotSupported`.
+    fn prepared_transactions(&self) -> Arc<dyn ActorPersistence<A, PreparedTransaction>> {
+        Arc::new(NoTransactions(PhantomData))
+    }
+}
+
+/// Prepared transactions of storage which does not support transactions
+struct NoTransactions<A>(PhantomData<fn() -> A>);
+
+impl<A> NoTransactions<A> {
+    fn unsupported<T: Send + 'static>() -> BoxFuture<'static, Result<T, BoxedActorError>> {
+        Box::pin(future::ready(Err(BoxedActorError::new(
+            PersistenceError::TransactionsNotSupported,
+        ))))
+    }
+}
+
+impl<A: VirtualActor> ActorPersistence<A, PreparedTransaction> for NoTransactions<A> {
+    fn load(
+        &self,
+        _id: &A::ActorId,
+    ) -> BoxFuture<'_, Result<Option<PreparedTransaction>, BoxedActorError>> {
+        Self::unsupported()
+    }
+
+    fn save(
+        &self,
+        _id: &A::ActorId,
+        _state: &PreparedTransaction,
+    ) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        Self::unsupported()
+    }
+
+    fn load_versioned(
+        &self,
+        _id: &A::ActorId,
+    ) -> BoxFuture<'_, Result<Option<(PreparedTransaction, ETag)>, BoxedActorError>> {
+        Self::unsupported()
+    }
+
+    fn save_versioned(
+        &self,
+        _id: &A::ActorId,
+        _state: &PreparedTransaction,
+        _expected: Option<ETag>,
+    ) -> BoxFuture<'_, Result<ETag, BoxedActorError>> {
+        Self::unsupported()
+    }
+
+    fn clear(&self, _id: &A::ActorId) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        Self::unsupported()
+    }
+
+    fn clear_versioned(
+        &self,
+        _id: &A::ActorId,
+        _expected: ETag,
+    ) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        Self::unsupported()
+    }
+
+    fn slot(&self, _name: SlotName) -> Arc<dyn ActorPersistence<A, PreparedTransaction>> {
+        Arc::new(Self(PhantomData))
+    }
 }
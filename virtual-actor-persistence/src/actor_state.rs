@@ -3,26 +3,54 @@
 use std::{
     ops::{Deref, DerefMut},
     sync::Arc,
+    time::Instant,
 };
 
-use virtual_actor_runtime::errors::BoxedActorError;
+use serde::{de::DeserializeOwned, Serialize};
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::VirtualActor, RuntimeContext};
 
 use super::actor_with_state_trait::ActorWithState;
-use super::actor_persistence_trait::ActorPersistence;
+use super::actor_persistence_trait::{ActorPersistence, ETag, SlotName};
+use super::save_policy::{PendingChanges, SavePolicy};
+use super::state_version::{MigrationPolicy, StateVersion, Versioned};
+use super::transaction::{
+    Enlistment, PreparedTransaction, TransactionError, TransactionId, TransactionScope,
+};
 
 /// Container for actor state
-pub struct ActorState<A>
+///
+/// State `S` is `ActorWithState::State` by default,
+/// actor can hold additional states of other types in named slots.
+pub struct ActorState<A, S = <A as ActorWithState>::State>
 where
-    A: ActorWithState,
+    A: VirtualActor,
 {
     actor_id: A::ActorId,
-    persistence: Arc<dyn ActorPersistence<A>>,
-    state: A::State,
+    persistence: Arc<dyn ActorPersistence<A, S>>,
+    state: S,
+    /// Name of state slot, `None` for default state
+    slot: Option<SlotName>,
+    /// Version of loaded or last saved state
+    etag: Option<ETag>,
+    save_policy: SavePolicy,
+    /// State was accessed mutably during current message
+    touched: bool,
+    pending: Option<PendingChanges>,
+    last_save: Option<Instant>,
+    /// State is loaded with `load_transactional` and can be enlisted in transactions
+    transactional: bool,
+    /// Transaction state is enlisted in
+    transaction: Option<Enlistment<S>>,
+    /// Storage of prepared transaction of state
+    prepared_transactions: Arc<dyn ActorPersistence<A, PreparedTransaction>>,
+    /// Transaction prepared by previous activation, restored by first transactional call
+    recovered: Option<PreparedTransaction>,
 }
 
-impl<A> ActorState<A>
+impl<A, S> ActorState<A, S>
 where
-    A: ActorWithState,
+    A: VirtualActor,
+    S: Default + 'static,
 {
     /// Create a new actor state
     ///
@@ -30,52 +58,488 @@ where
     ///
     /// Returns error from persistence layer
     pub async fn load(
-        persistence: &Arc<dyn ActorPersistence<A>>,
+        persistence: &Arc<dyn ActorPersistence<A, S>>,
         id: &A::ActorId,
     ) -> Result<Self, BoxedActorError> {
-        let state = persistence.load(id).await?.unwrap_or_default();
+        let (state, etag) = match persistence.load_versioned(id).await? {
+            Some((state, etag)) => (state, Some(etag)),
+            None => (S::default(), None),
+        };
         Ok(Self {
             actor_id: id.clone(),
             persistence: persistence.clone(),
             state,
+            slot: None,
+            etag,
+            save_policy: SavePolicy::default(),
+            touched: false,
+            pending: None,
+            last_save: None,
+            transactional: false,
+            transaction: None,
+            prepared_transactions: persistence.prepared_transactions(),
+            recovered: None,
         })
     }
 
-    /// Save state
+    /// Create a new actor state which can be enlisted in transactions,
+    /// transaction prepared by previous activation is restored with it
+    ///
+    /// # Errors
+    ///
+    /// Returns error from persistence layer,
+    /// e.g. `PersistenceError::TransactionsNotSupported`
+    pub async fn load_transactional(
+        persistence: &Arc<dyn ActorPersistence<A, S>>,
+        id: &A::ActorId,
+    ) -> Result<Self, BoxedActorError> {
+        let mut state = Self::load(persistence, id).await?;
+        state.recovered = state.prepared_transactions.load(id).await?;
+        if state
+            .recovered
+            .as_ref()
+            .is_some_and(|prepared| prepared.etag != state.etag)
+        {
+            // state was saved by commit after it was prepared
+            state.prepared_transactions.clear(id).await?;
+            state.recovered = None;
+        }
+        state.transactional = true;
+        Ok(state)
+    }
+
+    /// Create a new actor state stored in named slot of persistence,
+    /// it is saved independently of other states of actor
     ///
     /// # Errors
     ///
     /// Returns error from persistence layer
-    pub async fn save(&self) -> Result<(), BoxedActorError> {
-        self.persistence.save(&self.actor_id, &self.state).await
+    pub async fn load_slot(
+        persistence: &Arc<dyn ActorPersistence<A, S>>,
+        id: &A::ActorId,
+        name: SlotName,
+    ) -> Result<Self, BoxedActorError> {
+        let mut state = Self::load(&persistence.slot(name), id).await?;
+        state.slot = Some(name);
+        Ok(state)
+    }
+
+    /// Name of state slot, `None` for default state of actor
+    pub fn slot_name(&self) -> Option<SlotName> {
+        self.slot
+    }
+
+    /// Sets policy of saving changed state after messages
+    #[must_use]
+    pub fn with_save_policy(mut self, save_policy: SavePolicy) -> Self {
+        self.save_policy = save_policy;
+        self
+    }
+
+    /// Save state if it was not changed by another writer since it was loaded or saved
+    ///
+    /// # Errors
+    ///
+    /// Returns `PersistenceError::InconsistentState` if persisted state was changed,
+    /// `TransactionError::Prepared` if state is prepared in transaction
+    /// or error from persistence layer
+    pub async fn save(&mut self) -> Result<(), BoxedActorError> {
+        if let Some(id) = self.prepared_transaction() {
+            return Err(BoxedActorError::new(TransactionError::Prepared(id)));
+        }
+        let etag = self
+            .persistence
+            .save_versioned(&self.actor_id, &self.state, self.etag)
+            .await?;
+        self.etag = Some(etag);
+        self.touched = false;
+        self.pending = None;
+        self.last_save = Some(Instant::now());
+        Ok(())
     }
 
-    /// Clear state
+    /// Records changes made by processed message and saves them according to save policy,
+    /// call it from actor `after_message` hook.
+    /// Actor is woken up to save changes which are left unsaved, see `RuntimeContext::wake_after`.
     ///
     /// # Errors
     ///
     /// Returns error from persistence layer
-    pub async fn clear(&self) -> Result<(), BoxedActorError> {
-        self.persistence.clear(&self.actor_id).await
+    pub async fn after_message(&mut self, ctx: &RuntimeContext<A>) -> Result<(), BoxedActorError> {
+        if std::mem::take(&mut self.touched) {
+            self.pending
+                .get_or_insert(PendingChanges {
+                    since: Instant::now(),
+                    messages: 0,
+                })
+                .messages += 1;
+        }
+        let Some(pending) = self.pending else {
+            return Ok(());
+        };
+        if self.save_policy.should_save(&pending, self.last_save) {
+            return self.save().await;
+        }
+        if let Some(deadline) = self.save_policy.deadline(&pending, self.last_save) {
+            ctx.wake_after(deadline.saturating_duration_since(Instant::now()));
+        }
+        Ok(())
+    }
+
+    /// Saves unsaved changes, call it when actor is deactivated
+    ///
+    /// # Errors
+    ///
+    /// Returns error from persistence layer
+    pub async fn flush(&mut self) -> Result<(), BoxedActorError> {
+        if self.is_dirty() {
+            self.save().await?;
+        }
+        Ok(())
+    }
+
+    /// State has changes which are not saved
+    pub fn is_dirty(&self) -> bool {
+        self.touched || self.pending.is_some()
+    }
+
+    /// Version of loaded or last saved state, `None` if state is not persisted
+    pub fn etag(&self) -> Option<ETag> {
+        self.etag
+    }
+
+    /// Transaction state is prepared in, it is finished by commit or rollback only
+    fn prepared_transaction(&self) -> Option<TransactionId> {
+        match (&self.transaction, &self.recovered) {
+            (_, Some(prepared)) => Some(prepared.scope.id),
+            (Some(enlistment), None) if enlistment.prepared => Some(enlistment.scope.id),
+            _ => None,
+        }
+    }
+
+    /// Clear state if it was not changed by another writer since it was loaded or saved
+    ///
+    /// # Errors
+    ///
+    /// Returns `PersistenceError::InconsistentState` if persisted state was changed,
+    /// `TransactionError::Prepared` if state is prepared in transaction
+    /// or error from persistence layer
+    pub async fn clear(&mut self) -> Result<(), BoxedActorError> {
+        if let Some(id) = self.prepared_transaction() {
+            return Err(BoxedActorError::new(TransactionError::Prepared(id)));
+        }
+        match self.etag {
+            Some(etag) => self.persistence.clear_versioned(&self.actor_id, etag).await?,
+            None => self.persistence.clear(&self.actor_id).await?,
+        }
+        self.etag = None;
+        self.touched = false;
+        self.pending = None;
+        Ok(())
     }
 }
 
-impl<A> Deref for ActorState<A>
+impl<A, S> ActorState<A, S>
+where
+    A: VirtualActor,
+    S: Clone + Default + Serialize + DeserializeOwned + 'static,
+{
+    /// Enlists state in transaction, returns copy of state changed by transaction.
+    /// Copy is applied on commit, actor state is unchanged until then.
+    ///
+    /// State can be enlisted in one transaction at a time,
+    /// transaction holding state after its deadline is rolled back unless state is prepared.
+    /// State has to be loaded with `load_transactional`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransactionError::Conflict` if state is enlisted in another transaction,
+    /// `TransactionError::Timeout` if deadline of transaction has passed
+    /// or `TransactionError::Aborted` if state is not loaded with `load_transactional`
+    pub fn enlist(&mut self, scope: TransactionScope) -> Result<&mut S, TransactionError> {
+        if !self.transactional {
+            return Err(TransactionError::Aborted {
+                transaction: scope.id,
+                reason: "State is not loaded with `ActorState::load_transactional`".to_string(),
+            });
+        }
+        if scope.is_expired() {
+            return Err(TransactionError::Timeout(scope.id));
+        }
+        if let Some(prepared) = &self.recovered {
+            return Err(if prepared.scope.id == scope.id {
+                TransactionError::Aborted {
+                    transaction: scope.id,
+                    reason: "State is already prepared".to_string(),
+                }
+            } else {
+                TransactionError::Conflict {
+                    transaction: scope.id,
+                    holder: prepared.scope.id,
+                }
+            });
+        }
+        match &self.transaction {
+            Some(holder)
+                if holder.scope.id != scope.id
+                    && (holder.prepared || !holder.scope.is_expired()) =>
+            {
+                return Err(TransactionError::Conflict {
+                    transaction: scope.id,
+                    holder: holder.scope.id,
+                });
+            }
+            Some(holder) if holder.scope.id == scope.id => {}
+            _ => {
+                self.transaction = Some(Enlistment {
+                    scope,
+                    staged: self.state.clone(),
+                    etag: self.etag,
+                    prepared: false,
+                });
+            }
+        }
+        let enlistment = self.enlistment(scope.id)?;
+        if enlistment.prepared {
+            // prepared state is voted for commit and can not be changed
+            return Err(TransactionError::Aborted {
+                transaction: scope.id,
+                reason: "State is already prepared".to_string(),
+            });
+        }
+        Ok(&mut enlistment.staged)
+    }
+
+    /// Transaction state is enlisted in
+    pub fn transaction(&self) -> Option<TransactionId> {
+        self.recovered
+            .as_ref()
+            .map(|p| p.scope.id)
+            .or(self.transaction.as_ref().map(|e| e.scope.id))
+    }
+
+    /// Votes for commit if state was not changed outside of transaction since it was enlisted,
+    /// call it from `PrepareTransaction` handler.
+    /// Enlistment is rolled back if vote is negative.
+    ///
+    /// Prepared state is persisted next to the state and actor is held active until transaction
+    /// is finished, prepared state of deactivated actor is restored by its next activation.
+    /// State must not be saved outside of transaction until then.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransactionError::StateChanged` if state was changed after it was enlisted,
+    /// `TransactionError::Timeout` if deadline of transaction has passed,
+    /// `TransactionError::NotEnlisted` if state is not enlisted in transaction
+    /// or `TransactionError::Persistence` if prepared state can not be saved
+    pub async fn prepare(
+        &mut self,
+        id: TransactionId,
+        ctx: &RuntimeContext<A>,
+    ) -> Result<(), TransactionError> {
+        self.recover(ctx)?;
+        let enlistment = self.enlistment(id)?;
+        if enlistment.prepared {
+            // vote is repeated
+            return Ok(());
+        }
+        if enlistment.scope.is_expired() {
+            self.transaction = None;
+            return Err(TransactionError::Timeout(id));
+        }
+        let enlisted_etag = enlistment.etag;
+
+        let persisted = self
+            .persistence
+            .load_versioned(&self.actor_id)
+            .await
+            .map_err(|e| TransactionError::Persistence(e.to_string()));
+        let vote = match persisted {
+            Ok(persisted) => {
+                let persisted_etag = persisted.map(|(_, etag)| etag);
+                if self.is_dirty() || self.etag != enlisted_etag || persisted_etag != enlisted_etag
+                {
+                    Err(TransactionError::StateChanged(id))
+                } else {
+                    self.persist_prepared(id).await
+                }
+            }
+            Err(e) => Err(e),
+        };
+        match vote {
+            Ok(()) => {
+                self.enlistment(id)?.prepared = true;
+                ctx.hold_activation();
+            }
+            Err(_) => self.transaction = None,
+        }
+        vote
+    }
+
+    /// Saves and applies state changed by transaction, call it from `CommitTransaction` handler
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransactionError::NotPrepared` if state was not prepared,
+    /// `TransactionError::NotEnlisted` if state is not enlisted in transaction,
+    /// e.g. transaction is already committed,
+    /// or `TransactionError::Persistence` if state can not be saved
+    pub async fn commit(
+        &mut self,
+        id: TransactionId,
+        ctx: &RuntimeContext<A>,
+    ) -> Result<(), TransactionError> {
+        self.recover(ctx)?;
+        let Some(enlistment) = self.transaction.as_ref().filter(|e| e.scope.id == id) else {
+            return Err(TransactionError::NotEnlisted(id));
+        };
+        if !enlistment.prepared {
+            return Err(TransactionError::NotPrepared(id));
+        }
+        let etag = self
+            .persistence
+            .save_versioned(&self.actor_id, &enlistment.staged, enlistment.etag)
+            .await
+            .map_err(|e| TransactionError::Persistence(e.to_string()))?;
+
+        if let Some(enlistment) = self.transaction.take() {
+            self.state = enlistment.staged;
+        }
+        self.etag = Some(etag);
+        self.touched = false;
+        self.pending = None;
+        self.last_save = Some(Instant::now());
+        ctx.release_activation();
+
+        // prepared state left after failure is recognized as committed by its version
+        if let Err(e) = self.prepared_transactions.clear(&self.actor_id).await {
+            eprintln!(
+                "Failed to clear prepared state of {}::{} in transaction {id}: {e:?}",
+                A::name(),
+                self.actor_id
+            );
+        }
+        Ok(())
+    }
+
+    /// Discards state changed by transaction, call it from `AbortTransaction` handler
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransactionError::Persistence` if prepared state can not be cleared,
+    /// state stays prepared then
+    pub async fn rollback(
+        &mut self,
+        id: TransactionId,
+        ctx: &RuntimeContext<A>,
+    ) -> Result<(), TransactionError> {
+        self.recover(ctx)?;
+        let Some(enlistment) = self.transaction.as_ref().filter(|e| e.scope.id == id) else {
+            return Ok(());
+        };
+        if enlistment.prepared {
+            self.prepared_transactions
+                .clear(&self.actor_id)
+                .await
+                .map_err(|e| TransactionError::Persistence(e.to_string()))?;
+            ctx.release_activation();
+        }
+        self.transaction = None;
+        Ok(())
+    }
+
+    /// Saves enlisted state before vote for commit
+    async fn persist_prepared(&mut self, id: TransactionId) -> Result<(), TransactionError> {
+        let enlistment = self.enlistment(id)?;
+        let prepared = PreparedTransaction {
+            scope: enlistment.scope,
+            etag: enlistment.etag,
+            staged: bincode::serialize(&enlistment.staged)
+                .map_err(|e| TransactionError::Persistence(e.to_string()))?,
+        };
+        self.prepared_transactions
+            .save(&self.actor_id, &prepared)
+            .await
+            .map_err(|e| TransactionError::Persistence(e.to_string()))
+    }
+
+    /// Restores transaction prepared by previous activation
+    fn recover(&mut self, ctx: &RuntimeContext<A>) -> Result<(), TransactionError> {
+        let Some(prepared) = &self.recovered else {
+            return Ok(());
+        };
+        let staged = bincode::deserialize(&prepared.staged)
+            .map_err(|e| TransactionError::Persistence(e.to_string()))?;
+        self.transaction = Some(Enlistment {
+            scope: prepared.scope,
+            staged,
+            etag: prepared.etag,
+            prepared: true,
+        });
+        self.recovered = None;
+        ctx.hold_activation();
+        Ok(())
+    }
+
+    fn enlistment(&mut self, id: TransactionId) -> Result<&mut Enlistment<S>, TransactionError> {
+        self.transaction
+            .as_mut()
+            .filter(|e| e.scope.id == id)
+            .ok_or(TransactionError::NotEnlisted(id))
+    }
+}
+
+impl<A> ActorState<A>
 where
     A: ActorWithState,
 {
-    type Target = A::State;
+    /// Reports estimated size of state to runtime,
+    /// see `ActorWithState::estimate_state_size`
+    pub fn report_size(&self, ctx: &RuntimeContext<A>) {
+        ctx.report_memory_usage(A::estimate_state_size(&self.state));
+    }
+}
+
+impl<A, S> ActorState<A, Versioned<S>>
+where
+    A: VirtualActor,
+    S: StateVersion + Default + 'static,
+{
+    /// Create a new actor state, state of older version is migrated while it is loaded
+    ///
+    /// # Errors
+    ///
+    /// Returns error from persistence layer
+    pub async fn load_with_policy(
+        persistence: &Arc<dyn ActorPersistence<A, Versioned<S>>>,
+        id: &A::ActorId,
+        policy: MigrationPolicy,
+    ) -> Result<Self, BoxedActorError> {
+        let mut state = Self::load(persistence, id).await?;
+        if policy == MigrationPolicy::Rewrite && state.migrated_from().is_some() {
+            state.save().await?;
+        }
+        Ok(state)
+    }
+}
+
+impl<A, S> Deref for ActorState<A, S>
+where
+    A: VirtualActor,
+{
+    type Target = S;
 
     fn deref(&self) -> &Self::Target {
         &self.state
     }
 }
 
-impl<A> DerefMut for ActorState<A>
+impl<A, S> DerefMut for ActorState<A, S>
 where
-    A: ActorWithState,
+    A: VirtualActor,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        self.touched = true;
         &mut self.state
     }
 }
@@ -10,4 +10,14 @@ pub trait ActorWithState: VirtualActor {
 
     /// Accessor for actor state
     fn state(&self) -> &ActorState<Self>;
+
+    /// Mutable accessor for actor state
+    fn state_mut(&mut self) -> &mut ActorState<Self>;
+
+    /// Estimated memory used by state in bytes,
+    /// reported to runtime to detect memory pressure.
+    /// Override it for states with heap allocated data.
+    fn estimate_state_size(state: &Self::State) -> usize {
+        std::mem::size_of_val(state)
+    }
 }
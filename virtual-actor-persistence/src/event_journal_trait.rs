@@ -0,0 +1,92 @@
+use futures::future::BoxFuture;
+use virtual_actor_runtime::errors::BoxedActorError;
+
+use crate::event_sourced_actor_trait::EventSourcedActor;
+
+/// Journal error
+#[derive(Debug, thiserror::Error)]
+pub enum JournalError {
+    /// Journal was appended concurrently
+    #[error("Sequence mismatch: expected {expected}, actual {actual}")]
+    SequenceMismatch {
+        /// Sequence number expected by writer
+        expected: u64,
+        /// Sequence number of last event in journal
+        actual: u64,
+    },
+    /// Failed to serialize id
+    #[error("Failed to serialize id: {0}")]
+    SerializeId(String),
+    /// Failed to serialize event
+    #[error("Failed to serialize event: {0}")]
+    SerializeEvent(String),
+    /// Failed to deserialize event
+    #[error("Failed to deserialize event: {0}")]
+    DeserializeEvent(String),
+    /// Journal storage error
+    #[error("Journal io error: {0:?}")]
+    Io(#[from] std::io::Error),
+    /// Journal storage is corrupted
+    #[error("Journal is corrupted: {0}")]
+    Corrupted(String),
+}
+
+impl JournalError {
+    #[allow(clippy::needless_pass_by_value)]
+    pub(crate) fn failed_to_serialize_id(e: bincode::Error) -> BoxedActorError {
+        BoxedActorError::new(Self::SerializeId(e.to_string()))
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    pub(crate) fn failed_to_serialize_event(e: bincode::Error) -> BoxedActorError {
+        BoxedActorError::new(Self::SerializeEvent(e.to_string()))
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    pub(crate) fn failed_to_deserialize_event(e: bincode::Error) -> BoxedActorError {
+        BoxedActorError::new(Self::DeserializeEvent(e.to_string()))
+    }
+}
+
+/// Journaled event with its sequence number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry<E> {
+    /// Sequence number of event, starting from 1
+    pub sequence: u64,
+    /// Event
+    pub event: E,
+}
+
+/// Event journal
+pub trait EventJournal<A: EventSourcedActor>: Send + Sync + 'static {
+    /// Appends events to the end of journal.
+    /// `expected_sequence` is sequence number of last event known to writer, 0 for empty journal.
+    /// Returns sequence number of last appended event.
+    ///
+    /// Fails with `JournalError::SequenceMismatch` if journal contains other events.
+    fn append<'a>(
+        &'a self,
+        id: &A::ActorId,
+        expected_sequence: u64,
+        events: &[A::Event],
+    ) -> BoxFuture<'a, Result<u64, BoxedActorError>>;
+
+    /// Reads events starting from `from_sequence` inclusive,
+    /// truncated events are skipped
+    fn read(
+        &self,
+        id: &A::ActorId,
+        from_sequence: u64,
+    ) -> BoxFuture<'_, Result<Vec<JournalEntry<A::Event>>, BoxedActorError>>;
+
+    /// Removes events up to `to_sequence` inclusive,
+    /// sequence numbers of remaining and further events are kept
+    fn truncate(
+        &self,
+        id: &A::ActorId,
+        to_sequence: u64,
+    ) -> BoxFuture<'_, Result<(), BoxedActorError>>;
+
+    /// Removes all events
+    fn clear(&self, id: &A::ActorId) -> BoxFuture<'_, Result<(), BoxedActorError>>;
+}
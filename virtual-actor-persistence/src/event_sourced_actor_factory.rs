@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::*};
+
+use super::actor_persistence_trait::ActorPersistence;
+use super::event_journal_trait::EventJournal;
+use super::event_sourced_actor_trait::EventSourcedActor;
+use super::event_sourced_state::{EventSourcedState, SnapshotPersistence};
+use super::snapshot::{Snapshot, SnapshotPolicy};
+
+/// Factory of event sourced actors, recovers state from journal on activation
+pub struct EventSourcedActorFactory<A>
+where
+    A: EventSourcedActor,
+{
+    journal: Arc<dyn EventJournal<A>>,
+    snapshots: Option<(SnapshotPersistence<A>, SnapshotPolicy)>,
+}
+
+impl<A> EventSourcedActorFactory<A>
+where
+    A: EventSourcedActor,
+{
+    /// Create a new factory
+    #[must_use]
+    pub fn new(journal: Arc<dyn EventJournal<A>>) -> Self {
+        Self {
+            journal,
+            snapshots: None,
+        }
+    }
+
+    /// Create a new factory, which takes snapshots according to policy
+    #[must_use]
+    pub fn with_snapshots(
+        journal: Arc<dyn EventJournal<A>>,
+        snapshots: Arc<dyn ActorPersistence<A, Snapshot<A::State>>>,
+        policy: SnapshotPolicy,
+    ) -> Self {
+        Self {
+            journal,
+            snapshots: Some((snapshots, policy)),
+        }
+    }
+}
+
+impl<A> ActorFactory for EventSourcedActorFactory<A>
+where
+    A: EventSourcedActor,
+{
+    type Actor = A;
+}
+
+impl<A> VirtualActorFactory for EventSourcedActorFactory<A>
+where
+    A: EventSourcedActor,
+{
+    type Error = BoxedActorError;
+
+    async fn create_actor(&self, id: &A::ActorId) -> Result<A, Self::Error> {
+        let state = match &self.snapshots {
+            Some((snapshots, policy)) => {
+                EventSourcedState::recover_with_snapshots(&self.journal, snapshots, policy, id)
+                    .await?
+            }
+            None => EventSourcedState::recover(&self.journal, id).await?,
+        };
+        Ok(A::recovered(id, state))
+    }
+
+    async fn deactivate_actor(&self, actor: &mut A) -> Result<(), Self::Error> {
+        actor.state_mut().deactivate().await
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{de::DeserializeOwned, Serialize};
+use virtual_actor_runtime::prelude::VirtualActor;
+
+use super::event_sourced_state::EventSourcedState;
+
+/// Event sourced actor trait
+///
+/// State of actor is recovered on activation by replaying events from `EventJournal`,
+/// starting from the latest snapshot if snapshots are enabled.
+pub trait EventSourcedActor: VirtualActor {
+    /// Type of actor state
+    type State: Serialize + DeserializeOwned + Default;
+
+    /// Type of journaled event
+    type Event: Serialize + DeserializeOwned + Send + Sync + 'static;
+
+    /// Applies event to state, must be deterministic
+    fn apply(state: &mut Self::State, event: &Self::Event);
+
+    /// Creates actor from recovered state, used by `EventSourcedActorFactory`
+    fn recovered(id: &Self::ActorId, state: EventSourcedState<Self>) -> Self;
+
+    /// Accessor for actor state
+    fn state_mut(&mut self) -> &mut EventSourcedState<Self>;
+
+    /// Estimated memory used by recovered state in bytes,
+    /// same as `ActorWithState::estimate_state_size`
+    fn estimate_state_size(state: &Self::State) -> usize {
+        std::mem::size_of_val(state)
+    }
+}
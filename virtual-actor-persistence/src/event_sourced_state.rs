@@ -0,0 +1,229 @@
+//! Event sourced state
+
+use std::{ops::Deref, sync::Arc, time::Instant};
+
+use virtual_actor_runtime::{errors::BoxedActorError, RuntimeContext};
+
+use super::actor_persistence_trait::ActorPersistence;
+use super::event_journal_trait::EventJournal;
+use super::event_sourced_actor_trait::EventSourcedActor;
+use super::snapshot::{Snapshot, SnapshotPolicy};
+
+/// Persistence of event sourced actor snapshots
+pub(crate) type SnapshotPersistence<A> =
+    Arc<dyn ActorPersistence<A, Snapshot<<A as EventSourcedActor>::State>>>;
+
+/// Snapshots settings and progress
+struct Snapshots<A>
+where
+    A: EventSourcedActor,
+{
+    persistence: SnapshotPersistence<A>,
+    policy: SnapshotPolicy,
+    /// Sequence number of last taken snapshot
+    sequence: u64,
+    /// Time of last taken snapshot or recovery
+    timestamp: Instant,
+}
+
+/// Container for event sourced actor state
+///
+/// State is changed only by persisting events, so it provides read only access.
+pub struct EventSourcedState<A>
+where
+    A: EventSourcedActor,
+{
+    actor_id: A::ActorId,
+    journal: Arc<dyn EventJournal<A>>,
+    snapshots: Option<Snapshots<A>>,
+    current: Snapshot<A::State>,
+}
+
+impl<A> EventSourcedState<A>
+where
+    A: EventSourcedActor,
+{
+    /// Recovers state by replaying all journaled events
+    ///
+    /// # Errors
+    ///
+    /// Returns error from journal
+    pub async fn recover(
+        journal: &Arc<dyn EventJournal<A>>,
+        id: &A::ActorId,
+    ) -> Result<Self, BoxedActorError> {
+        let current = Self::replay(journal, id, Snapshot::default()).await?;
+        Ok(Self {
+            actor_id: id.clone(),
+            journal: journal.clone(),
+            snapshots: None,
+            current,
+        })
+    }
+
+    /// Recovers state from the latest snapshot and replays events persisted after it
+    ///
+    /// # Errors
+    ///
+    /// Returns error from journal or snapshot persistence
+    pub async fn recover_with_snapshots(
+        journal: &Arc<dyn EventJournal<A>>,
+        snapshots: &Arc<dyn ActorPersistence<A, Snapshot<A::State>>>,
+        policy: &SnapshotPolicy,
+        id: &A::ActorId,
+    ) -> Result<Self, BoxedActorError> {
+        let snapshot = snapshots.load(id).await?.unwrap_or_default();
+        let snapshot_sequence = snapshot.sequence;
+        let current = Self::replay(journal, id, snapshot).await?;
+        Ok(Self {
+            actor_id: id.clone(),
+            journal: journal.clone(),
+            snapshots: Some(Snapshots {
+                persistence: snapshots.clone(),
+                policy: policy.clone(),
+                sequence: snapshot_sequence,
+                timestamp: Instant::now(),
+            }),
+            current,
+        })
+    }
+
+    async fn replay(
+        journal: &Arc<dyn EventJournal<A>>,
+        id: &A::ActorId,
+        mut snapshot: Snapshot<A::State>,
+    ) -> Result<Snapshot<A::State>, BoxedActorError> {
+        for entry in journal.read(id, snapshot.sequence + 1).await? {
+            A::apply(&mut snapshot.state, &entry.event);
+            snapshot.sequence = entry.sequence;
+        }
+        Ok(snapshot)
+    }
+
+    /// Appends event to journal and applies it to state
+    ///
+    /// # Errors
+    ///
+    /// Returns error from journal, state is not changed in this case
+    pub async fn persist(&mut self, event: A::Event) -> Result<(), BoxedActorError> {
+        self.persist_all(vec![event]).await
+    }
+
+    /// Appends events to journal atomically and applies them to state,
+    /// then takes snapshot if required by policy
+    ///
+    /// # Errors
+    ///
+    /// Returns error from journal, state is not changed in this case.
+    /// Returns error from snapshot persistence, events are persisted in this case.
+    pub async fn persist_all(&mut self, events: Vec<A::Event>) -> Result<(), BoxedActorError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let sequence = self
+            .journal
+            .append(&self.actor_id, self.current.sequence, &events)
+            .await?;
+        for event in &events {
+            A::apply(&mut self.current.state, event);
+        }
+        self.current.sequence = sequence;
+
+        if self.is_snapshot_due() {
+            self.snapshot().await?;
+        }
+        Ok(())
+    }
+
+    fn is_snapshot_due(&self) -> bool {
+        let Some(snapshots) = &self.snapshots else {
+            return false;
+        };
+        let policy = &snapshots.policy;
+        policy
+            .every_events
+            .is_some_and(|count| self.current.sequence - snapshots.sequence >= count)
+            || policy
+                .every_interval
+                .is_some_and(|interval| snapshots.timestamp.elapsed() >= interval)
+    }
+
+    /// Takes snapshot of current state if there are events after the last snapshot,
+    /// and truncates journal if required by policy.
+    /// Does nothing if snapshots are not enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns error from snapshot persistence or journal
+    pub async fn snapshot(&mut self) -> Result<(), BoxedActorError> {
+        let Some(snapshots) = &mut self.snapshots else {
+            return Ok(());
+        };
+        if snapshots.sequence == self.current.sequence {
+            return Ok(());
+        }
+        snapshots
+            .persistence
+            .save(&self.actor_id, &self.current)
+            .await?;
+        snapshots.sequence = self.current.sequence;
+        snapshots.timestamp = Instant::now();
+
+        if snapshots.policy.truncate_journal {
+            self.journal
+                .truncate(&self.actor_id, self.current.sequence)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Takes snapshot if required by policy on deactivation,
+    /// called by `EventSourcedActorFactory`
+    ///
+    /// # Errors
+    ///
+    /// Returns error from snapshot persistence or journal
+    pub async fn deactivate(&mut self) -> Result<(), BoxedActorError> {
+        match &self.snapshots {
+            Some(snapshots) if snapshots.policy.on_deactivation => self.snapshot().await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Sequence number of last applied event, 0 if there are no events
+    pub fn sequence(&self) -> u64 {
+        self.current.sequence
+    }
+
+    /// Reports estimated size of state to runtime,
+    /// see `EventSourcedActor::estimate_state_size`
+    pub fn report_size(&self, ctx: &RuntimeContext<A>) {
+        ctx.report_memory_usage(A::estimate_state_size(&self.current.state));
+    }
+
+    /// Clears journal and snapshot, resets state to default
+    ///
+    /// # Errors
+    ///
+    /// Returns error from journal or snapshot persistence
+    pub async fn clear(&mut self) -> Result<(), BoxedActorError> {
+        if let Some(snapshots) = &mut self.snapshots {
+            snapshots.persistence.clear(&self.actor_id).await?;
+            snapshots.sequence = 0;
+        }
+        self.journal.clear(&self.actor_id).await?;
+        self.current = Snapshot::default();
+        Ok(())
+    }
+}
+
+impl<A> Deref for EventSourcedState<A>
+where
+    A: EventSourcedActor,
+{
+    type Target = A::State;
+
+    fn deref(&self) -> &Self::Target {
+        &self.current.state
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use dashmap::DashMap;
+use futures::future::BoxFuture;
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+use virtual_actor_runtime::errors::BoxedActorError;
+
+use super::event_journal_trait::{EventJournal, JournalEntry, JournalError};
+use super::event_sourced_actor_trait::EventSourcedActor;
+use super::key_encoding::KeyEncoding;
+
+const HEADER_SIZE: usize = std::mem::size_of::<u64>();
+const LENGTH_PREFIX_SIZE: usize = std::mem::size_of::<u32>();
+
+/// Sequence number of last event in journal file, `None` until file is scanned
+type JournalFileLock = Arc<Mutex<Option<u64>>>;
+
+/// Decoded journal file
+struct JournalFile<'a> {
+    /// Number of truncated events
+    truncated: u64,
+    /// Serialized events
+    records: Vec<&'a [u8]>,
+    /// Length of complete header and batches,
+    /// bytes after it are left by a write interrupted by crash
+    len: usize,
+}
+
+impl<'a> JournalFile<'a> {
+    fn decode(bytes: &'a [u8]) -> Self {
+        let Some((header, mut rest)) = bytes.split_first_chunk::<HEADER_SIZE>() else {
+            // header is written together with first records, nothing was committed
+            return Self {
+                truncated: 0,
+                records: Vec::new(),
+                len: 0,
+            };
+        };
+        let truncated = u64::from_le_bytes(*header);
+
+        let mut records = Vec::new();
+        while let Some((batch, tail)) = split_record(rest) {
+            let Some(batch) = decode_batch(batch) else {
+                break;
+            };
+            records.extend(batch);
+            rest = tail;
+        }
+        Self {
+            truncated,
+            records,
+            len: bytes.len() - rest.len(),
+        }
+    }
+
+    fn last_sequence(&self) -> u64 {
+        self.truncated + self.records.len() as u64
+    }
+}
+
+/// Splits complete length prefixed record from the start of `bytes`
+fn split_record(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (prefix, tail) = bytes.split_first_chunk::<LENGTH_PREFIX_SIZE>()?;
+    let len = u32::from_le_bytes(*prefix) as usize;
+    (tail.len() >= len).then(|| tail.split_at(len))
+}
+
+/// Decodes records of batch, `None` if batch is malformed
+fn decode_batch(mut batch: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut records = Vec::new();
+    while !batch.is_empty() {
+        let (record, tail) = split_record(batch)?;
+        records.push(record);
+        batch = tail;
+    }
+    Some(records)
+}
+
+/// File backed event journal
+///
+/// Events of each actor are stored in separate append only file
+/// `<root>/<actor name>/<hex encoded id>.journal`:
+/// number of truncated events followed by length prefixed batches,
+/// each batch holds length prefixed records of events appended together.
+/// Partial batch left at the end of file by interrupted append is dropped as a whole
+/// when file is read, so events appended together are never replayed partially.
+/// Directory must not be shared between several journal instances.
+pub struct FileJournal {
+    root: PathBuf,
+    locks: Arc<DashMap<PathBuf, JournalFileLock>>,
+}
+
+impl FileJournal {
+    /// Create a new file journal stored in `root` directory
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            locks: Arc::new(DashMap::new()),
+        }
+    }
+
+    fn journal_path<A: EventSourcedActor>(
+        &self,
+        id: &A::ActorId,
+    ) -> Result<PathBuf, BoxedActorError> {
+        let file_name = KeyEncoding::Hex
+            .encode(id)
+            .map_err(JournalError::failed_to_serialize_id)?;
+        Ok(self
+            .root
+            .join(A::name())
+            .join(format!("{file_name}.journal")))
+    }
+
+    fn lock(&self, path: &Path) -> JournalFileLock {
+        self.locks.entry(path.to_path_buf()).or_default().clone()
+    }
+}
+
+async fn read_file(path: &Path) -> Result<Vec<u8>, JournalError> {
+    let mut bytes = match tokio::fs::read(path).await {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let len = JournalFile::decode(&bytes).len;
+    if len < bytes.len() {
+        // drop partial trailing batch, so following appends are not lost after it
+        let file = tokio::fs::OpenOptions::new().write(true).open(path).await?;
+        file.set_len(len as u64).await?;
+        file.sync_data().await?;
+        bytes.truncate(len);
+    }
+    Ok(bytes)
+}
+
+fn encode_record(bytes: &mut Vec<u8>, record: &[u8]) -> Result<(), JournalError> {
+    let len = u32::try_from(record.len())
+        .map_err(|_| JournalError::Corrupted("record is too large".into()))?;
+    bytes.extend_from_slice(&len.to_le_bytes());
+    bytes.extend_from_slice(record);
+    Ok(())
+}
+
+/// Encodes records as one batch, which is read back whole or not at all
+fn encode_batch<'a>(
+    bytes: &mut Vec<u8>,
+    records: impl IntoIterator<Item = &'a [u8]>,
+) -> Result<(), JournalError> {
+    let mut batch = Vec::new();
+    for record in records {
+        encode_record(&mut batch, record)?;
+    }
+    encode_record(bytes, &batch)
+}
+
+async fn append_file(path: &Path, records: &[Vec<u8>]) -> Result<(), JournalError> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    let mut bytes = Vec::new();
+    if file.metadata().await?.len() == 0 {
+        bytes.extend_from_slice(&0_u64.to_le_bytes());
+    }
+    encode_batch(&mut bytes, records.iter().map(Vec::as_slice))?;
+    file.write_all(&bytes).await?;
+    file.sync_data().await?;
+    Ok(())
+}
+
+/// Replaces journal file atomically
+async fn rewrite_file(path: &Path, truncated: u64, records: &[&[u8]]) -> Result<(), JournalError> {
+    let mut bytes = truncated.to_le_bytes().to_vec();
+    if !records.is_empty() {
+        encode_batch(&mut bytes, records.iter().copied())?;
+    }
+
+    let tmp_path = path.with_extension("journal.tmp");
+    let mut file = tokio::fs::File::create(&tmp_path).await?;
+    file.write_all(&bytes).await?;
+    file.sync_data().await?;
+    tokio::fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
+impl<A> EventJournal<A> for FileJournal
+where
+    A: EventSourcedActor,
+{
+    fn append<'a>(
+        &'a self,
+        id: &A::ActorId,
+        expected_sequence: u64,
+        events: &[A::Event],
+    ) -> BoxFuture<'a, Result<u64, BoxedActorError>> {
+        let path = self.journal_path::<A>(id);
+        let events_bytes = events
+            .iter()
+            .map(bincode::serialize)
+            .collect::<Result<Vec<_>, _>>();
+
+        Box::pin(async move {
+            let path = path?;
+            let events_bytes = events_bytes.map_err(JournalError::failed_to_serialize_event)?;
+
+            let lock = self.lock(&path);
+            let mut last_sequence = lock.lock().await;
+            let actual = if let Some(sequence) = *last_sequence {
+                sequence
+            } else {
+                let file = read_file(&path).await.map_err(BoxedActorError::new)?;
+                JournalFile::decode(&file).last_sequence()
+            };
+            if actual != expected_sequence {
+                *last_sequence = Some(actual);
+                return Err(BoxedActorError::new(JournalError::SequenceMismatch {
+                    expected: expected_sequence,
+                    actual,
+                }));
+            }
+
+            if let Err(e) = append_file(&path, &events_bytes).await {
+                // partially written batch is dropped by rescan on next append
+                *last_sequence = None;
+                return Err(BoxedActorError::new(e));
+            }
+
+            let sequence = actual + events_bytes.len() as u64;
+            *last_sequence = Some(sequence);
+            Ok(sequence)
+        })
+    }
+
+    fn read(
+        &self,
+        id: &A::ActorId,
+        from_sequence: u64,
+    ) -> BoxFuture<'_, Result<Vec<JournalEntry<A::Event>>, BoxedActorError>> {
+        let path = self.journal_path::<A>(id);
+
+        Box::pin(async move {
+            let path = path?;
+            let lock = self.lock(&path);
+            let _guard = lock.lock().await;
+            let file = read_file(&path).await.map_err(BoxedActorError::new)?;
+            let journal = JournalFile::decode(&file);
+
+            (journal.truncated + 1..)
+                .zip(journal.records)
+                .skip_while(|(sequence, _)| *sequence < from_sequence)
+                .map(|(sequence, bytes)| {
+                    bincode::deserialize::<A::Event>(bytes)
+                        .map(|event| JournalEntry { sequence, event })
+                        .map_err(JournalError::failed_to_deserialize_event)
+                })
+                .collect()
+        })
+    }
+
+    fn truncate(
+        &self,
+        id: &A::ActorId,
+        to_sequence: u64,
+    ) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let path = self.journal_path::<A>(id);
+
+        Box::pin(async move {
+            let path = path?;
+            let lock = self.lock(&path);
+            let _guard = lock.lock().await;
+            let file = read_file(&path).await.map_err(BoxedActorError::new)?;
+            let journal = JournalFile::decode(&file);
+
+            let to_sequence = to_sequence.min(journal.last_sequence());
+            if to_sequence <= journal.truncated {
+                return Ok(());
+            }
+            let count = usize::try_from(to_sequence - journal.truncated).unwrap_or(usize::MAX);
+            rewrite_file(&path, to_sequence, &journal.records[count..])
+                .await
+                .map_err(BoxedActorError::new)
+        })
+    }
+
+    fn clear(&self, id: &A::ActorId) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let path = self.journal_path::<A>(id);
+
+        Box::pin(async move {
+            let path = path?;
+            let lock = self.lock(&path);
+            let mut last_sequence = lock.lock().await;
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(BoxedActorError::new(JournalError::from(e))),
+            }
+            *last_sequence = Some(0);
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,479 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use dashmap::DashMap;
+use futures::future::BoxFuture;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    sync::Mutex,
+};
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::VirtualActor};
+
+use super::actor_persistence_trait::{
+    state_namespace, transaction_slot, ActorPersistence, ETag, PersistenceError, SlotName,
+    VersionedIds,
+};
+use super::key_encoding::KeyEncoding;
+use super::state_codec::{BincodeCodec, CodecError, Codecs, StateCodec};
+use super::state_version::decode_state;
+use super::transaction::PreparedTransaction;
+
+const ETAG_SIZE: usize = std::mem::size_of::<u64>();
+const STATE_EXTENSION: &str = "state";
+const TMP_EXTENSION: &str = "state.tmp";
+
+/// File persistence error
+#[derive(Debug, thiserror::Error)]
+pub enum FilePersistenceError {
+    /// Failed to serialize id
+    #[error("Failed to serialize id: {0}")]
+    SerializeId(String),
+    /// Failed to decode id from state file name
+    #[error("Failed to decode id from state file name: {0}")]
+    DeserializeId(PathBuf),
+    /// Failed to serialize state
+    #[error("Failed to serialize state: {0}")]
+    SerializeState(String),
+    /// Failed to deserialize state
+    #[error("Failed to deserialize state: {0}")]
+    DeserializeState(String),
+    /// State file is corrupted
+    #[error("State file is corrupted: {0}")]
+    Corrupted(PathBuf),
+    /// File system error
+    #[error("File system error: {0:?}")]
+    Io(#[from] std::io::Error),
+}
+
+impl FilePersistenceError {
+    #[allow(clippy::needless_pass_by_value)]
+    fn failed_to_serialize_id(e: bincode::Error) -> BoxedActorError {
+        BoxedActorError::new(Self::SerializeId(e.to_string()))
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn failed_to_serialize_state(e: CodecError) -> BoxedActorError {
+        BoxedActorError::new(Self::SerializeState(e.to_string()))
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn failed_to_deserialize_state(e: CodecError) -> BoxedActorError {
+        BoxedActorError::new(Self::DeserializeState(e.to_string()))
+    }
+
+    fn io(e: std::io::Error) -> BoxedActorError {
+        BoxedActorError::new(Self::Io(e))
+    }
+}
+
+/// Defines how state files are flushed to disk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// Never sync, state can be lost on power failure, but never partially written
+    Never,
+    /// Sync file data before it replaces previous state
+    Data,
+    /// Sync file before it replaces previous state and directory after that
+    #[default]
+    Full,
+}
+
+/// File persistence preferences
+#[derive(Debug, Clone, Default)]
+pub struct FilePersistencePreferences {
+    /// Encoding of actor id into file name
+    pub key_encoding: KeyEncoding,
+    /// Fsync policy
+    pub fsync: FsyncPolicy,
+}
+
+/// File system actor state persistence
+///
+/// State of each actor is stored in `<root>/<actor name>/<encoded id>.state`,
+/// states of named slot in `<root>/<actor name>.<slot>/<encoded id>.state`
+/// as etag followed by state encoded with codec `C`.
+/// State is written to temporary file and atomically renamed,
+/// temporary files left after crash are removed on next load.
+/// Directory must not be shared between several persistence instances.
+#[derive(Clone)]
+pub struct FilePersistence<C: StateCodec = BincodeCodec> {
+    root: PathBuf,
+    preferences: FilePersistencePreferences,
+    locks: Arc<DashMap<PathBuf, Arc<Mutex<()>>>>,
+    /// Source of etags, initialized from current time, so etags are not reused after restart
+    last_etag: Arc<AtomicU64>,
+    codecs: Codecs<C>,
+    slot: Option<SlotName>,
+}
+
+impl FilePersistence {
+    /// Create a new file persistence stored in `root` directory
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self::with_preferences(root, FilePersistencePreferences::default())
+    }
+
+    /// Create a new file persistence with preferences
+    pub fn with_preferences(
+        root: impl Into<PathBuf>,
+        preferences: FilePersistencePreferences,
+    ) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX));
+        Self {
+            root: root.into(),
+            preferences,
+            locks: Arc::new(DashMap::new()),
+            last_etag: Arc::new(AtomicU64::new(now)),
+            codecs: Codecs::new(BincodeCodec),
+            slot: None,
+        }
+    }
+}
+
+impl<C: StateCodec> FilePersistence<C> {
+    /// Replaces state codec, overrides for actor types are reset
+    #[must_use]
+    pub fn with_codec<C2: StateCodec>(self, codec: C2) -> FilePersistence<C2> {
+        FilePersistence {
+            root: self.root,
+            preferences: self.preferences,
+            locks: self.locks,
+            last_etag: self.last_etag,
+            codecs: Codecs::new(codec),
+            slot: self.slot,
+        }
+    }
+
+    /// Overrides state codec for actor type
+    #[must_use]
+    pub fn with_actor_codec<A: VirtualActor>(mut self, codec: C) -> Self {
+        self.codecs.set::<A>(codec);
+        self
+    }
+
+    fn state_path<A: VirtualActor>(&self, id: &A::ActorId) -> Result<PathBuf, BoxedActorError> {
+        let key = self
+            .preferences
+            .key_encoding
+            .encode(id)
+            .map_err(FilePersistenceError::failed_to_serialize_id)?;
+        Ok(self
+            .root
+            .join(state_namespace::<A>(self.slot).as_ref())
+            .join(format!("{key}.{STATE_EXTENSION}")))
+    }
+
+    fn lock(&self, path: &Path) -> Arc<Mutex<()>> {
+        self.locks.entry(path.to_path_buf()).or_default().clone()
+    }
+
+    fn next_etag(&self) -> ETag {
+        ETag(self.last_etag.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    /// Reads etag and serialized state, removes leftover of interrupted write
+    async fn read_state(path: &Path) -> Result<Option<(ETag, Vec<u8>)>, BoxedActorError> {
+        match tokio::fs::remove_file(path.with_extension(TMP_EXTENSION)).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(FilePersistenceError::io(e)),
+        }
+
+        let mut bytes = match tokio::fs::read(path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(FilePersistenceError::io(e)),
+        };
+        let Some(etag) = bytes.first_chunk::<ETAG_SIZE>() else {
+            return Err(BoxedActorError::new(FilePersistenceError::Corrupted(
+                path.to_path_buf(),
+            )));
+        };
+        let etag = ETag(u64::from_le_bytes(*etag));
+        bytes.drain(..ETAG_SIZE);
+        Ok(Some((etag, bytes)))
+    }
+
+    async fn load_state<A: VirtualActor, S: DeserializeOwned>(
+        &self,
+        path: &Path,
+    ) -> Result<Option<(S, ETag)>, BoxedActorError> {
+        let lock = self.lock(path);
+        let _guard = lock.lock().await;
+        match Self::read_state(path).await? {
+            Some((etag, bytes)) => match decode_state::<_, S>(self.codecs.get::<A>(), &bytes) {
+                Ok(state) => Ok(Some((state, etag))),
+                Err(e) => Err(FilePersistenceError::failed_to_deserialize_state(e)),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Reads etag of persisted state, files are replaced atomically so header matches state
+    async fn read_etag(path: &Path) -> Result<Option<ETag>, BoxedActorError> {
+        let mut file = match tokio::fs::File::open(path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(FilePersistenceError::io(e)),
+        };
+        let mut etag = [0; ETAG_SIZE];
+        match file.read_exact(&mut etag).await {
+            Ok(_) => Ok(Some(ETag(u64::from_le_bytes(etag)))),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => Err(BoxedActorError::new(
+                FilePersistenceError::Corrupted(path.to_path_buf()),
+            )),
+            Err(e) => Err(FilePersistenceError::io(e)),
+        }
+    }
+
+    /// Replaces state file atomically
+    async fn write_state(
+        &self,
+        path: &Path,
+        etag: ETag,
+        state_bytes: &[u8],
+    ) -> Result<(), std::io::Error> {
+        let dir = path.parent().unwrap_or(&self.root);
+        tokio::fs::create_dir_all(dir).await?;
+
+        let tmp_path = path.with_extension(TMP_EXTENSION);
+        let mut file = tokio::fs::File::create(&tmp_path).await?;
+        file.write_all(&etag.0.to_le_bytes()).await?;
+        file.write_all(state_bytes).await?;
+        if self.preferences.fsync != FsyncPolicy::Never {
+            file.sync_data().await?;
+        }
+        drop(file);
+
+        tokio::fs::rename(&tmp_path, path).await?;
+        if self.preferences.fsync == FsyncPolicy::Full {
+            tokio::fs::File::open(dir).await?.sync_all().await?;
+        }
+        Ok(())
+    }
+}
+
+impl<A, S, C> ActorPersistence<A, S> for FilePersistence<C>
+where
+    A: VirtualActor,
+    S: Serialize + DeserializeOwned,
+    C: StateCodec,
+{
+    fn load(&self, id: &A::ActorId) -> BoxFuture<'_, Result<Option<S>, BoxedActorError>> {
+        let path = self.state_path::<A>(id);
+
+        Box::pin(async move {
+            let state = self.load_state::<A, S>(&path?).await?;
+            Ok(state.map(|(state, _)| state))
+        })
+    }
+
+    fn save(&self, id: &A::ActorId, state: &S) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let path = self.state_path::<A>(id);
+        let state_bytes = self.codecs.get::<A>().encode(state);
+
+        Box::pin(async move {
+            let path = path?;
+            let state_bytes =
+                state_bytes.map_err(FilePersistenceError::failed_to_serialize_state)?;
+
+            let lock = self.lock(&path);
+            let _guard = lock.lock().await;
+            self.write_state(&path, self.next_etag(), &state_bytes)
+                .await
+                .map_err(FilePersistenceError::io)
+        })
+    }
+
+    fn load_versioned(
+        &self,
+        id: &A::ActorId,
+    ) -> BoxFuture<'_, Result<Option<(S, ETag)>, BoxedActorError>> {
+        let path = self.state_path::<A>(id);
+
+        Box::pin(async move { self.load_state::<A, S>(&path?).await })
+    }
+
+    fn save_versioned(
+        &self,
+        id: &A::ActorId,
+        state: &S,
+        expected: Option<ETag>,
+    ) -> BoxFuture<'_, Result<ETag, BoxedActorError>> {
+        let path = self.state_path::<A>(id);
+        let state_bytes = self.codecs.get::<A>().encode(state);
+
+        Box::pin(async move {
+            let path = path?;
+            let state_bytes =
+                state_bytes.map_err(FilePersistenceError::failed_to_serialize_state)?;
+
+            let lock = self.lock(&path);
+            let _guard = lock.lock().await;
+            let actual = Self::read_state(&path).await?.map(|(etag, _)| etag);
+            if actual != expected {
+                return Err(BoxedActorError::new(PersistenceError::InconsistentState {
+                    expected,
+                    actual,
+                }));
+            }
+
+            let etag = self.next_etag();
+            self.write_state(&path, etag, &state_bytes)
+                .await
+                .map_err(FilePersistenceError::io)?;
+            Ok(etag)
+        })
+    }
+
+    fn clear(&self, id: &A::ActorId) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let path = self.state_path::<A>(id);
+
+        Box::pin(async move {
+            let path = path?;
+
+            let lock = self.lock(&path);
+            let _guard = lock.lock().await;
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(FilePersistenceError::io(e)),
+            }
+        })
+    }
+
+    fn clear_versioned(
+        &self,
+        id: &A::ActorId,
+        expected: ETag,
+    ) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let path = self.state_path::<A>(id);
+
+        Box::pin(async move {
+            let path = path?;
+
+            let lock = self.lock(&path);
+            let _guard = lock.lock().await;
+            let actual = Self::read_etag(&path).await?;
+            if actual != Some(expected) {
+                return Err(BoxedActorError::new(PersistenceError::InconsistentState {
+                    expected: Some(expected),
+                    actual,
+                }));
+            }
+            tokio::fs::remove_file(&path)
+                .await
+                .map_err(FilePersistenceError::io)
+        })
+    }
+
+    fn expired(
+        &self,
+        saved_before: SystemTime,
+        limit: usize,
+    ) -> BoxFuture<'_, Result<VersionedIds<A::ActorId>, BoxedActorError>> {
+        let dir = self.root.join(state_namespace::<A>(self.slot).as_ref());
+        let key_encoding = self.preferences.key_encoding;
+
+        Box::pin(async move {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(FilePersistenceError::io(e)),
+            };
+
+            // time of last save is tracked by file modification time
+            let mut expired = Vec::new();
+            while expired.len() < limit {
+                let Some(entry) = entries
+                    .next_entry()
+                    .await
+                    .map_err(FilePersistenceError::io)?
+                else {
+                    break;
+                };
+                let path = entry.path();
+                if path.extension().is_none_or(|e| e != STATE_EXTENSION) {
+                    continue;
+                }
+                let modified = match entry.metadata().await.and_then(|m| m.modified()) {
+                    Ok(modified) => modified,
+                    Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                    Err(e) => return Err(FilePersistenceError::io(e)),
+                };
+                if modified >= saved_before {
+                    continue;
+                }
+                let id = path
+                    .file_stem()
+                    .and_then(|key| key.to_str())
+                    .and_then(|key| key_encoding.decode(key))
+                    .ok_or_else(|| {
+                        BoxedActorError::new(FilePersistenceError::DeserializeId(path.clone()))
+                    })?;
+                let Some(etag) = Self::read_etag(&path).await? else {
+                    continue;
+                };
+                expired.push((id, etag));
+            }
+            Ok(expired)
+        })
+    }
+
+    fn ids(&self) -> BoxFuture<'_, Result<Vec<A::ActorId>, BoxedActorError>> {
+        let dir = self.root.join(state_namespace::<A>(self.slot).as_ref());
+        let key_encoding = self.preferences.key_encoding;
+
+        Box::pin(async move {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(FilePersistenceError::io(e)),
+            };
+
+            let mut ids = Vec::new();
+            while let Some(entry) = entries
+                .next_entry()
+                .await
+                .map_err(FilePersistenceError::io)?
+            {
+                let path = entry.path();
+                if path.extension().is_none_or(|e| e != STATE_EXTENSION) {
+                    continue;
+                }
+                let id = path
+                    .file_stem()
+                    .and_then(|key| key.to_str())
+                    .and_then(|key| key_encoding.decode(key))
+                    .ok_or_else(|| {
+                        BoxedActorError::new(FilePersistenceError::DeserializeId(path.clone()))
+                    })?;
+                ids.push(id);
+            }
+            Ok(ids)
+        })
+    }
+
+    fn slot(&self, name: SlotName) -> Arc<dyn ActorPersistence<A, S>> {
+        Arc::new(Self {
+            slot: Some(name),
+            ..self.clone()
+        })
+    }
+
+    fn prepared_transactions(&self) -> Arc<dyn ActorPersistence<A, PreparedTransaction>> {
+        Arc::new(Self {
+            slot: Some(transaction_slot(self.slot)),
+            ..self.clone()
+        })
+    }
+}
@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use futures::future::BoxFuture;
+use virtual_actor_runtime::errors::BoxedActorError;
+
+use super::event_journal_trait::{EventJournal, JournalEntry, JournalError};
+use super::event_sourced_actor_trait::EventSourcedActor;
+
+/// Journal of single actor
+#[derive(Default)]
+struct ActorJournal {
+    /// Number of truncated events
+    truncated: u64,
+    /// Serialized events
+    events: Vec<Vec<u8>>,
+}
+
+impl ActorJournal {
+    fn last_sequence(&self) -> u64 {
+        self.truncated + self.events.len() as u64
+    }
+}
+
+type ActorJournalStorage = Arc<DashMap<Vec<u8>, ActorJournal>>;
+
+/// Inmemory event journal
+///
+/// Test purposes implementation of `EventJournal` trait
+pub struct InmemoryJournal {
+    storages: Arc<DashMap<String, ActorJournalStorage>>,
+}
+
+impl InmemoryJournal {
+    /// Create a new inmemory journal
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            storages: Arc::new(DashMap::new()),
+        }
+    }
+
+    fn storage<A: EventSourcedActor>(&self) -> ActorJournalStorage {
+        self.storages
+            .entry(A::name().to_string())
+            .or_insert_with(|| Arc::new(DashMap::new()))
+            .clone()
+    }
+}
+
+impl Default for InmemoryJournal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> EventJournal<A> for InmemoryJournal
+where
+    A: EventSourcedActor,
+{
+    fn append<'a>(
+        &'a self,
+        id: &A::ActorId,
+        expected_sequence: u64,
+        events: &[A::Event],
+    ) -> BoxFuture<'a, Result<u64, BoxedActorError>> {
+        let storage = self.storage::<A>();
+
+        let id_bytes = bincode::serialize(id);
+        let events_bytes = events
+            .iter()
+            .map(bincode::serialize)
+            .collect::<Result<Vec<_>, _>>();
+
+        Box::pin(async move {
+            let id_bytes = id_bytes.map_err(JournalError::failed_to_serialize_id)?;
+            let events_bytes = events_bytes.map_err(JournalError::failed_to_serialize_event)?;
+
+            let mut journal = storage.entry(id_bytes).or_default();
+            let actual = journal.last_sequence();
+            if actual != expected_sequence {
+                return Err(BoxedActorError::new(JournalError::SequenceMismatch {
+                    expected: expected_sequence,
+                    actual,
+                }));
+            }
+            journal.events.extend(events_bytes);
+            Ok(journal.last_sequence())
+        })
+    }
+
+    fn read(
+        &self,
+        id: &A::ActorId,
+        from_sequence: u64,
+    ) -> BoxFuture<'_, Result<Vec<JournalEntry<A::Event>>, BoxedActorError>> {
+        let storage = self.storage::<A>();
+
+        let id_bytes = bincode::serialize(id);
+
+        Box::pin(async move {
+            let id_bytes = id_bytes.map_err(JournalError::failed_to_serialize_id)?;
+            let Some(journal) = storage.get(&id_bytes) else {
+                return Ok(Vec::new());
+            };
+
+            (journal.truncated + 1..)
+                .zip(journal.events.iter())
+                .skip_while(|(sequence, _)| *sequence < from_sequence)
+                .map(|(sequence, bytes)| {
+                    bincode::deserialize::<A::Event>(bytes)
+                        .map(|event| JournalEntry { sequence, event })
+                        .map_err(JournalError::failed_to_deserialize_event)
+                })
+                .collect()
+        })
+    }
+
+    fn truncate(
+        &self,
+        id: &A::ActorId,
+        to_sequence: u64,
+    ) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let storage = self.storage::<A>();
+
+        let id_bytes = bincode::serialize(id);
+
+        Box::pin(async move {
+            let id_bytes = id_bytes.map_err(JournalError::failed_to_serialize_id)?;
+            if let Some(mut journal) = storage.get_mut(&id_bytes) {
+                let to_sequence = to_sequence.min(journal.last_sequence());
+                if to_sequence > journal.truncated {
+                    let count =
+                        usize::try_from(to_sequence - journal.truncated).unwrap_or(usize::MAX);
+                    journal.events.drain(..count);
+                    journal.truncated = to_sequence;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn clear(&self, id: &A::ActorId) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let storage = self.storage::<A>();
+
+        let id_bytes = bincode::serialize(id);
+
+        Box::pin(async move {
+            let id_bytes = id_bytes.map_err(JournalError::failed_to_serialize_id)?;
+            storage.remove(&id_bytes);
+            Ok(())
+        })
+    }
+}
@@ -1,11 +1,23 @@
-use std::sync::Arc;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::SystemTime,
+};
 
-use dashmap::DashMap;
+use dashmap::{mapref::entry::Entry, DashMap};
 use futures::future::BoxFuture;
-use virtual_actor_runtime::errors::BoxedActorError;
+use serde::{de::DeserializeOwned, Serialize};
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::VirtualActor};
 
-use super::actor_with_state_trait::ActorWithState;
-use super::actor_persistence_trait::ActorPersistence;
+use super::actor_persistence_trait::{
+    state_namespace, transaction_slot, ActorPersistence, ETag, PersistenceError, SlotName,
+    VersionedIds,
+};
+use super::state_codec::{BincodeCodec, CodecError, Codecs, StateCodec};
+use super::state_version::decode_state;
+use super::transaction::PreparedTransaction;
 
 /// Inmemory persistence error
 #[derive(Debug, thiserror::Error)]
@@ -13,6 +25,9 @@ pub enum InmemoryPersistenceError {
     /// Failed to serialize id
     #[error("Failed to serialize id: {0}")]
     SerializeId(String),
+    /// Failed to deserialize id
+    #[error("Failed to deserialize id: {0}")]
+    DeserializeId(String),
     /// Failed to serialize state
     #[error("Failed to serialize state: {0}")]
     SerializeState(String),
@@ -28,23 +43,37 @@ impl InmemoryPersistenceError {
     }
 
     #[allow(clippy::needless_pass_by_value)]
-    pub fn failed_to_serialize_state(e: bincode::Error) -> BoxedActorError {
+    pub fn failed_to_deserialize_id(e: bincode::Error) -> BoxedActorError {
+        BoxedActorError::new(Self::DeserializeId(e.to_string()))
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn failed_to_serialize_state(e: CodecError) -> BoxedActorError {
         BoxedActorError::new(Self::SerializeState(e.to_string()))
     }
 
     #[allow(clippy::needless_pass_by_value)]
-    pub fn failed_to_deserialize_state(e: bincode::Error) -> BoxedActorError {
+    pub fn failed_to_deserialize_state(e: CodecError) -> BoxedActorError {
         BoxedActorError::new(Self::DeserializeState(e.to_string()))
     }
 }
 
-type ActorStateStorage = Arc<DashMap<Vec<u8>, Vec<u8>>>;
+/// Serialized state with its version and time of save
+type VersionedBytes = (Vec<u8>, ETag, SystemTime);
+
+type ActorStateStorage = Arc<DashMap<Vec<u8>, VersionedBytes>>;
 
 /// Inmemory actor state persistence
 /// 
-/// Test purposes implementation of `ActorPersistence` trait
-pub struct InmemoryPersistence {
+/// Test purposes implementation of `ActorPersistence` trait.
+/// State is encoded with codec `C`, ids are always encoded with bincode.
+#[derive(Clone)]
+pub struct InmemoryPersistence<C: StateCodec = BincodeCodec> {
     storages: Arc<DashMap<String, ActorStateStorage>>,
+    /// Source of etags, unique across all states, so cleared and saved again state gets new etag
+    last_etag: Arc<AtomicU64>,
+    codecs: Codecs<C>,
+    slot: Option<SlotName>,
 }
 
 impl InmemoryPersistence {
@@ -53,8 +82,42 @@ impl InmemoryPersistence {
     pub fn new() -> Self {
         Self {
             storages: Arc::new(DashMap::new()),
+            last_etag: Arc::new(AtomicU64::new(0)),
+            codecs: Codecs::new(BincodeCodec),
+            slot: None,
+        }
+    }
+}
+
+impl<C: StateCodec> InmemoryPersistence<C> {
+    /// Replaces state codec, overrides for actor types are reset
+    #[must_use]
+    pub fn with_codec<C2: StateCodec>(self, codec: C2) -> InmemoryPersistence<C2> {
+        InmemoryPersistence {
+            storages: self.storages,
+            last_etag: self.last_etag,
+            codecs: Codecs::new(codec),
+            slot: self.slot,
         }
     }
+
+    /// Overrides state codec for actor type
+    #[must_use]
+    pub fn with_actor_codec<A: VirtualActor>(mut self, codec: C) -> Self {
+        self.codecs.set::<A>(codec);
+        self
+    }
+
+    fn storage<A: VirtualActor>(&self) -> ActorStateStorage {
+        self.storages
+            .entry(state_namespace::<A>(self.slot).into_owned())
+            .or_insert_with(|| Arc::new(DashMap::new()))
+            .clone()
+    }
+
+    fn next_etag(last_etag: &AtomicU64) -> ETag {
+        ETag(last_etag.fetch_add(1, Ordering::Relaxed) + 1)
+    }
 }
 
 impl Default for InmemoryPersistence {
@@ -63,25 +126,24 @@ impl Default for InmemoryPersistence {
     }
 }
 
-impl<A> ActorPersistence<A> for InmemoryPersistence
+impl<A, S, C> ActorPersistence<A, S> for InmemoryPersistence<C>
 where
-    A: ActorWithState,
+    A: VirtualActor,
+    S: Serialize + DeserializeOwned,
+    C: StateCodec,
 {
-    fn load(&self, id: &A::ActorId) -> BoxFuture<Result<Option<A::State>, BoxedActorError>> {
-        let storage = self
-            .storages
-            .entry(A::name().to_string())
-            .or_insert_with(|| Arc::new(DashMap::new()))
-            .clone();
+    fn load(&self, id: &A::ActorId) -> BoxFuture<'_, Result<Option<S>, BoxedActorError>> {
+        let storage = self.storage::<A>();
 
         let id_bytes = bincode::serialize(id);
+        let codec = self.codecs.get::<A>();
 
         Box::pin(async move {
             let id_bytes = id_bytes.map_err(InmemoryPersistenceError::failed_to_serialize_id)?;
             let opt = storage.get(&id_bytes);
 
             match opt {
-                Some(a) => match bincode::deserialize::<A::State>(&a) {
+                Some(a) => match decode_state::<_, S>(codec, &a.0) {
                     Ok(state) => Ok(Some(state)),
                     Err(e) => Err(InmemoryPersistenceError::failed_to_deserialize_state(e)),
                 },
@@ -90,31 +152,88 @@ where
         })
     }
 
-    fn save(&self, id: &A::ActorId, state: &A::State) -> BoxFuture<Result<(), BoxedActorError>> {
-        let storage = self
-            .storages
-            .entry(A::name().to_string())
-            .or_insert_with(|| Arc::new(DashMap::new()))
-            .clone();
+    fn save(&self, id: &A::ActorId, state: &S) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let storage = self.storage::<A>();
+        let last_etag = self.last_etag.clone();
 
         let id_bytes = bincode::serialize(id);
-        let state_bytes = bincode::serialize(state);
+        let state_bytes = self.codecs.get::<A>().encode(state);
 
         Box::pin(async move {
             let id_bytes = id_bytes.map_err(InmemoryPersistenceError::failed_to_serialize_id)?;
             let state_bytes =
                 state_bytes.map_err(InmemoryPersistenceError::failed_to_serialize_state)?;
-            storage.insert(id_bytes, state_bytes);
+            storage.insert(
+                id_bytes,
+                (state_bytes, Self::next_etag(&last_etag), SystemTime::now()),
+            );
             Ok(())
         })
     }
 
-    fn clear(&self, id: &A::ActorId) -> BoxFuture<Result<(), BoxedActorError>> {
-        let storage = self
-            .storages
-            .entry(A::name().to_string())
-            .or_insert_with(|| Arc::new(DashMap::new()))
-            .clone();
+    fn load_versioned(
+        &self,
+        id: &A::ActorId,
+    ) -> BoxFuture<'_, Result<Option<(S, ETag)>, BoxedActorError>> {
+        let storage = self.storage::<A>();
+
+        let id_bytes = bincode::serialize(id);
+        let codec = self.codecs.get::<A>();
+
+        Box::pin(async move {
+            let id_bytes = id_bytes.map_err(InmemoryPersistenceError::failed_to_serialize_id)?;
+            let opt = storage.get(&id_bytes);
+
+            match opt {
+                Some(a) => match decode_state::<_, S>(codec, &a.0) {
+                    Ok(state) => Ok(Some((state, a.1))),
+                    Err(e) => Err(InmemoryPersistenceError::failed_to_deserialize_state(e)),
+                },
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn save_versioned(
+        &self,
+        id: &A::ActorId,
+        state: &S,
+        expected: Option<ETag>,
+    ) -> BoxFuture<'_, Result<ETag, BoxedActorError>> {
+        let storage = self.storage::<A>();
+        let last_etag = self.last_etag.clone();
+
+        let id_bytes = bincode::serialize(id);
+        let state_bytes = self.codecs.get::<A>().encode(state);
+
+        Box::pin(async move {
+            let id_bytes = id_bytes.map_err(InmemoryPersistenceError::failed_to_serialize_id)?;
+            let state_bytes =
+                state_bytes.map_err(InmemoryPersistenceError::failed_to_serialize_state)?;
+
+            let actual = match storage.entry(id_bytes) {
+                Entry::Occupied(mut entry) if Some(entry.get().1) == expected => {
+                    let etag = Self::next_etag(&last_etag);
+                    entry.insert((state_bytes, etag, SystemTime::now()));
+                    return Ok(etag);
+                }
+                Entry::Vacant(entry) if expected.is_none() => {
+                    let etag = Self::next_etag(&last_etag);
+                    entry.insert((state_bytes, etag, SystemTime::now()));
+                    return Ok(etag);
+                }
+                Entry::Occupied(entry) => Some(entry.get().1),
+                Entry::Vacant(_) => None,
+            };
+            Err(BoxedActorError::new(PersistenceError::InconsistentState {
+                expected,
+                actual,
+            }))
+        })
+    }
+
+    fn clear(&self, id: &A::ActorId) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let storage = self.storage::<A>();
 
         let id_bytes = bincode::serialize(id);
 
@@ -124,4 +243,79 @@ where
             Ok(())
         })
     }
+
+    fn clear_versioned(
+        &self,
+        id: &A::ActorId,
+        expected: ETag,
+    ) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let storage = self.storage::<A>();
+
+        let id_bytes = bincode::serialize(id);
+
+        Box::pin(async move {
+            let id_bytes = id_bytes.map_err(InmemoryPersistenceError::failed_to_serialize_id)?;
+            let actual = match storage.entry(id_bytes) {
+                Entry::Occupied(entry) if entry.get().1 == expected => {
+                    entry.remove();
+                    return Ok(());
+                }
+                Entry::Occupied(entry) => Some(entry.get().1),
+                Entry::Vacant(_) => None,
+            };
+            Err(BoxedActorError::new(PersistenceError::InconsistentState {
+                expected: Some(expected),
+                actual,
+            }))
+        })
+    }
+
+    fn expired(
+        &self,
+        saved_before: SystemTime,
+        limit: usize,
+    ) -> BoxFuture<'_, Result<VersionedIds<A::ActorId>, BoxedActorError>> {
+        let storage = self.storage::<A>();
+
+        Box::pin(async move {
+            storage
+                .iter()
+                .filter(|entry| entry.value().2 < saved_before)
+                .take(limit)
+                .map(|entry| {
+                    bincode::deserialize(entry.key())
+                        .map(|id| (id, entry.value().1))
+                        .map_err(InmemoryPersistenceError::failed_to_deserialize_id)
+                })
+                .collect()
+        })
+    }
+
+    fn ids(&self) -> BoxFuture<'_, Result<Vec<A::ActorId>, BoxedActorError>> {
+        let storage = self.storage::<A>();
+
+        Box::pin(async move {
+            storage
+                .iter()
+                .map(|entry| {
+                    bincode::deserialize(entry.key())
+                        .map_err(InmemoryPersistenceError::failed_to_deserialize_id)
+                })
+                .collect()
+        })
+    }
+
+    fn slot(&self, name: SlotName) -> Arc<dyn ActorPersistence<A, S>> {
+        Arc::new(Self {
+            slot: Some(name),
+            ..self.clone()
+        })
+    }
+
+    fn prepared_transactions(&self) -> Arc<dyn ActorPersistence<A, PreparedTransaction>> {
+        Arc::new(Self {
+            slot: Some(transaction_slot(self.slot)),
+            ..self.clone()
+        })
+    }
 }
@@ -0,0 +1,76 @@
+use std::fmt::{Display, Write as _};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Encoding of actor id into file name
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyEncoding {
+    /// Hex encoded binary representation of id, suitable for any id
+    #[default]
+    Hex,
+    /// `Display` representation of id,
+    /// characters other than ASCII alphanumerics, `-` and `_` are percent encoded
+    Display,
+}
+
+impl KeyEncoding {
+    /// Encodes id into string usable as file name
+    pub(crate) fn encode<I>(self, id: &I) -> Result<String, bincode::Error>
+    where
+        I: Serialize + Display,
+    {
+        match self {
+            Self::Hex => {
+                let bytes = bincode::serialize(id)?;
+                Ok(bytes.iter().fold(String::new(), |mut key, byte| {
+                    let _ = write!(key, "{byte:02x}");
+                    key
+                }))
+            }
+            Self::Display => Ok(id.to_string().bytes().fold(String::new(), |mut key, byte| {
+                if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+                    key.push(char::from(byte));
+                } else {
+                    let _ = write!(key, "%{byte:02X}");
+                }
+                key
+            })),
+        }
+    }
+
+    /// Decodes id from file name, `None` if name was not produced by `encode`
+    ///
+    /// `Display` representation is parsed as JSON value or string,
+    /// so it is decodable for numeric and string ids only.
+    pub(crate) fn decode<I: DeserializeOwned>(self, key: &str) -> Option<I> {
+        match self {
+            Self::Hex => {
+                let bytes = (0..key.len())
+                    .step_by(2)
+                    .map(|i| {
+                        key.get(i..i + 2)
+                            .and_then(|b| u8::from_str_radix(b, 16).ok())
+                    })
+                    .collect::<Option<Vec<u8>>>()?;
+                bincode::deserialize(&bytes).ok()
+            }
+            Self::Display => {
+                let mut bytes = Vec::with_capacity(key.len());
+                let mut chars = key.bytes();
+                while let Some(byte) = chars.next() {
+                    if byte == b'%' {
+                        let hex = [chars.next()?, chars.next()?];
+                        let hex = std::str::from_utf8(&hex).ok()?;
+                        bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                    } else {
+                        bytes.push(byte);
+                    }
+                }
+                let display = String::from_utf8(bytes).ok()?;
+                serde_json::from_str(&display)
+                    .or_else(|_| serde_json::from_value(serde_json::Value::String(display)))
+                    .ok()
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use super::{LayerError, StateLayer};
+
+/// Tag of stored bytes
+const RAW: u8 = 0;
+const ZSTD: u8 = 1;
+const LZ4: u8 = 2;
+
+/// Compression algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionAlgorithm {
+    /// Zstandard with compression level
+    Zstd {
+        /// Compression level, 1 to 22
+        level: i32,
+    },
+    /// LZ4, faster with lower ratio
+    Lz4,
+}
+
+impl Default for CompressionAlgorithm {
+    fn default() -> Self {
+        Self::Zstd { level: 3 }
+    }
+}
+
+/// Compresses states larger than threshold
+///
+/// Algorithm is stored with state, so states written with another algorithm remain readable.
+#[derive(Debug, Clone, Copy)]
+pub struct Compression {
+    algorithm: CompressionAlgorithm,
+    threshold: usize,
+}
+
+impl Compression {
+    /// Create a new compression layer, states smaller than `threshold` bytes are stored as is
+    #[must_use]
+    pub fn new(algorithm: CompressionAlgorithm, threshold: usize) -> Self {
+        Self {
+            algorithm,
+            threshold,
+        }
+    }
+}
+
+impl StateLayer for Compression {
+    fn seal(&self, _state_key: &[u8], bytes: Vec<u8>) -> Result<Vec<u8>, LayerError> {
+        if bytes.len() < self.threshold {
+            let mut sealed = Vec::with_capacity(bytes.len() + 1);
+            sealed.push(RAW);
+            sealed.extend_from_slice(&bytes);
+            return Ok(sealed);
+        }
+        let (tag, compressed) = match self.algorithm {
+            CompressionAlgorithm::Zstd { level } => (
+                ZSTD,
+                zstd::bulk::compress(&bytes, level)
+                    .map_err(|e| LayerError::Compression(e.to_string()))?,
+            ),
+            CompressionAlgorithm::Lz4 => (LZ4, lz4_flex::compress_prepend_size(&bytes)),
+        };
+        let mut sealed = Vec::with_capacity(compressed.len() + 1);
+        sealed.push(tag);
+        sealed.extend_from_slice(&compressed);
+        Ok(sealed)
+    }
+
+    fn open(&self, _state_key: &[u8], bytes: Vec<u8>) -> Result<Vec<u8>, LayerError> {
+        let Some((&tag, payload)) = bytes.split_first() else {
+            return Err(LayerError::Decompression("empty state".to_string()));
+        };
+        match tag {
+            RAW => Ok(payload.to_vec()),
+            ZSTD => zstd::stream::decode_all(payload)
+                .map_err(|e| LayerError::Decompression(e.to_string())),
+            LZ4 => lz4_flex::decompress_size_prepended(payload)
+                .map_err(|e| LayerError::Decompression(e.to_string())),
+            tag => Err(LayerError::UnknownFormat(tag)),
+        }
+    }
+}
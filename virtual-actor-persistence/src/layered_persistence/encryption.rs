@@ -0,0 +1,184 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+
+use super::{LayerError, StateLayer};
+
+/// Version of encrypted state format
+const FORMAT_VERSION: u8 = 1;
+/// Format version followed by key id
+const HEADER_SIZE: usize = 5;
+const NONCE_SIZE: usize = 24;
+
+/// Identifier of encryption key, stored with encrypted state
+pub type KeyId = u32;
+
+/// 256-bit encryption key
+#[derive(Clone)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    /// Create key from bytes
+    #[must_use]
+    pub fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Generate random key
+    #[must_use]
+    pub fn generate() -> Self {
+        Self(XChaCha20Poly1305::generate_key(&mut OsRng).into())
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+/// Source of encryption keys
+pub trait KeyProvider: Send + Sync + 'static {
+    /// Key used to encrypt saved states
+    ///
+    /// # Errors
+    ///
+    /// Returns error if key is not available
+    fn current_key(&self) -> Result<(KeyId, EncryptionKey), LayerError>;
+
+    /// Key with given id, used to decrypt states encrypted before key rotation
+    ///
+    /// # Errors
+    ///
+    /// Returns `LayerError::UnknownKey` if key is not known
+    fn key(&self, id: KeyId) -> Result<EncryptionKey, LayerError>;
+}
+
+/// Key provider with keys kept in memory
+///
+/// Rotated keys are kept to decrypt states, which are re-encrypted with current key on next save.
+pub struct StaticKeys {
+    keys: RwLock<(KeyId, HashMap<KeyId, EncryptionKey>)>,
+}
+
+impl StaticKeys {
+    /// Create provider with current key
+    #[must_use]
+    pub fn new(id: KeyId, key: EncryptionKey) -> Self {
+        Self {
+            keys: RwLock::new((id, HashMap::from([(id, key)]))),
+        }
+    }
+
+    /// Adds key and makes it current
+    pub fn rotate(&self, id: KeyId, key: EncryptionKey) {
+        let mut keys = self.keys.write().unwrap_or_else(PoisonError::into_inner);
+        keys.0 = id;
+        keys.1.insert(id, key);
+    }
+
+    /// Removes key, states encrypted with it can not be decrypted anymore
+    pub fn retire(&self, id: KeyId) {
+        let mut keys = self.keys.write().unwrap_or_else(PoisonError::into_inner);
+        if keys.0 != id {
+            keys.1.remove(&id);
+        }
+    }
+}
+
+impl KeyProvider for StaticKeys {
+    fn current_key(&self) -> Result<(KeyId, EncryptionKey), LayerError> {
+        let keys = self.keys.read().unwrap_or_else(PoisonError::into_inner);
+        let id = keys.0;
+        keys.1
+            .get(&id)
+            .map(|key| (id, key.clone()))
+            .ok_or(LayerError::UnknownKey(id))
+    }
+
+    fn key(&self, id: KeyId) -> Result<EncryptionKey, LayerError> {
+        let keys = self.keys.read().unwrap_or_else(PoisonError::into_inner);
+        keys.1.get(&id).cloned().ok_or(LayerError::UnknownKey(id))
+    }
+}
+
+/// Authenticated encryption of state with XChaCha20-Poly1305
+///
+/// Encrypted state is bound to its storage key, so it can not be moved to another actor or slot.
+pub struct Encryption<K: KeyProvider> {
+    keys: Arc<K>,
+}
+
+impl<K: KeyProvider> Encryption<K> {
+    /// Create a new encryption layer, keys can be rotated through shared provider
+    #[must_use]
+    pub fn new(keys: Arc<K>) -> Self {
+        Self { keys }
+    }
+}
+
+/// Associated data authenticated with encrypted state
+fn associated_data(header: &[u8], state_key: &[u8]) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(header.len() + state_key.len());
+    aad.extend_from_slice(header);
+    aad.extend_from_slice(state_key);
+    aad
+}
+
+impl<K: KeyProvider> StateLayer for Encryption<K> {
+    fn seal(&self, state_key: &[u8], bytes: Vec<u8>) -> Result<Vec<u8>, LayerError> {
+        let (key_id, key) = self.keys.current_key()?;
+        let cipher = XChaCha20Poly1305::new(&key.0.into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let mut header = [FORMAT_VERSION; HEADER_SIZE];
+        header[1..].copy_from_slice(&key_id.to_le_bytes());
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &bytes,
+                    aad: &associated_data(&header, state_key),
+                },
+            )
+            .map_err(|e| LayerError::Encryption(e.to_string()))?;
+
+        let mut sealed = Vec::with_capacity(HEADER_SIZE + NONCE_SIZE + ciphertext.len());
+        sealed.extend_from_slice(&header);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    fn open(&self, state_key: &[u8], bytes: Vec<u8>) -> Result<Vec<u8>, LayerError> {
+        let Some((header, rest)) = bytes.split_first_chunk::<HEADER_SIZE>() else {
+            return Err(LayerError::Decryption("truncated state".to_string()));
+        };
+        let [version, key_id @ ..] = *header;
+        if version != FORMAT_VERSION {
+            return Err(LayerError::UnknownFormat(version));
+        }
+        let Some((nonce, ciphertext)) = rest.split_first_chunk::<NONCE_SIZE>() else {
+            return Err(LayerError::Decryption("truncated state".to_string()));
+        };
+
+        let key = self.keys.key(KeyId::from_le_bytes(key_id))?;
+        let cipher = XChaCha20Poly1305::new(&key.0.into());
+        cipher
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &associated_data(header, state_key),
+                },
+            )
+            .map_err(|e| LayerError::Decryption(e.to_string()))
+    }
+}
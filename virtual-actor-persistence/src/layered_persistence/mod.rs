@@ -0,0 +1,252 @@
+//! Persistence middleware transforming serialized state
+
+mod compression;
+mod encryption;
+
+use std::{sync::Arc, time::SystemTime};
+
+use futures::future::BoxFuture;
+use serde::{de::DeserializeOwned, Serialize};
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::VirtualActor};
+
+use super::actor_persistence_trait::{
+    state_namespace, transaction_slot, ActorPersistence, ETag, SlotName, VersionedIds,
+};
+use super::state_codec::{BincodeCodec, StateCodec};
+use super::state_version::decode_state;
+use super::transaction::PreparedTransaction;
+
+pub use compression::{Compression, CompressionAlgorithm};
+pub use encryption::{Encryption, EncryptionKey, KeyId, KeyProvider, StaticKeys};
+
+/// Layered persistence error
+#[derive(Debug, thiserror::Error)]
+pub enum LayerError {
+    /// Failed to serialize id
+    #[error("Failed to serialize id: {0}")]
+    SerializeId(String),
+    /// Failed to serialize state
+    #[error("Failed to serialize state: {0}")]
+    SerializeState(String),
+    /// Failed to deserialize state
+    #[error("Failed to deserialize state: {0}")]
+    DeserializeState(String),
+    /// Failed to compress state
+    #[error("Failed to compress state: {0}")]
+    Compression(String),
+    /// Failed to decompress state
+    #[error("Failed to decompress state: {0}")]
+    Decompression(String),
+    /// Failed to encrypt state
+    #[error("Failed to encrypt state: {0}")]
+    Encryption(String),
+    /// State can not be decrypted or it was tampered with
+    #[error("Failed to decrypt state: {0}")]
+    Decryption(String),
+    /// Key is not known to key provider
+    #[error("Unknown encryption key {0}")]
+    UnknownKey(KeyId),
+    /// Stored bytes were not produced by layer
+    #[error("Unknown format of stored state: {0}")]
+    UnknownFormat(u8),
+}
+
+/// Transformation of serialized state, e.g. compression or encryption
+pub trait StateLayer: Send + Sync + 'static {
+    /// Transforms state before it is stored,
+    /// `state_key` identifies stored state and can be used to bind output to it
+    ///
+    /// # Errors
+    ///
+    /// Returns error if state can not be transformed
+    fn seal(&self, state_key: &[u8], bytes: Vec<u8>) -> Result<Vec<u8>, LayerError>;
+
+    /// Restores state transformed by `seal`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if bytes were not produced by `seal` with the same `state_key`
+    fn open(&self, state_key: &[u8], bytes: Vec<u8>) -> Result<Vec<u8>, LayerError>;
+}
+
+/// Persistence middleware, stores serialized state transformed by layers in inner persistence
+///
+/// Layers are applied to saved state in order they are added and reversed on load,
+/// so compression has to be added before encryption.
+/// Inner persistence stores bytes, any storage implementing `ActorPersistence` for all state types
+/// or another `LayeredPersistence` can be used.
+pub struct LayeredPersistence<A: VirtualActor, C: StateCodec = BincodeCodec> {
+    inner: Arc<dyn ActorPersistence<A, Vec<u8>>>,
+    layers: Vec<Arc<dyn StateLayer>>,
+    codec: C,
+    slot: Option<SlotName>,
+}
+
+impl<A: VirtualActor> LayeredPersistence<A> {
+    /// Create a new layered persistence without layers
+    #[must_use]
+    pub fn new(inner: Arc<dyn ActorPersistence<A, Vec<u8>>>) -> Self {
+        Self {
+            inner,
+            layers: Vec::new(),
+            codec: BincodeCodec,
+            slot: None,
+        }
+    }
+}
+
+impl<A: VirtualActor, C: StateCodec> LayeredPersistence<A, C> {
+    /// Replaces codec used to serialize state before it is passed to layers
+    #[must_use]
+    pub fn with_codec<C2: StateCodec>(self, codec: C2) -> LayeredPersistence<A, C2> {
+        LayeredPersistence {
+            inner: self.inner,
+            layers: self.layers,
+            codec,
+            slot: self.slot,
+        }
+    }
+
+    /// Adds layer applied after already added ones
+    #[must_use]
+    pub fn with_layer(mut self, layer: impl StateLayer) -> Self {
+        self.layers.push(Arc::new(layer));
+        self
+    }
+
+    /// Identifies stored state by actor type, slot and id
+    fn state_key(&self, id: &A::ActorId) -> Result<Vec<u8>, BoxedActorError> {
+        let mut key = state_namespace::<A>(self.slot).into_owned().into_bytes();
+        key.push(0);
+        bincode::serialize_into(&mut key, id)
+            .map_err(|e| BoxedActorError::new(LayerError::SerializeId(e.to_string())))?;
+        Ok(key)
+    }
+
+    fn seal<S: Serialize>(&self, id: &A::ActorId, state: &S) -> Result<Vec<u8>, BoxedActorError> {
+        let state_key = self.state_key(id)?;
+        let bytes = self
+            .codec
+            .encode(state)
+            .map_err(|e| BoxedActorError::new(LayerError::SerializeState(e.to_string())))?;
+        self.layers
+            .iter()
+            .try_fold(bytes, |bytes, layer| layer.seal(&state_key, bytes))
+            .map_err(BoxedActorError::new)
+    }
+
+    fn open<S: DeserializeOwned>(
+        &self,
+        state_key: &[u8],
+        bytes: Vec<u8>,
+    ) -> Result<S, BoxedActorError> {
+        let bytes = self
+            .layers
+            .iter()
+            .rev()
+            .try_fold(bytes, |bytes, layer| layer.open(state_key, bytes))
+            .map_err(BoxedActorError::new)?;
+        decode_state(&self.codec, &bytes)
+            .map_err(|e| BoxedActorError::new(LayerError::DeserializeState(e.to_string())))
+    }
+}
+
+impl<A, S, C> ActorPersistence<A, S> for LayeredPersistence<A, C>
+where
+    A: VirtualActor,
+    S: Serialize + DeserializeOwned,
+    C: StateCodec,
+{
+    fn load(&self, id: &A::ActorId) -> BoxFuture<'_, Result<Option<S>, BoxedActorError>> {
+        let state_key = self.state_key(id);
+        let loaded = self.inner.load(id);
+
+        Box::pin(async move {
+            let state_key = state_key?;
+            match loaded.await? {
+                Some(bytes) => self.open(&state_key, bytes).map(Some),
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn save(&self, id: &A::ActorId, state: &S) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        match self.seal(id, state) {
+            Ok(bytes) => self.inner.save(id, &bytes),
+            Err(e) => Box::pin(futures::future::ready(Err(e))),
+        }
+    }
+
+    fn load_versioned(
+        &self,
+        id: &A::ActorId,
+    ) -> BoxFuture<'_, Result<Option<(S, ETag)>, BoxedActorError>> {
+        let state_key = self.state_key(id);
+        let loaded = self.inner.load_versioned(id);
+
+        Box::pin(async move {
+            let state_key = state_key?;
+            match loaded.await? {
+                Some((bytes, etag)) => self
+                    .open(&state_key, bytes)
+                    .map(|state| Some((state, etag))),
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn save_versioned(
+        &self,
+        id: &A::ActorId,
+        state: &S,
+        expected: Option<ETag>,
+    ) -> BoxFuture<'_, Result<ETag, BoxedActorError>> {
+        match self.seal(id, state) {
+            Ok(bytes) => self.inner.save_versioned(id, &bytes, expected),
+            Err(e) => Box::pin(futures::future::ready(Err(e))),
+        }
+    }
+
+    fn clear(&self, id: &A::ActorId) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        self.inner.clear(id)
+    }
+
+    fn clear_versioned(
+        &self,
+        id: &A::ActorId,
+        expected: ETag,
+    ) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        self.inner.clear_versioned(id, expected)
+    }
+
+    fn expired(
+        &self,
+        saved_before: SystemTime,
+        limit: usize,
+    ) -> BoxFuture<'_, Result<VersionedIds<A::ActorId>, BoxedActorError>> {
+        self.inner.expired(saved_before, limit)
+    }
+
+    fn ids(&self) -> BoxFuture<'_, Result<Vec<A::ActorId>, BoxedActorError>> {
+        self.inner.ids()
+    }
+
+    fn slot(&self, name: SlotName) -> Arc<dyn ActorPersistence<A, S>> {
+        Arc::new(Self {
+            inner: self.inner.slot(name),
+            layers: self.layers.clone(),
+            codec: self.codec.clone(),
+            slot: Some(name),
+        })
+    }
+
+    fn prepared_transactions(&self) -> Arc<dyn ActorPersistence<A, PreparedTransaction>> {
+        let name = transaction_slot(self.slot);
+        Arc::new(Self {
+            inner: self.inner.slot(name),
+            layers: self.layers.clone(),
+            codec: self.codec.clone(),
+            slot: Some(name),
+        })
+    }
+}
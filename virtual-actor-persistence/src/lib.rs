@@ -3,14 +3,76 @@
 mod actor_state;
 mod actor_with_state_trait;
 mod actor_persistence_trait;
+mod event_journal_trait;
+mod event_sourced_actor_factory;
+mod event_sourced_actor_trait;
+mod event_sourced_state;
+mod file_journal;
+mod file_persistence;
+mod inmemory_journal;
 mod inmemory_persistence;
+mod key_encoding;
+mod layered_persistence;
+mod persistent_actor_constructor_trait;
+mod persistent_actor_factory;
+mod save_policy;
+mod snapshot;
+mod sqlite_persistence;
+mod state_codec;
+mod state_retention;
+mod state_sweeper;
+mod state_version;
+mod transaction;
+mod workflow;
 
 pub mod prelude {
     //! Virtual actor persistence prelude
     
     pub use super::actor_state::ActorState;
     pub use super::actor_with_state_trait::ActorWithState;
-    pub use super::actor_persistence_trait::ActorPersistence;
+    pub use super::actor_persistence_trait::{ActorPersistence, ETag};
+    pub use super::event_journal_trait::{EventJournal, JournalEntry};
+    pub use super::event_sourced_actor_factory::EventSourcedActorFactory;
+    pub use super::event_sourced_actor_trait::EventSourcedActor;
+    pub use super::event_sourced_state::EventSourcedState;
+    pub use super::persistent_actor_constructor_trait::PersistentActorConstructor;
+    pub use super::persistent_actor_factory::PersistentActorFactory;
+    pub use super::save_policy::SavePolicy;
+    pub use super::snapshot::{Snapshot, SnapshotPolicy};
+    pub use super::state_codec::StateCodec;
+    pub use super::state_version::{Migrate, StateVersion, Versioned};
+    pub use virtual_actor_derive::ActorWithState;
 }
 
-pub use inmemory_persistence::InmemoryPersistence;
\ No newline at end of file
+pub use actor_persistence_trait::{PersistenceError, SlotName, VersionedIds};
+pub use event_journal_trait::JournalError;
+pub use file_journal::FileJournal;
+pub use file_persistence::{
+    FilePersistence, FilePersistenceError, FilePersistencePreferences, FsyncPolicy,
+};
+pub use inmemory_journal::InmemoryJournal;
+pub use inmemory_persistence::InmemoryPersistence;
+pub use key_encoding::KeyEncoding;
+pub use layered_persistence::{
+    Compression, CompressionAlgorithm, Encryption, EncryptionKey, KeyId, KeyProvider, LayerError,
+    LayeredPersistence, StateLayer, StaticKeys,
+};
+pub use sqlite_persistence::{
+    SqlitePersistence, SqlitePersistenceError, SqlitePersistencePreferences, TableLayout,
+};
+pub use state_codec::{
+    BincodeCodec, CborCodec, CodecError, JsonCodec, MessagePackCodec, StateFormat,
+};
+pub use state_retention::StateRetention;
+pub use state_sweeper::{StateSweeper, StateSweeperError, StateSweeperFactory, Sweep};
+pub use state_version::{InitialVersion, MigrationPolicy};
+pub use transaction::{
+    resolve_transactions, AbortTransaction, CommitTransaction, FileTransactionLog,
+    InmemoryTransactionLog, PrepareTransaction, PreparedTransaction, Transaction,
+    TransactionCoordinator, TransactionDecision, TransactionError, TransactionId, TransactionLog,
+    TransactionScope,
+};
+pub use workflow::{
+    resume_workflows, ResumeWorkflow, StepEvent, StepOutcome, StepRetryPolicy, Workflow,
+    WorkflowError, WorkflowLog, WorkflowState, WorkflowStatus,
+};
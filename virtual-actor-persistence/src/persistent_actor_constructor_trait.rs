@@ -0,0 +1,8 @@
+use super::actor_state::ActorState;
+use super::actor_with_state_trait::ActorWithState;
+
+/// Constructor of actor with loaded state, used by `PersistentActorFactory`
+pub trait PersistentActorConstructor: ActorWithState {
+    /// Creates actor with given id and state
+    fn new(id: &Self::ActorId, state: ActorState<Self>) -> Self;
+}
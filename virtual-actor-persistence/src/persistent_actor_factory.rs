@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::*};
+
+use super::actor_persistence_trait::ActorPersistence;
+use super::actor_state::ActorState;
+use super::persistent_actor_constructor_trait::PersistentActorConstructor;
+use super::save_policy::SavePolicy;
+
+/// Factory of actors with state, loads state on activation and flushes unsaved changes on deactivation
+pub struct PersistentActorFactory<A>
+where
+    A: PersistentActorConstructor,
+{
+    persistence: Arc<dyn ActorPersistence<A>>,
+    save_policy: SavePolicy,
+    transactional: bool,
+}
+
+impl<A> PersistentActorFactory<A>
+where
+    A: PersistentActorConstructor,
+{
+    /// Create a new factory
+    #[must_use]
+    pub fn new(persistence: Arc<dyn ActorPersistence<A>>) -> Self {
+        Self {
+            persistence,
+            save_policy: SavePolicy::default(),
+            transactional: false,
+        }
+    }
+
+    /// Sets save policy of loaded states
+    #[must_use]
+    pub fn with_save_policy(mut self, save_policy: SavePolicy) -> Self {
+        self.save_policy = save_policy;
+        self
+    }
+
+    /// Loads states with `ActorState::load_transactional`, so they can be enlisted in transactions
+    #[must_use]
+    pub fn with_transactions(mut self) -> Self {
+        self.transactional = true;
+        self
+    }
+}
+
+impl<A> ActorFactory for PersistentActorFactory<A>
+where
+    A: PersistentActorConstructor,
+{
+    type Actor = A;
+}
+
+impl<A> VirtualActorFactory for PersistentActorFactory<A>
+where
+    A: PersistentActorConstructor,
+{
+    type Error = BoxedActorError;
+
+    async fn create_actor(&self, id: &A::ActorId) -> Result<A, Self::Error> {
+        let state = if self.transactional {
+            ActorState::load_transactional(&self.persistence, id).await?
+        } else {
+            ActorState::load(&self.persistence, id).await?
+        };
+        let state = state.with_save_policy(self.save_policy);
+        Ok(A::new(id, state))
+    }
+
+    async fn deactivate_actor(&self, actor: &mut A) -> Result<(), Self::Error> {
+        actor.state_mut().flush().await
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::{Duration, Instant};
+
+/// Defines when `ActorState::after_message` saves changed state
+///
+/// Changes are tracked through mutable access to state,
+/// idle actor is woken up to save changes when their deadline is reached,
+/// unsaved changes are written by `ActorState::flush` when actor is deactivated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SavePolicy {
+    /// State is saved only by explicit `ActorState::save` calls
+    #[default]
+    Manual,
+    /// Changed state is saved after every message
+    EveryMessage,
+    /// Changed state is saved after message when given time is passed since last save
+    Debounce(Duration),
+    /// Changes of several messages are written in one save
+    WriteBehind {
+        /// Save when oldest unsaved change is older than this delay
+        max_delay: Duration,
+        /// Save when given number of messages changed state
+        max_messages: usize,
+    },
+}
+
+/// Unsaved changes of state
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PendingChanges {
+    /// Time of first unsaved change
+    pub since: Instant,
+    /// Number of messages which changed state
+    pub messages: usize,
+}
+
+impl SavePolicy {
+    /// Checks if pending changes should be saved now
+    pub(crate) fn should_save(self, pending: &PendingChanges, last_save: Option<Instant>) -> bool {
+        match self {
+            Self::Manual => false,
+            Self::EveryMessage => true,
+            Self::Debounce(interval) => last_save.is_none_or(|t| t.elapsed() >= interval),
+            Self::WriteBehind {
+                max_delay,
+                max_messages,
+            } => pending.since.elapsed() >= max_delay || pending.messages >= max_messages,
+        }
+    }
+
+    /// Time when pending changes which are not saved yet have to be saved
+    pub(crate) fn deadline(
+        self,
+        pending: &PendingChanges,
+        last_save: Option<Instant>,
+    ) -> Option<Instant> {
+        match self {
+            Self::Manual | Self::EveryMessage => None,
+            Self::Debounce(interval) => last_save.map(|t| t + interval),
+            Self::WriteBehind { max_delay, .. } => Some(pending.since + max_delay),
+        }
+    }
+}
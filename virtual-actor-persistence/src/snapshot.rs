@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// State of event sourced actor with sequence number of last applied event
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot<S> {
+    /// Sequence number of last applied event
+    pub sequence: u64,
+    /// State
+    pub state: S,
+}
+
+/// Defines when snapshot of event sourced actor is taken
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotPolicy {
+    /// Take snapshot when given number of events is persisted since last snapshot
+    pub every_events: Option<u64>,
+    /// Take snapshot when given time is passed since last snapshot,
+    /// checked when events are persisted
+    pub every_interval: Option<Duration>,
+    /// Take snapshot when actor is deactivated
+    pub on_deactivation: bool,
+    /// Remove events covered by snapshot from journal
+    pub truncate_journal: bool,
+}
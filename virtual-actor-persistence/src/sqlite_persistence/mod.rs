@@ -0,0 +1,453 @@
+//! Embedded sqlite persistence
+
+mod worker;
+
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures::future::BoxFuture;
+use rusqlite::Connection;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::oneshot;
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::VirtualActor};
+
+use super::actor_persistence_trait::{
+    state_namespace, transaction_slot, ActorPersistence, ETag, SlotName, VersionedIds,
+};
+use super::state_codec::{BincodeCodec, CodecError, Codecs, StateCodec};
+use super::state_version::decode_state;
+use super::transaction::PreparedTransaction;
+use worker::{Command, Expected, Worker};
+
+/// Sqlite persistence error
+#[derive(Debug, thiserror::Error)]
+pub enum SqlitePersistenceError {
+    /// Failed to serialize id
+    #[error("Failed to serialize id: {0}")]
+    SerializeId(String),
+    /// Failed to deserialize id
+    #[error("Failed to deserialize id: {0}")]
+    DeserializeId(String),
+    /// Failed to serialize state
+    #[error("Failed to serialize state: {0}")]
+    SerializeState(String),
+    /// Failed to deserialize state
+    #[error("Failed to deserialize state: {0}")]
+    DeserializeState(String),
+    /// Database error
+    #[error("SQLite error: {0}")]
+    Sqlite(String),
+    /// Worker thread is stopped
+    #[error("SQLite worker is stopped")]
+    WorkerStopped,
+    /// Unable to start worker thread
+    #[error("Unable to spawn SQLite worker thread {0:?}")]
+    ThreadSpawnError(std::io::Error),
+}
+
+impl SqlitePersistenceError {
+    #[allow(clippy::needless_pass_by_value)]
+    fn failed_to_serialize_id(e: bincode::Error) -> BoxedActorError {
+        BoxedActorError::new(Self::SerializeId(e.to_string()))
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn failed_to_deserialize_id(e: bincode::Error) -> BoxedActorError {
+        BoxedActorError::new(Self::DeserializeId(e.to_string()))
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn failed_to_serialize_state(e: CodecError) -> BoxedActorError {
+        BoxedActorError::new(Self::SerializeState(e.to_string()))
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn failed_to_deserialize_state(e: CodecError) -> BoxedActorError {
+        BoxedActorError::new(Self::DeserializeState(e.to_string()))
+    }
+
+    fn sqlite(e: impl std::borrow::Borrow<rusqlite::Error>) -> BoxedActorError {
+        BoxedActorError::new(Self::Sqlite(e.borrow().to_string()))
+    }
+
+    fn worker_stopped<E>(_: E) -> BoxedActorError {
+        BoxedActorError::new(Self::WorkerStopped)
+    }
+}
+
+impl From<rusqlite::Error> for SqlitePersistenceError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::Sqlite(e.to_string())
+    }
+}
+
+/// Layout of actor state tables
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TableLayout {
+    /// Single `actor_state` table keyed by actor name with optional slot name and serialized id
+    #[default]
+    Shared,
+    /// Table `actor_state_<actor name>` per actor type keyed by serialized id,
+    /// named state slots use `actor_state_<actor name>.<slot>` tables
+    PerActorType,
+}
+
+/// Sqlite persistence preferences
+#[derive(Debug, Clone)]
+pub struct SqlitePersistencePreferences {
+    /// Layout of actor state tables
+    pub table_layout: TableLayout,
+    /// Use write-ahead log, allows reading database while it is written
+    pub wal: bool,
+    /// Maximal number of queued operations executed in one transaction
+    pub max_batch_size: usize,
+}
+
+impl Default for SqlitePersistencePreferences {
+    fn default() -> Self {
+        Self {
+            table_layout: TableLayout::default(),
+            wal: true,
+            max_batch_size: 128,
+        }
+    }
+}
+
+/// Embedded sqlite actor state persistence
+///
+/// Database is owned by dedicated thread,
+/// operations queued while previous batch is executed are committed in one transaction.
+/// States are encoded with codec `C`.
+#[derive(Clone)]
+pub struct SqlitePersistence<C: StateCodec = BincodeCodec> {
+    commands: mpsc::Sender<Command>,
+    /// Source of etags, initialized from current time, so etags are not reused after restart
+    last_etag: Arc<AtomicU64>,
+    codecs: Codecs<C>,
+    slot: Option<SlotName>,
+}
+
+impl SqlitePersistence {
+    /// Opens database file, creates it if it does not exist
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database can not be opened or worker thread can not be started
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SqlitePersistenceError> {
+        Self::open_with_preferences(path, &SqlitePersistencePreferences::default())
+    }
+
+    /// Opens database file with preferences
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database can not be opened or worker thread can not be started
+    pub fn open_with_preferences(
+        path: impl AsRef<Path>,
+        preferences: &SqlitePersistencePreferences,
+    ) -> Result<Self, SqlitePersistenceError> {
+        Self::start(Connection::open(path)?, preferences)
+    }
+
+    /// Opens private in-memory database
+    ///
+    /// # Errors
+    ///
+    /// Returns error if database can not be opened or worker thread can not be started
+    pub fn open_in_memory() -> Result<Self, SqlitePersistenceError> {
+        Self::start(
+            Connection::open_in_memory()?,
+            &SqlitePersistencePreferences::default(),
+        )
+    }
+
+    fn start(
+        connection: Connection,
+        preferences: &SqlitePersistencePreferences,
+    ) -> Result<Self, SqlitePersistenceError> {
+        if preferences.wal {
+            connection.pragma_update(None, "journal_mode", "WAL")?;
+            connection.pragma_update(None, "synchronous", "NORMAL")?;
+        }
+
+        let worker = Worker::new(
+            connection,
+            preferences.table_layout,
+            preferences.max_batch_size,
+        );
+        let (commands, receiver) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("sqlite-persistence".to_string())
+            .spawn(move || worker.run(&receiver))
+            .map_err(SqlitePersistenceError::ThreadSpawnError)?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX));
+        Ok(Self {
+            commands,
+            last_etag: Arc::new(AtomicU64::new(now)),
+            codecs: Codecs::new(BincodeCodec),
+            slot: None,
+        })
+    }
+}
+
+impl<C: StateCodec> SqlitePersistence<C> {
+    /// Replaces state codec, overrides for actor types are reset
+    #[must_use]
+    pub fn with_codec<C2: StateCodec>(self, codec: C2) -> SqlitePersistence<C2> {
+        SqlitePersistence {
+            commands: self.commands,
+            last_etag: self.last_etag,
+            codecs: Codecs::new(codec),
+            slot: self.slot,
+        }
+    }
+
+    /// Overrides state codec for actor type
+    #[must_use]
+    pub fn with_actor_codec<A: VirtualActor>(mut self, codec: C) -> Self {
+        self.codecs.set::<A>(codec);
+        self
+    }
+
+    fn next_etag(&self) -> ETag {
+        ETag(self.last_etag.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn load_bytes<A: VirtualActor>(
+        &self,
+        id: &A::ActorId,
+    ) -> impl std::future::Future<Output = Result<Option<(Vec<u8>, ETag)>, BoxedActorError>> {
+        let id_bytes = bincode::serialize(id);
+        let commands = self.commands.clone();
+        let actor_name = state_namespace::<A>(self.slot);
+
+        async move {
+            let id = id_bytes.map_err(SqlitePersistenceError::failed_to_serialize_id)?;
+            let (reply, response) = oneshot::channel();
+            commands
+                .send(Command::Load {
+                    actor_name,
+                    id,
+                    reply,
+                })
+                .map_err(SqlitePersistenceError::worker_stopped)?;
+            response
+                .await
+                .map_err(SqlitePersistenceError::worker_stopped)?
+        }
+    }
+
+    fn save_bytes<A: VirtualActor>(
+        &self,
+        id: &A::ActorId,
+        state_bytes: Result<Vec<u8>, CodecError>,
+        expected: Expected,
+    ) -> impl std::future::Future<Output = Result<ETag, BoxedActorError>> {
+        let id_bytes = bincode::serialize(id);
+        let etag = self.next_etag();
+        let commands = self.commands.clone();
+        let actor_name = state_namespace::<A>(self.slot);
+
+        async move {
+            let id = id_bytes.map_err(SqlitePersistenceError::failed_to_serialize_id)?;
+            let state = state_bytes.map_err(SqlitePersistenceError::failed_to_serialize_state)?;
+            let (reply, response) = oneshot::channel();
+            commands
+                .send(Command::Save {
+                    actor_name,
+                    id,
+                    state,
+                    etag,
+                    expected,
+                    reply,
+                })
+                .map_err(SqlitePersistenceError::worker_stopped)?;
+            response
+                .await
+                .map_err(SqlitePersistenceError::worker_stopped)?
+        }
+    }
+}
+
+impl<A, S, C> ActorPersistence<A, S> for SqlitePersistence<C>
+where
+    A: VirtualActor,
+    S: Serialize + DeserializeOwned,
+    C: StateCodec,
+{
+    fn load(&self, id: &A::ActorId) -> BoxFuture<'_, Result<Option<S>, BoxedActorError>> {
+        let bytes = self.load_bytes::<A>(id);
+        let codec = self.codecs.get::<A>();
+
+        Box::pin(async move {
+            match bytes.await? {
+                Some((bytes, _)) => decode_state::<_, S>(codec, &bytes)
+                    .map(Some)
+                    .map_err(SqlitePersistenceError::failed_to_deserialize_state),
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn save(&self, id: &A::ActorId, state: &S) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let saved = self.save_bytes::<A>(id, self.codecs.get::<A>().encode(state), Expected::Any);
+
+        Box::pin(async move {
+            saved.await?;
+            Ok(())
+        })
+    }
+
+    fn load_versioned(
+        &self,
+        id: &A::ActorId,
+    ) -> BoxFuture<'_, Result<Option<(S, ETag)>, BoxedActorError>> {
+        let bytes = self.load_bytes::<A>(id);
+        let codec = self.codecs.get::<A>();
+
+        Box::pin(async move {
+            match bytes.await? {
+                Some((bytes, etag)) => decode_state::<_, S>(codec, &bytes)
+                    .map(|state| Some((state, etag)))
+                    .map_err(SqlitePersistenceError::failed_to_deserialize_state),
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn save_versioned(
+        &self,
+        id: &A::ActorId,
+        state: &S,
+        expected: Option<ETag>,
+    ) -> BoxFuture<'_, Result<ETag, BoxedActorError>> {
+        Box::pin(self.save_bytes::<A>(
+            id,
+            self.codecs.get::<A>().encode(state),
+            Expected::ETag(expected),
+        ))
+    }
+
+    fn clear(&self, id: &A::ActorId) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let id_bytes = bincode::serialize(id);
+        let commands = self.commands.clone();
+        let actor_name = state_namespace::<A>(self.slot);
+
+        Box::pin(async move {
+            let id = id_bytes.map_err(SqlitePersistenceError::failed_to_serialize_id)?;
+            let (reply, response) = oneshot::channel();
+            commands
+                .send(Command::Clear {
+                    actor_name,
+                    id,
+                    expected: None,
+                    reply,
+                })
+                .map_err(SqlitePersistenceError::worker_stopped)?;
+            response
+                .await
+                .map_err(SqlitePersistenceError::worker_stopped)?
+        })
+    }
+
+    fn clear_versioned(
+        &self,
+        id: &A::ActorId,
+        expected: ETag,
+    ) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        let id_bytes = bincode::serialize(id);
+        let commands = self.commands.clone();
+        let actor_name = state_namespace::<A>(self.slot);
+
+        Box::pin(async move {
+            let id = id_bytes.map_err(SqlitePersistenceError::failed_to_serialize_id)?;
+            let (reply, response) = oneshot::channel();
+            commands
+                .send(Command::Clear {
+                    actor_name,
+                    id,
+                    expected: Some(expected),
+                    reply,
+                })
+                .map_err(SqlitePersistenceError::worker_stopped)?;
+            response
+                .await
+                .map_err(SqlitePersistenceError::worker_stopped)?
+        })
+    }
+
+    fn expired(
+        &self,
+        saved_before: SystemTime,
+        limit: usize,
+    ) -> BoxFuture<'_, Result<VersionedIds<A::ActorId>, BoxedActorError>> {
+        let commands = self.commands.clone();
+        let actor_name = state_namespace::<A>(self.slot);
+
+        Box::pin(async move {
+            let (reply, response) = oneshot::channel();
+            commands
+                .send(Command::Expired {
+                    actor_name,
+                    saved_before,
+                    limit,
+                    reply,
+                })
+                .map_err(SqlitePersistenceError::worker_stopped)?;
+            response
+                .await
+                .map_err(SqlitePersistenceError::worker_stopped)??
+                .iter()
+                .map(|(id, etag)| {
+                    bincode::deserialize(id)
+                        .map(|id| (id, *etag))
+                        .map_err(SqlitePersistenceError::failed_to_deserialize_id)
+                })
+                .collect()
+        })
+    }
+
+    fn ids(&self) -> BoxFuture<'_, Result<Vec<A::ActorId>, BoxedActorError>> {
+        let commands = self.commands.clone();
+        let actor_name = state_namespace::<A>(self.slot);
+
+        Box::pin(async move {
+            let (reply, response) = oneshot::channel();
+            commands
+                .send(Command::Ids { actor_name, reply })
+                .map_err(SqlitePersistenceError::worker_stopped)?;
+            response
+                .await
+                .map_err(SqlitePersistenceError::worker_stopped)??
+                .iter()
+                .map(|id| {
+                    bincode::deserialize(id)
+                        .map_err(SqlitePersistenceError::failed_to_deserialize_id)
+                })
+                .collect()
+        })
+    }
+
+    fn slot(&self, name: SlotName) -> Arc<dyn ActorPersistence<A, S>> {
+        Arc::new(Self {
+            slot: Some(name),
+            ..self.clone()
+        })
+    }
+
+    fn prepared_transactions(&self) -> Arc<dyn ActorPersistence<A, PreparedTransaction>> {
+        Arc::new(Self {
+            slot: Some(transaction_slot(self.slot)),
+            ..self.clone()
+        })
+    }
+}
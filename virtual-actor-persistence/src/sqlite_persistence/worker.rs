@@ -0,0 +1,513 @@
+//! Worker thread owning sqlite connection
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::mpsc::{Receiver, TryRecvError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use rusqlite::{named_params, Connection, OptionalExtension, ToSql};
+use tokio::sync::oneshot;
+use virtual_actor_runtime::errors::BoxedActorError;
+
+use super::{SqlitePersistenceError, TableLayout};
+use crate::actor_persistence_trait::{ETag, PersistenceError};
+
+const SHARED_TABLE: &str = "actor_state";
+
+/// Version check for write
+pub enum Expected {
+    /// Unconditional write
+    Any,
+    /// Write only if persisted version matches
+    ETag(Option<ETag>),
+}
+
+/// Reply with result of command
+pub type Reply<T> = oneshot::Sender<Result<T, BoxedActorError>>;
+
+/// Command executed by worker
+pub enum Command {
+    Load {
+        actor_name: Cow<'static, str>,
+        id: Vec<u8>,
+        reply: Reply<Option<(Vec<u8>, ETag)>>,
+    },
+    Save {
+        actor_name: Cow<'static, str>,
+        id: Vec<u8>,
+        state: Vec<u8>,
+        etag: ETag,
+        expected: Expected,
+        reply: Reply<ETag>,
+    },
+    Clear {
+        actor_name: Cow<'static, str>,
+        id: Vec<u8>,
+        /// Clear only this version of state, any version if `None`
+        expected: Option<ETag>,
+        reply: Reply<()>,
+    },
+    Expired {
+        actor_name: Cow<'static, str>,
+        saved_before: SystemTime,
+        limit: usize,
+        reply: Reply<Vec<(Vec<u8>, ETag)>>,
+    },
+    Ids {
+        actor_name: Cow<'static, str>,
+        reply: Reply<Vec<Vec<u8>>>,
+    },
+}
+
+/// Result of write, sent once transaction is committed
+enum Pending {
+    Save(Reply<ETag>, Result<ETag, BoxedActorError>),
+    Clear(Reply<()>, Result<(), BoxedActorError>),
+}
+
+impl Pending {
+    fn send(self, commit_error: Option<&rusqlite::Error>) {
+        match self {
+            Self::Save(reply, result) => send_committed(reply, result, commit_error),
+            Self::Clear(reply, result) => send_committed(reply, result, commit_error),
+        }
+    }
+}
+
+fn send_committed<T>(
+    reply: Reply<T>,
+    result: Result<T, BoxedActorError>,
+    commit_error: Option<&rusqlite::Error>,
+) {
+    let result = match commit_error {
+        None => result,
+        Some(e) => Err(SqlitePersistenceError::sqlite(e)),
+    };
+    let _ = reply.send(result);
+}
+
+/// Time of save stored in `saved_at` column, milliseconds since Unix epoch
+pub fn unix_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_millis()).unwrap_or(i64::MAX))
+}
+
+/// Adds `saved_at` column to table created before save time was tracked,
+/// existing states are considered saved now
+fn add_saved_at_column(connection: &Connection, table: &str) -> Result<(), rusqlite::Error> {
+    let exists: bool = connection.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = 'saved_at'",
+        [table],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        connection.execute_batch(&format!(
+            "ALTER TABLE \"{}\" ADD COLUMN saved_at INTEGER NOT NULL DEFAULT {}",
+            table.replace('"', "\"\""),
+            unix_millis(SystemTime::now())
+        ))?;
+    }
+    Ok(())
+}
+
+/// SQL statements of actor state table
+struct Table {
+    /// Actor name column value, `None` if table is dedicated to actor type
+    actor_name: Option<String>,
+    select: String,
+    upsert: String,
+    delete: String,
+    expired: String,
+    ids: String,
+}
+
+impl Table {
+    /// Creates table if it does not exist
+    fn create(
+        connection: &Connection,
+        layout: TableLayout,
+        actor_name: &str,
+    ) -> Result<Self, rusqlite::Error> {
+        match layout {
+            TableLayout::Shared => {
+                connection.execute_batch(&format!(
+                    "CREATE TABLE IF NOT EXISTS {SHARED_TABLE} (
+                        actor_name TEXT NOT NULL,
+                        actor_id BLOB NOT NULL,
+                        state BLOB NOT NULL,
+                        etag INTEGER NOT NULL,
+                        saved_at INTEGER NOT NULL,
+                        PRIMARY KEY (actor_name, actor_id)
+                    ) WITHOUT ROWID"
+                ))?;
+                add_saved_at_column(connection, SHARED_TABLE)?;
+                connection.execute_batch(&format!(
+                    "CREATE INDEX IF NOT EXISTS {SHARED_TABLE}_saved_at
+                        ON {SHARED_TABLE} (actor_name, saved_at)"
+                ))?;
+                Ok(Self {
+                    actor_name: Some(actor_name.to_owned()),
+                    select: format!(
+                        "SELECT state, etag FROM {SHARED_TABLE}
+                            WHERE actor_name = :actor_name AND actor_id = :actor_id"
+                    ),
+                    upsert: format!(
+                        "INSERT INTO {SHARED_TABLE} (actor_name, actor_id, state, etag, saved_at)
+                            VALUES (:actor_name, :actor_id, :state, :etag, :saved_at)
+                            ON CONFLICT (actor_name, actor_id)
+                            DO UPDATE SET state = excluded.state, etag = excluded.etag,
+                                saved_at = excluded.saved_at"
+                    ),
+                    delete: format!(
+                        "DELETE FROM {SHARED_TABLE}
+                            WHERE actor_name = :actor_name AND actor_id = :actor_id"
+                    ),
+                    expired: format!(
+                        "SELECT actor_id, etag FROM {SHARED_TABLE}
+                            WHERE actor_name = :actor_name AND saved_at < :saved_before
+                            LIMIT :limit"
+                    ),
+                    ids: format!(
+                        "SELECT actor_id FROM {SHARED_TABLE} WHERE actor_name = :actor_name"
+                    ),
+                })
+            }
+            TableLayout::PerActorType => {
+                let name = format!("{SHARED_TABLE}_{actor_name}");
+                let table = format!("\"{}\"", name.replace('"', "\"\""));
+                let index = format!("\"{}_saved_at\"", name.replace('"', "\"\""));
+                connection.execute_batch(&format!(
+                    "CREATE TABLE IF NOT EXISTS {table} (
+                        actor_id BLOB NOT NULL PRIMARY KEY,
+                        state BLOB NOT NULL,
+                        etag INTEGER NOT NULL,
+                        saved_at INTEGER NOT NULL
+                    ) WITHOUT ROWID"
+                ))?;
+                add_saved_at_column(connection, &name)?;
+                connection.execute_batch(&format!(
+                    "CREATE INDEX IF NOT EXISTS {index} ON {table} (saved_at)"
+                ))?;
+                Ok(Self {
+                    actor_name: None,
+                    select: format!("SELECT state, etag FROM {table} WHERE actor_id = :actor_id"),
+                    upsert: format!(
+                        "INSERT INTO {table} (actor_id, state, etag, saved_at)
+                            VALUES (:actor_id, :state, :etag, :saved_at)
+                            ON CONFLICT (actor_id)
+                            DO UPDATE SET state = excluded.state, etag = excluded.etag,
+                                saved_at = excluded.saved_at"
+                    ),
+                    delete: format!("DELETE FROM {table} WHERE actor_id = :actor_id"),
+                    expired: format!(
+                        "SELECT actor_id, etag FROM {table} WHERE saved_at < :saved_before LIMIT :limit"
+                    ),
+                    ids: format!("SELECT actor_id FROM {table}"),
+                })
+            }
+        }
+    }
+
+    fn key_params<'a>(&'a self, id: &'a dyn ToSql) -> Vec<(&'static str, &'a dyn ToSql)> {
+        let mut params: Vec<(&'static str, &dyn ToSql)> = vec![(":actor_id", id)];
+        if let Some(actor_name) = &self.actor_name {
+            params.push((":actor_name", actor_name));
+        }
+        params
+    }
+
+    fn load(
+        &self,
+        connection: &Connection,
+        id: &dyn ToSql,
+    ) -> Result<Option<(Vec<u8>, ETag)>, rusqlite::Error> {
+        connection
+            .prepare_cached(&self.select)?
+            .query_row(self.key_params(id).as_slice(), |row| {
+                Ok((row.get(0)?, ETag(row.get::<_, i64>(1)?.cast_unsigned())))
+            })
+            .optional()
+    }
+
+    fn save(
+        &self,
+        connection: &Connection,
+        id: &dyn ToSql,
+        state: &[u8],
+        etag: ETag,
+    ) -> Result<(), rusqlite::Error> {
+        // SQLite integers are signed, etag is stored bitwise
+        let etag = etag.0.cast_signed();
+        let saved_at = unix_millis(SystemTime::now());
+        let mut params = self.key_params(id);
+        params.extend(named_params! { ":state": state, ":etag": etag, ":saved_at": saved_at });
+        connection
+            .prepare_cached(&self.upsert)?
+            .execute(params.as_slice())?;
+        Ok(())
+    }
+
+    fn clear(&self, connection: &Connection, id: &dyn ToSql) -> Result<(), rusqlite::Error> {
+        connection
+            .prepare_cached(&self.delete)?
+            .execute(self.key_params(id).as_slice())?;
+        Ok(())
+    }
+
+    fn expired(
+        &self,
+        connection: &Connection,
+        saved_before: SystemTime,
+        limit: usize,
+    ) -> Result<Vec<(Vec<u8>, ETag)>, rusqlite::Error> {
+        let saved_before = unix_millis(saved_before);
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let mut params: Vec<(&'static str, &dyn ToSql)> =
+            named_params! { ":saved_before": saved_before, ":limit": limit }.to_vec();
+        if let Some(actor_name) = &self.actor_name {
+            params.push((":actor_name", actor_name));
+        }
+        connection
+            .prepare_cached(&self.expired)?
+            .query_map(params.as_slice(), |row| {
+                Ok((row.get(0)?, ETag(row.get::<_, i64>(1)?.cast_unsigned())))
+            })?
+            .collect()
+    }
+
+    fn ids(&self, connection: &Connection) -> Result<Vec<Vec<u8>>, rusqlite::Error> {
+        let mut params: Vec<(&'static str, &dyn ToSql)> = Vec::new();
+        if let Some(actor_name) = &self.actor_name {
+            params.push((":actor_name", actor_name));
+        }
+        connection
+            .prepare_cached(&self.ids)?
+            .query_map(params.as_slice(), |row| row.get(0))?
+            .collect()
+    }
+}
+
+pub struct Worker {
+    connection: Connection,
+    layout: TableLayout,
+    max_batch_size: usize,
+    tables: HashMap<String, Table>,
+}
+
+impl Worker {
+    pub fn new(connection: Connection, layout: TableLayout, max_batch_size: usize) -> Self {
+        Self {
+            connection,
+            layout,
+            max_batch_size: max_batch_size.max(1),
+            tables: HashMap::new(),
+        }
+    }
+
+    /// Executes commands until all senders are dropped,
+    /// commands received together are executed in one transaction
+    pub fn run(mut self, commands: &Receiver<Command>) {
+        while let Ok(command) = commands.recv() {
+            let mut batch = vec![command];
+            while batch.len() < self.max_batch_size {
+                match commands.try_recv() {
+                    Ok(command) => batch.push(command),
+                    Err(TryRecvError::Empty | TryRecvError::Disconnected) => break,
+                }
+            }
+            self.execute_batch(batch);
+        }
+    }
+
+    fn execute_batch(&mut self, batch: Vec<Command>) {
+        // tables are created outside of batch transaction, so they are kept if commit fails
+        let mut ready = Vec::with_capacity(batch.len());
+        for command in batch {
+            match table(
+                &mut self.tables,
+                &self.connection,
+                self.layout,
+                command.actor_name(),
+            ) {
+                Ok(_) => ready.push(command),
+                Err(e) => command.fail(&e),
+            }
+        }
+        let batch = ready;
+        if batch.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.connection.execute_batch("BEGIN IMMEDIATE") {
+            for command in batch {
+                command.fail(&e);
+            }
+            return;
+        }
+
+        let mut pending = Vec::new();
+        for command in batch {
+            match command {
+                Command::Load {
+                    actor_name,
+                    id,
+                    reply,
+                } => {
+                    let result =
+                        table(&mut self.tables, &self.connection, self.layout, &actor_name)
+                            .and_then(|table| table.load(&self.connection, &id))
+                            .map_err(SqlitePersistenceError::sqlite);
+                    let _ = reply.send(result);
+                }
+                Command::Save {
+                    actor_name,
+                    id,
+                    state,
+                    etag,
+                    expected,
+                    reply,
+                } => {
+                    let result = self.save(&actor_name, &id, &state, etag, &expected);
+                    pending.push(Pending::Save(reply, result));
+                }
+                Command::Clear {
+                    actor_name,
+                    id,
+                    expected,
+                    reply,
+                } => {
+                    let result = self.clear(&actor_name, &id, expected);
+                    pending.push(Pending::Clear(reply, result));
+                }
+                Command::Expired {
+                    actor_name,
+                    saved_before,
+                    limit,
+                    reply,
+                } => {
+                    let result =
+                        table(&mut self.tables, &self.connection, self.layout, &actor_name)
+                            .and_then(|table| table.expired(&self.connection, saved_before, limit))
+                            .map_err(SqlitePersistenceError::sqlite);
+                    let _ = reply.send(result);
+                }
+                Command::Ids { actor_name, reply } => {
+                    let result =
+                        table(&mut self.tables, &self.connection, self.layout, &actor_name)
+                            .and_then(|table| table.ids(&self.connection))
+                            .map_err(SqlitePersistenceError::sqlite);
+                    let _ = reply.send(result);
+                }
+            }
+        }
+
+        let commit = self.connection.execute_batch("COMMIT");
+        if commit.is_err() {
+            let _ = self.connection.execute_batch("ROLLBACK");
+        }
+        for pending in pending {
+            pending.send(commit.as_ref().err());
+        }
+    }
+
+    fn save(
+        &mut self,
+        actor_name: &str,
+        id: &[u8],
+        state: &[u8],
+        etag: ETag,
+        expected: &Expected,
+    ) -> Result<ETag, BoxedActorError> {
+        let table = table(&mut self.tables, &self.connection, self.layout, actor_name)
+            .map_err(SqlitePersistenceError::sqlite)?;
+        if let Expected::ETag(expected) = expected {
+            let actual = table
+                .load(&self.connection, &id)
+                .map_err(SqlitePersistenceError::sqlite)?
+                .map(|(_, etag)| etag);
+            if actual != *expected {
+                return Err(BoxedActorError::new(PersistenceError::InconsistentState {
+                    expected: *expected,
+                    actual,
+                }));
+            }
+        }
+        table
+            .save(&self.connection, &id, state, etag)
+            .map_err(SqlitePersistenceError::sqlite)?;
+        Ok(etag)
+    }
+
+    fn clear(
+        &mut self,
+        actor_name: &str,
+        id: &[u8],
+        expected: Option<ETag>,
+    ) -> Result<(), BoxedActorError> {
+        let table = table(&mut self.tables, &self.connection, self.layout, actor_name)
+            .map_err(SqlitePersistenceError::sqlite)?;
+        if let Some(expected) = expected {
+            let actual = table
+                .load(&self.connection, &id)
+                .map_err(SqlitePersistenceError::sqlite)?
+                .map(|(_, etag)| etag);
+            if actual != Some(expected) {
+                return Err(BoxedActorError::new(PersistenceError::InconsistentState {
+                    expected: Some(expected),
+                    actual,
+                }));
+            }
+        }
+        table
+            .clear(&self.connection, &id)
+            .map_err(SqlitePersistenceError::sqlite)
+    }
+}
+
+/// Returns table of actor type, creates it on first access
+fn table<'a>(
+    tables: &'a mut HashMap<String, Table>,
+    connection: &Connection,
+    layout: TableLayout,
+    actor_name: &str,
+) -> Result<&'a Table, rusqlite::Error> {
+    if !tables.contains_key(actor_name) {
+        let table = Table::create(connection, layout, actor_name)?;
+        tables.insert(actor_name.to_owned(), table);
+    }
+    Ok(&tables[actor_name])
+}
+
+impl Command {
+    fn actor_name(&self) -> &str {
+        match self {
+            Self::Load { actor_name, .. }
+            | Self::Save { actor_name, .. }
+            | Self::Clear { actor_name, .. }
+            | Self::Expired { actor_name, .. }
+            | Self::Ids { actor_name, .. } => actor_name,
+        }
+    }
+
+    fn fail(self, e: &rusqlite::Error) {
+        match self {
+            Self::Load { reply, .. } => {
+                let _ = reply.send(Err(SqlitePersistenceError::sqlite(e)));
+            }
+            Self::Save { reply, .. } => {
+                let _ = reply.send(Err(SqlitePersistenceError::sqlite(e)));
+            }
+            Self::Clear { reply, .. } => {
+                let _ = reply.send(Err(SqlitePersistenceError::sqlite(e)));
+            }
+            Self::Expired { reply, .. } => {
+                let _ = reply.send(Err(SqlitePersistenceError::sqlite(e)));
+            }
+            Self::Ids { reply, .. } => {
+                let _ = reply.send(Err(SqlitePersistenceError::sqlite(e)));
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use serde::{de::DeserializeOwned, Serialize};
+use virtual_actor_runtime::prelude::{ActorName, VirtualActor};
+
+/// State codec error
+#[derive(Debug, thiserror::Error)]
+pub enum CodecError {
+    /// Failed to encode value
+    #[error("Failed to encode: {0}")]
+    Encode(String),
+    /// Failed to decode value
+    #[error("Failed to decode: {0}")]
+    Decode(String),
+}
+
+impl CodecError {
+    #[allow(clippy::needless_pass_by_value)]
+    fn encode<E: ToString>(e: E) -> Self {
+        Self::Encode(e.to_string())
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn decode<E: ToString>(e: E) -> Self {
+        Self::Decode(e.to_string())
+    }
+}
+
+/// Serialization format of persisted state
+pub trait StateCodec: Clone + Send + Sync + 'static {
+    /// Encodes value
+    ///
+    /// # Errors
+    ///
+    /// Returns error if value can not be encoded
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, CodecError>;
+
+    /// Decodes value
+    ///
+    /// # Errors
+    ///
+    /// Returns error if bytes do not contain value of requested type
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError>;
+}
+
+/// Compact binary format, default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BincodeCodec;
+
+impl StateCodec for BincodeCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, CodecError> {
+        bincode::serialize(value).map_err(CodecError::encode)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        bincode::deserialize(bytes).map_err(CodecError::decode)
+    }
+}
+
+/// CBOR format
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CborCodec;
+
+impl StateCodec for CborCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, CodecError> {
+        serde_cbor::to_vec(value).map_err(CodecError::encode)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        serde_cbor::from_slice(bytes).map_err(CodecError::decode)
+    }
+}
+
+/// JSON format, human readable
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec {
+    /// Pretty print encoded values
+    pub pretty: bool,
+}
+
+impl StateCodec for JsonCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, CodecError> {
+        if self.pretty {
+            serde_json::to_vec_pretty(value).map_err(CodecError::encode)
+        } else {
+            serde_json::to_vec(value).map_err(CodecError::encode)
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        serde_json::from_slice(bytes).map_err(CodecError::decode)
+    }
+}
+
+/// `MessagePack` format, structs are encoded as maps with field names
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessagePackCodec;
+
+impl StateCodec for MessagePackCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, CodecError> {
+        rmp_serde::to_vec_named(value).map_err(CodecError::encode)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        rmp_serde::from_slice(bytes).map_err(CodecError::decode)
+    }
+}
+
+/// Format selected at runtime, allows different formats per actor type in one persistence
+#[derive(Debug, Clone, Copy, Default)]
+pub enum StateFormat {
+    /// See `BincodeCodec`
+    #[default]
+    Bincode,
+    /// See `CborCodec`
+    Cbor,
+    /// See `JsonCodec`
+    Json(JsonCodec),
+    /// See `MessagePackCodec`
+    MessagePack,
+}
+
+impl StateCodec for StateFormat {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, CodecError> {
+        match self {
+            Self::Bincode => BincodeCodec.encode(value),
+            Self::Cbor => CborCodec.encode(value),
+            Self::Json(codec) => codec.encode(value),
+            Self::MessagePack => MessagePackCodec.encode(value),
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        match self {
+            Self::Bincode => BincodeCodec.decode(bytes),
+            Self::Cbor => CborCodec.decode(bytes),
+            Self::Json(codec) => codec.decode(bytes),
+            Self::MessagePack => MessagePackCodec.decode(bytes),
+        }
+    }
+}
+
+/// Codec of persistence with overrides per actor type
+#[derive(Debug, Clone)]
+pub(crate) struct Codecs<C: StateCodec> {
+    default: C,
+    per_actor: HashMap<ActorName, C>,
+}
+
+impl<C: StateCodec> Codecs<C> {
+    pub fn new(default: C) -> Self {
+        Self {
+            default,
+            per_actor: HashMap::new(),
+        }
+    }
+
+    pub fn set<A: VirtualActor>(&mut self, codec: C) {
+        self.per_actor.insert(A::name(), codec);
+    }
+
+    pub fn get<A: VirtualActor>(&self) -> &C {
+        self.per_actor.get(A::name()).unwrap_or(&self.default)
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use futures::future::BoxFuture;
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::VirtualActor, RuntimeContext};
+
+use super::actor_persistence_trait::{ActorPersistence, ETag, PersistenceError};
+use super::actor_with_state_trait::ActorWithState;
+use super::state_sweeper::StateSweeper;
+
+/// Default maximal number of states expired by one sweep
+const DEFAULT_MAX_EXPIRED_PER_SWEEP: usize = 1024;
+
+/// Hook called with expired state before it is cleared
+type ExpireHook<A, S> = Arc<
+    dyn Fn(<A as VirtualActor>::ActorId, S) -> BoxFuture<'static, Result<(), BoxedActorError>>
+        + Send
+        + Sync,
+>;
+
+/// Retention of persisted states of actor type
+///
+/// States not saved for longer than TTL are considered abandoned and cleared by `StateSweeper`.
+/// States of active actors and states saved again after they were listed as expired are kept.
+pub struct StateRetention<A: VirtualActor, S = <A as ActorWithState>::State> {
+    persistence: Arc<dyn ActorPersistence<A, S>>,
+    ttl: Duration,
+    max_expired_per_sweep: usize,
+    on_expire: Option<ExpireHook<A, S>>,
+}
+
+impl<A: VirtualActor, S: Send + 'static> StateRetention<A, S> {
+    /// Create a new retention of states stored in `persistence` for `ttl` since last save
+    #[must_use]
+    pub fn new(persistence: Arc<dyn ActorPersistence<A, S>>, ttl: Duration) -> Self {
+        Self {
+            persistence,
+            ttl,
+            max_expired_per_sweep: DEFAULT_MAX_EXPIRED_PER_SWEEP,
+            on_expire: None,
+        }
+    }
+
+    /// Limits number of states expired by one sweep, the rest is expired by next sweeps
+    #[must_use]
+    pub fn with_max_expired_per_sweep(mut self, max_expired_per_sweep: usize) -> Self {
+        self.max_expired_per_sweep = max_expired_per_sweep;
+        self
+    }
+
+    /// Sets hook called with expired state before it is cleared, e.g. to archive it.
+    /// State is kept if hook fails and expired again by next sweep.
+    #[must_use]
+    pub fn on_expire<F, Fut>(mut self, on_expire: F) -> Self
+    where
+        F: Fn(A::ActorId, S) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), BoxedActorError>> + Send + 'static,
+    {
+        self.on_expire = Some(Arc::new(move |id, state| Box::pin(on_expire(id, state))));
+        self
+    }
+
+    /// Clears states not saved for longer than TTL, returns number of cleared states.
+    /// States of actors for which `is_active` returns `true` are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if expired states can not be listed,
+    /// states failed to be archived or cleared are skipped
+    pub async fn sweep(
+        &self,
+        is_active: impl Fn(&A::ActorId) -> bool,
+    ) -> Result<usize, BoxedActorError> {
+        let saved_before = SystemTime::now()
+            .checked_sub(self.ttl)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let expired = self
+            .persistence
+            .expired(saved_before, self.max_expired_per_sweep)
+            .await?;
+
+        let actor_name = A::name();
+        let mut cleared = 0;
+        for (id, etag) in expired {
+            if is_active(&id) {
+                continue;
+            }
+            match self.expire(&id, etag).await {
+                Ok(true) => cleared += 1,
+                Ok(false) => {}
+                Err(e) => eprintln!("Failed to expire state of {actor_name}::{id}: {e:?}"),
+            }
+        }
+        Ok(cleared)
+    }
+
+    /// Clears state if it was not saved since it was listed as expired,
+    /// returns `false` if state is kept
+    async fn expire(&self, id: &A::ActorId, etag: ETag) -> Result<bool, BoxedActorError> {
+        if let Some(on_expire) = &self.on_expire {
+            match self.persistence.load_versioned(id).await? {
+                Some((state, actual)) if actual == etag => on_expire(id.clone(), state).await?,
+                _ => return Ok(false),
+            }
+        }
+        match self.persistence.clear_versioned(id, etag).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.is::<PersistenceError>() => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Retention of actor type with erased state type, swept by `StateSweeper`
+pub(crate) trait RetentionTask: Send + Sync + 'static {
+    /// Name of actor type
+    fn actor_name(&self) -> &'static str;
+
+    /// Clears expired states of actors which are not active
+    fn sweep<'a>(
+        &'a self,
+        ctx: &'a RuntimeContext<StateSweeper>,
+    ) -> BoxFuture<'a, Result<usize, BoxedActorError>>;
+}
+
+impl<A: VirtualActor, S: Send + 'static> RetentionTask for StateRetention<A, S> {
+    fn actor_name(&self) -> &'static str {
+        A::name()
+    }
+
+    fn sweep<'a>(
+        &'a self,
+        ctx: &'a RuntimeContext<StateSweeper>,
+    ) -> BoxFuture<'a, Result<usize, BoxedActorError>> {
+        Box::pin(Self::sweep(self, |id| ctx.is_active::<A>(id)))
+    }
+}
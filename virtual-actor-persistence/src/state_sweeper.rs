@@ -0,0 +1,143 @@
+use std::{sync::Arc, time::Duration};
+
+use virtual_actor_runtime::{
+    errors::{LocalAddrError, LocalExecutorError},
+    prelude::*,
+    LocalAddr,
+};
+
+use super::state_retention::{RetentionTask, StateRetention};
+
+/// State sweeper error
+#[derive(Debug, thiserror::Error)]
+pub enum StateSweeperError {
+    /// Failed to spawn sweeper on housekeeping executor
+    #[error("Failed to spawn state sweeper: {0}")]
+    Spawn(#[from] LocalExecutorError),
+    /// Failed to schedule sweeps
+    #[error("Failed to schedule state sweeps: {0}")]
+    Schedule(#[from] LocalAddrError),
+}
+
+/// Sweeps expired states of all retained actor types,
+/// returns number of cleared states
+#[derive(Message)]
+#[result(usize)]
+pub struct Sweep;
+
+/// Clears abandoned states of retained actor types, see `StateRetention`
+///
+/// Runs on housekeeping executor, sweeps periodically once started by `StateSweeperFactory`.
+#[derive(Actor, LocalActor)]
+#[message(Sweep)]
+pub struct StateSweeper {
+    retentions: Vec<Arc<dyn RetentionTask>>,
+    sweep_interval: Duration,
+    /// Indicates that periodic sweeps are scheduled
+    scheduled: bool,
+}
+
+impl StateSweeper {
+    /// Sends `Sweep` every interval while sweeper is alive
+    fn schedule(&mut self, ctx: &<Self as Actor>::ActorContext) {
+        if self.scheduled {
+            return;
+        }
+        self.scheduled = true;
+
+        let ctx = ctx.clone();
+        let interval = self.sweep_interval;
+        tokio::task::spawn_local(async move {
+            // stops with sweeper or runtime shutdown
+            while ctx.sleep(interval).await.is_ok() {
+                let Some(addr) = ctx.self_addr().upgrade() else {
+                    return;
+                };
+                if addr.send(Sweep).await.is_err() {
+                    return;
+                }
+            }
+        });
+    }
+}
+
+impl MessageHandler<Sweep> for StateSweeper {
+    async fn handle(
+        &mut self,
+        _msg: Sweep,
+        ctx: &Self::ActorContext,
+    ) -> <Sweep as Message>::Result {
+        self.schedule(ctx);
+
+        let mut cleared = 0;
+        for retention in &self.retentions {
+            match retention.sweep(ctx).await {
+                Ok(count) => cleared += count,
+                Err(e) => eprintln!(
+                    "Failed to sweep expired states of {}: {e:?}",
+                    retention.actor_name()
+                ),
+            }
+        }
+        cleared
+    }
+}
+
+/// Factory of state sweeper with retention settings per actor type
+pub struct StateSweeperFactory {
+    retentions: Vec<Arc<dyn RetentionTask>>,
+    sweep_interval: Duration,
+}
+
+impl StateSweeperFactory {
+    /// Create a new factory of sweeper running every `sweep_interval`
+    #[must_use]
+    pub fn new(sweep_interval: Duration) -> Self {
+        Self {
+            retentions: Vec::new(),
+            sweep_interval,
+        }
+    }
+
+    /// Adds retention of actor type or state slot
+    #[must_use]
+    pub fn with_retention<A: VirtualActor, S: Send + 'static>(
+        mut self,
+        retention: StateRetention<A, S>,
+    ) -> Self {
+        self.retentions.push(Arc::new(retention));
+        self
+    }
+
+    /// Spawns sweeper on housekeeping executor of runtime, first sweep starts immediately
+    ///
+    /// # Errors
+    ///
+    /// Returns error if sweeper is not spawned
+    pub async fn start(
+        self,
+        runtime: &Runtime,
+    ) -> Result<LocalAddr<StateSweeper>, StateSweeperError> {
+        let addr = runtime
+            .spawn_local_with_factory(&Arc::new(self), runtime.housekeeping_executor())
+            .await?;
+        addr.dispatch(Sweep).await?;
+        Ok(addr)
+    }
+}
+
+impl ActorFactory for StateSweeperFactory {
+    type Actor = StateSweeper;
+}
+
+impl LocalActorFactory for StateSweeperFactory {
+    type Error = std::convert::Infallible;
+
+    async fn create_actor(&self) -> Result<StateSweeper, Self::Error> {
+        Ok(StateSweeper {
+            retentions: self.retentions.clone(),
+            sweep_interval: self.sweep_interval,
+            scheduled: false,
+        })
+    }
+}
@@ -0,0 +1,207 @@
+//! Versioned state with migrations
+
+use std::{
+    cell::Cell,
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{
+    de::{self, DeserializeOwned, SeqAccess, Visitor},
+    ser::SerializeTuple,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::state_codec::{CodecError, StateCodec};
+
+/// Marks versioned state envelope, distinguishes it from state persisted before versioning
+const ENVELOPE_MAGIC: u32 = u32::from_le_bytes(*b"VAVS");
+
+thread_local! {
+    /// Set while state persisted before versioning is decoded
+    static DECODING_UNVERSIONED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Migration of state from previous version
+pub trait Migrate<From>: Sized {
+    /// Converts state of previous version
+    fn migrate(from: From) -> Self;
+}
+
+/// Version of state in migration chain
+///
+/// First version uses `InitialVersion` as previous one, every next version
+/// points to version it is migrated from and implements `Migrate` for it.
+pub trait StateVersion:
+    Serialize + DeserializeOwned + Migrate<<Self as StateVersion>::Previous>
+{
+    /// Version tag stored with state, has to grow with every version
+    const VERSION: u32;
+
+    /// Version this one is migrated from
+    type Previous: StateVersion;
+}
+
+/// Start of migration chain, can not be constructed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum InitialVersion {}
+
+impl<T> Migrate<InitialVersion> for T {
+    fn migrate(from: InitialVersion) -> Self {
+        match from {}
+    }
+}
+
+impl StateVersion for InitialVersion {
+    const VERSION: u32 = 0;
+    type Previous = Self;
+}
+
+/// State persisted with its version tag
+///
+/// State of older version is migrated to `S` through `StateVersion::Previous` chain
+/// when it is deserialized. State persisted before versioning was introduced has no envelope,
+/// it is decoded as first version of chain and reported as migrated from version 0.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Versioned<S> {
+    state: S,
+    /// Version state was migrated from while it was deserialized
+    migrated_from: Option<u32>,
+}
+
+impl<S> Versioned<S> {
+    /// Wraps state of current version
+    pub fn new(state: S) -> Self {
+        Self {
+            state,
+            migrated_from: None,
+        }
+    }
+
+    /// Version state was migrated from, `None` if persisted state has current version
+    pub fn migrated_from(&self) -> Option<u32> {
+        self.migrated_from
+    }
+
+    /// Unwraps state
+    pub fn into_inner(self) -> S {
+        self.state
+    }
+}
+
+impl<S> Deref for Versioned<S> {
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        &self.state
+    }
+}
+
+impl<S> DerefMut for Versioned<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.state
+    }
+}
+
+impl<S: StateVersion> Serialize for Versioned<S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut tuple = serializer.serialize_tuple(3)?;
+        tuple.serialize_element(&ENVELOPE_MAGIC)?;
+        tuple.serialize_element(&S::VERSION)?;
+        tuple.serialize_element(&self.state)?;
+        tuple.end()
+    }
+}
+
+impl<'de, S: StateVersion> Deserialize<'de> for Versioned<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if DECODING_UNVERSIONED.with(|unversioned| unversioned.replace(false)) {
+            return Ok(Versioned {
+                state: unversioned_state::<S, D>(deserializer)?,
+                migrated_from: Some(InitialVersion::VERSION),
+            });
+        }
+        deserializer.deserialize_tuple(3, VersionedVisitor(PhantomData))
+    }
+}
+
+struct VersionedVisitor<S>(PhantomData<S>);
+
+impl<'de, S: StateVersion> Visitor<'de> for VersionedVisitor<S> {
+    type Value = Versioned<S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("envelope magic and version tag followed by state")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        if seq.next_element::<u32>()? != Some(ENVELOPE_MAGIC) {
+            return Err(de::Error::custom("Versioned state envelope is missing"));
+        }
+        let version: u32 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let state = next_state::<S, A>(version, &mut seq)?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        Ok(Versioned {
+            state,
+            migrated_from: (version != S::VERSION).then_some(version),
+        })
+    }
+}
+
+/// Deserializes state of given version and migrates it to `S`
+fn next_state<'de, S: StateVersion, A: SeqAccess<'de>>(
+    version: u32,
+    seq: &mut A,
+) -> Result<Option<S>, A::Error> {
+    if version == S::VERSION {
+        return seq.next_element();
+    }
+    if version > S::VERSION || S::Previous::VERSION >= S::VERSION {
+        return Err(de::Error::custom(format_args!(
+            "Unknown state version {version}"
+        )));
+    }
+    Ok(next_state::<S::Previous, A>(version, seq)?.map(S::migrate))
+}
+
+/// Deserializes state persisted before versioning as first version and migrates it to `S`
+fn unversioned_state<'de, S: StateVersion, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<S, D::Error> {
+    if S::VERSION == InitialVersion::VERSION {
+        return Err(de::Error::custom("Migration chain has no versions"));
+    }
+    if S::Previous::VERSION == InitialVersion::VERSION {
+        return S::deserialize(deserializer);
+    }
+    Ok(S::migrate(unversioned_state::<S::Previous, D>(
+        deserializer,
+    )?))
+}
+
+/// Decodes persisted state, `Versioned` state without envelope is decoded as unversioned one
+pub(crate) fn decode_state<C: StateCodec, T: DeserializeOwned>(
+    codec: &C,
+    bytes: &[u8],
+) -> Result<T, CodecError> {
+    // self describing formats fail on type mismatch before envelope magic is checked
+    codec.decode(bytes).or_else(|e| {
+        DECODING_UNVERSIONED.with(|unversioned| unversioned.set(true));
+        let decoded = codec.decode(bytes);
+        DECODING_UNVERSIONED.with(|unversioned| unversioned.set(false));
+        decoded.map_err(|_| e)
+    })
+}
+
+/// Handling of state migrated on load
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MigrationPolicy {
+    /// Migrated state is written with current version on next save
+    #[default]
+    Lazy,
+    /// Migrated state is written back right after it is loaded
+    Rewrite,
+}
@@ -0,0 +1,380 @@
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures::future::{join_all, BoxFuture};
+use virtual_actor_runtime::{prelude::*, VirtualAddr};
+
+use super::{
+    AbortTransaction, CommitTransaction, InmemoryTransactionLog, PrepareTransaction,
+    TransactionDecision, TransactionError, TransactionId, TransactionLog, TransactionScope,
+};
+
+/// Initial interval between attempts to deliver decided outcome
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+/// Maximal interval between attempts to deliver decided outcome
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+/// Attempts to deliver decided outcome before remaining deliveries are moved to background
+const DELIVERY_ATTEMPTS: u32 = 3;
+
+/// Participant of transaction with erased actor type
+trait Participant: Send + Sync {
+    /// Actor name and id
+    fn key(&self) -> String;
+
+    fn prepare(&self, id: TransactionId) -> BoxFuture<'_, Result<(), TransactionError>>;
+
+    fn commit(&self, id: TransactionId) -> BoxFuture<'_, Result<(), TransactionError>>;
+
+    fn abort(&self, id: TransactionId) -> BoxFuture<'_, Result<(), TransactionError>>;
+}
+
+/// Sends transaction message, delivery errors are reported as `TransactionError::Participant`
+async fn deliver<A, M>(
+    addr: &VirtualAddr<A>,
+    id: TransactionId,
+    msg: M,
+) -> Result<(), TransactionError>
+where
+    A: VirtualActor + MessageHandler<M>,
+    A::MessagesEnvelope: MessageEnvelopeFactory<A, M>,
+    M: Message<Result = Result<(), TransactionError>>,
+{
+    addr.send(msg)
+        .await
+        .map_err(|e| TransactionError::Participant {
+            transaction: id,
+            participant: format!("{}::{}", A::name(), addr.id()),
+            error: e.to_string(),
+        })?
+}
+
+impl<A> Participant for VirtualAddr<A>
+where
+    A: VirtualActor
+        + MessageHandler<PrepareTransaction>
+        + MessageHandler<CommitTransaction>
+        + MessageHandler<AbortTransaction>,
+    A::MessagesEnvelope: MessageEnvelopeFactory<A, PrepareTransaction>
+        + MessageEnvelopeFactory<A, CommitTransaction>
+        + MessageEnvelopeFactory<A, AbortTransaction>,
+{
+    fn key(&self) -> String {
+        format!("{}::{}", A::name(), self.id())
+    }
+
+    fn prepare(&self, id: TransactionId) -> BoxFuture<'_, Result<(), TransactionError>> {
+        Box::pin(deliver(self, id, PrepareTransaction(id)))
+    }
+
+    fn commit(&self, id: TransactionId) -> BoxFuture<'_, Result<(), TransactionError>> {
+        Box::pin(deliver(self, id, CommitTransaction(id)))
+    }
+
+    fn abort(&self, id: TransactionId) -> BoxFuture<'_, Result<(), TransactionError>> {
+        Box::pin(deliver(self, id, AbortTransaction(id)))
+    }
+}
+
+/// Starts transactions with two-phase commit across states of virtual actors
+///
+/// Participants enlist their `ActorState` in transactional message handlers with `ActorState::enlist`
+/// and handle `PrepareTransaction`, `CommitTransaction` and `AbortTransaction`
+/// with corresponding `ActorState` methods.
+///
+/// Commit decision is recorded in transaction log before it is sent to participants,
+/// participants left prepared by stopped coordinator are finished by `resolve_transactions`.
+#[derive(Clone)]
+pub struct TransactionCoordinator {
+    timeout: Duration,
+    /// Source of transaction ids, initialized from current time, so ids are not reused after restart
+    last_id: Arc<AtomicU64>,
+    log: Arc<dyn TransactionLog>,
+}
+
+impl TransactionCoordinator {
+    /// Create a new coordinator, transactions not finished within `timeout` are rolled back.
+    /// Decisions are recorded in `InmemoryTransactionLog`, see `with_log`.
+    #[must_use]
+    pub fn new(timeout: Duration) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX));
+        Self {
+            timeout,
+            last_id: Arc::new(AtomicU64::new(now)),
+            log: Arc::new(InmemoryTransactionLog::new()),
+        }
+    }
+
+    /// Sets log decisions are recorded in,
+    /// durable log is required to finish transactions after restart
+    #[must_use]
+    pub fn with_log(mut self, log: Arc<dyn TransactionLog>) -> Self {
+        self.log = log;
+        self
+    }
+
+    /// Begins transaction
+    #[must_use]
+    pub fn begin(&self) -> Transaction {
+        let id = TransactionId(self.last_id.fetch_add(1, Ordering::Relaxed) + 1);
+        Transaction {
+            scope: TransactionScope {
+                id,
+                deadline: SystemTime::now() + self.timeout,
+            },
+            participants: Vec::new(),
+            log: self.log.clone(),
+        }
+    }
+}
+
+/// Transaction in progress
+///
+/// Participants are enlisted by `execute` and finished by `commit` or `abort`.
+/// Participants of dropped transaction roll back once its deadline has passed,
+/// unless they are prepared by dropped `commit`, then they wait for `CommitTransaction`
+/// or `AbortTransaction` sent by `resolve_transactions`.
+pub struct Transaction {
+    scope: TransactionScope,
+    participants: Vec<Box<dyn Participant>>,
+    log: Arc<dyn TransactionLog>,
+}
+
+impl fmt::Debug for Transaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transaction")
+            .field("scope", &self.scope)
+            .field(
+                "participants",
+                &self
+                    .participants
+                    .iter()
+                    .map(|p| p.key())
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl Transaction {
+    /// Transaction identifier
+    #[must_use]
+    pub fn id(&self) -> TransactionId {
+        self.scope.id
+    }
+
+    /// Scope passed to participants in transactional messages
+    #[must_use]
+    pub fn scope(&self) -> TransactionScope {
+        self.scope
+    }
+
+    /// Sends transactional message to participant and enlists it,
+    /// transaction is rolled back if message fails
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransactionError::Aborted` with error returned by handler,
+    /// `TransactionError::Timeout` if deadline has passed
+    /// or `TransactionError::Participant` if message is not delivered
+    pub async fn execute<A, M, R, E>(
+        &mut self,
+        addr: &VirtualAddr<A>,
+        msg: M,
+    ) -> Result<R, TransactionError>
+    where
+        A: VirtualActor
+            + MessageHandler<M>
+            + MessageHandler<PrepareTransaction>
+            + MessageHandler<CommitTransaction>
+            + MessageHandler<AbortTransaction>,
+        A::MessagesEnvelope: MessageEnvelopeFactory<A, M>
+            + MessageEnvelopeFactory<A, PrepareTransaction>
+            + MessageEnvelopeFactory<A, CommitTransaction>
+            + MessageEnvelopeFactory<A, AbortTransaction>,
+        M: Message<Result = Result<R, E>>,
+        E: fmt::Display,
+    {
+        // enlisted before message is sent, so state enlisted by failed handler is rolled back too
+        let participant = addr.clone();
+        if !self
+            .participants
+            .iter()
+            .any(|p| p.key() == participant.key())
+        {
+            self.participants.push(Box::new(participant));
+        }
+
+        let id = self.id();
+        let result = match self.within_deadline(addr.send(msg)).await {
+            Ok(Ok(Ok(result))) => return Ok(result),
+            Ok(Ok(Err(e))) => TransactionError::Aborted {
+                transaction: id,
+                reason: e.to_string(),
+            },
+            Ok(Err(e)) => TransactionError::Participant {
+                transaction: id,
+                participant: Participant::key(addr),
+                error: e.to_string(),
+            },
+            Err(e) => e,
+        };
+        self.rollback().await;
+        Err(result)
+    }
+
+    /// Prepares all participants and commits transaction if all of them voted for commit,
+    /// otherwise rolls it back.
+    /// Commit decision is recorded in transaction log before it is sent.
+    /// Prepared participants keep their state until they acknowledge decided outcome,
+    /// so it is resent until every one of them does,
+    /// deliveries not acknowledged after few attempts are retried in background.
+    ///
+    /// # Errors
+    ///
+    /// Returns error of first participant failed to prepare,
+    /// `TransactionError::Timeout` if deadline has passed
+    /// or `TransactionError::Persistence` if decision can not be recorded
+    pub async fn commit(self) -> Result<(), TransactionError> {
+        let id = self.id();
+        let votes = self
+            .within_deadline(join_all(self.participants.iter().map(|p| p.prepare(id))))
+            .await;
+        let (error, in_doubt) = match votes {
+            // participants may be prepared already when deadline passes
+            Err(e) => (e, self.participants),
+            Ok(votes) => {
+                let mut error = None;
+                let mut in_doubt = Vec::new();
+                for (participant, vote) in self.participants.into_iter().zip(votes) {
+                    // participant voted against commit has rolled back already,
+                    // undelivered vote may be positive
+                    if matches!(vote, Ok(()) | Err(TransactionError::Participant { .. })) {
+                        in_doubt.push(participant);
+                    }
+                    if let Err(e) = vote {
+                        error.get_or_insert(e);
+                    }
+                }
+                match error {
+                    Some(error) => (error, in_doubt),
+                    None => match self.log.decide(id, TransactionDecision::Commit).await {
+                        Ok(TransactionDecision::Commit) => {
+                            complete(id, in_doubt, TransactionDecision::Commit, self.log).await;
+                            return Ok(());
+                        }
+                        // aborted by `resolve_transactions` after deadline
+                        Ok(TransactionDecision::Abort) => (TransactionError::Timeout(id), in_doubt),
+                        Err(e) => (TransactionError::Persistence(e.to_string()), in_doubt),
+                    },
+                }
+            }
+        };
+        complete(id, in_doubt, TransactionDecision::Abort, self.log).await;
+        Err(error)
+    }
+
+    /// Rolls back transaction
+    pub async fn abort(mut self) {
+        self.rollback().await;
+    }
+
+    /// Requests all participants to discard their enlisted states,
+    /// unreachable participants roll back once deadline has passed
+    async fn rollback(&mut self) {
+        let id = self.id();
+        let results = join_all(self.participants.iter().map(|p| p.abort(id))).await;
+        for (participant, result) in self.participants.iter().zip(results) {
+            if let Err(e) = result {
+                eprintln!(
+                    "Participant {} failed to abort transaction {id}: {e}",
+                    participant.key()
+                );
+            }
+        }
+    }
+
+    async fn within_deadline<F: Future>(&self, future: F) -> Result<F::Output, TransactionError> {
+        let remaining = self
+            .scope
+            .deadline
+            .duration_since(SystemTime::now())
+            .unwrap_or_default();
+        tokio::time::timeout(remaining, future)
+            .await
+            .map_err(|_| TransactionError::Timeout(self.id()))
+    }
+}
+
+/// Sends decided outcome to participants until every one of them acknowledges it,
+/// participants which do not acknowledge it within `DELIVERY_ATTEMPTS` are retried by background task.
+/// Decision is removed from log once it is applied by every participant.
+async fn complete(
+    id: TransactionId,
+    mut pending: Vec<Box<dyn Participant>>,
+    decision: TransactionDecision,
+    log: Arc<dyn TransactionLog>,
+) {
+    let mut interval = RETRY_INTERVAL;
+    for attempt in 1..=DELIVERY_ATTEMPTS {
+        pending = send_decision(id, pending, decision).await;
+        if pending.is_empty() {
+            forget_decision(id, &log).await;
+            return;
+        }
+        if attempt < DELIVERY_ATTEMPTS {
+            tokio::time::sleep(interval).await;
+            interval = (interval * 2).min(MAX_RETRY_INTERVAL);
+        }
+    }
+    tokio::spawn(async move {
+        while !pending.is_empty() {
+            tokio::time::sleep(interval).await;
+            interval = (interval * 2).min(MAX_RETRY_INTERVAL);
+            pending = send_decision(id, pending, decision).await;
+        }
+        forget_decision(id, &log).await;
+    });
+}
+
+/// Sends decided outcome to participants, returns participants which did not acknowledge it.
+/// Participant which is not enlisted in transaction has applied outcome already.
+async fn send_decision(
+    id: TransactionId,
+    pending: Vec<Box<dyn Participant>>,
+    decision: TransactionDecision,
+) -> Vec<Box<dyn Participant>> {
+    let results = join_all(pending.iter().map(|p| match decision {
+        TransactionDecision::Commit => p.commit(id),
+        TransactionDecision::Abort => p.abort(id),
+    }))
+    .await;
+    pending
+        .into_iter()
+        .zip(results)
+        .filter_map(|(participant, result)| match result {
+            Ok(()) | Err(TransactionError::NotEnlisted(_)) => None,
+            Err(e) => {
+                eprintln!(
+                    "Participant {} failed to finish transaction {id}, retrying: {e}",
+                    participant.key()
+                );
+                Some(participant)
+            }
+        })
+        .collect()
+}
+
+async fn forget_decision(id: TransactionId, log: &Arc<dyn TransactionLog>) {
+    if let Err(e) = log.forget(id).await {
+        eprintln!("Failed to remove decision of transaction {id} from log: {e}");
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    io::ErrorKind,
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use dashmap::DashMap;
+use futures::future::{self, BoxFuture};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use virtual_actor_runtime::errors::BoxedActorError;
+
+use super::TransactionId;
+
+/// Extension of file with decision of transaction
+const DECISION_EXTENSION: &str = "decision";
+/// Extension of decision file which is being written
+const TMP_EXTENSION: &str = "decision.tmp";
+
+/// Outcome of transaction decided by coordinator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionDecision {
+    /// All participants voted for commit
+    Commit,
+    /// Transaction is rolled back
+    Abort,
+}
+
+/// Log of transaction decisions, keeps decided outcome until every participant has applied it,
+/// so participants prepared in transaction are finished after coordinator is stopped,
+/// see `resolve_transactions`
+pub trait TransactionLog: Send + Sync + 'static {
+    /// Records decision unless transaction is decided already, returns recorded decision
+    fn decide(
+        &self,
+        id: TransactionId,
+        decision: TransactionDecision,
+    ) -> BoxFuture<'_, Result<TransactionDecision, BoxedActorError>>;
+
+    /// Decision of transaction, `None` if transaction is not decided
+    fn decision(
+        &self,
+        id: TransactionId,
+    ) -> BoxFuture<'_, Result<Option<TransactionDecision>, BoxedActorError>>;
+
+    /// Removes decision applied by every participant
+    fn forget(&self, id: TransactionId) -> BoxFuture<'_, Result<(), BoxedActorError>>;
+}
+
+/// Transaction log stored in memory, decisions are lost on restart
+#[derive(Debug, Default)]
+pub struct InmemoryTransactionLog {
+    decisions: DashMap<TransactionId, TransactionDecision>,
+}
+
+impl InmemoryTransactionLog {
+    /// Create a new transaction log
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TransactionLog for InmemoryTransactionLog {
+    fn decide(
+        &self,
+        id: TransactionId,
+        decision: TransactionDecision,
+    ) -> BoxFuture<'_, Result<TransactionDecision, BoxedActorError>> {
+        let decision = *self.decisions.entry(id).or_insert(decision);
+        Box::pin(future::ready(Ok(decision)))
+    }
+
+    fn decision(
+        &self,
+        id: TransactionId,
+    ) -> BoxFuture<'_, Result<Option<TransactionDecision>, BoxedActorError>> {
+        let decision = self.decisions.get(&id).map(|d| *d);
+        Box::pin(future::ready(Ok(decision)))
+    }
+
+    fn forget(&self, id: TransactionId) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        self.decisions.remove(&id);
+        Box::pin(future::ready(Ok(())))
+    }
+}
+
+/// Transaction log stored in files
+///
+/// Decision of each transaction is stored in `<root>/<transaction id>.decision`.
+/// Decision is written to temporary file and linked to its final name,
+/// so it is never replaced and is never read partially.
+pub struct FileTransactionLog {
+    root: PathBuf,
+    /// Source of unique names of temporary files
+    last_tmp: AtomicU64,
+}
+
+impl FileTransactionLog {
+    /// Create a new transaction log stored in `root` directory
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            last_tmp: AtomicU64::new(0),
+        }
+    }
+
+    fn decision_path(&self, id: TransactionId) -> PathBuf {
+        self.root.join(format!("{id}.{DECISION_EXTENSION}"))
+    }
+
+    async fn write_decision(
+        &self,
+        id: TransactionId,
+        decision: TransactionDecision,
+    ) -> Result<TransactionDecision, BoxedActorError> {
+        tokio::fs::create_dir_all(&self.root)
+            .await
+            .map_err(BoxedActorError::new)?;
+        let tmp_path = self.root.join(format!(
+            "{id}.{}.{TMP_EXTENSION}",
+            self.last_tmp.fetch_add(1, Ordering::Relaxed)
+        ));
+        let bytes = bincode::serialize(&decision).map_err(BoxedActorError::new)?;
+        let mut file = tokio::fs::File::create(&tmp_path)
+            .await
+            .map_err(BoxedActorError::new)?;
+        file.write_all(&bytes).await.map_err(BoxedActorError::new)?;
+        file.sync_data().await.map_err(BoxedActorError::new)?;
+        drop(file);
+
+        // link fails if transaction is decided already, unlike rename
+        let linked = tokio::fs::hard_link(&tmp_path, self.decision_path(id)).await;
+        if let Err(e) = tokio::fs::remove_file(&tmp_path).await {
+            eprintln!("Failed to remove temporary decision of transaction {id}: {e}");
+        }
+        match linked {
+            Ok(()) => Ok(decision),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => self
+                .read_decision(id)
+                .await?
+                .ok_or_else(|| BoxedActorError::new(std::io::Error::from(ErrorKind::NotFound))),
+            Err(e) => Err(BoxedActorError::new(e)),
+        }
+    }
+
+    async fn read_decision(
+        &self,
+        id: TransactionId,
+    ) -> Result<Option<TransactionDecision>, BoxedActorError> {
+        match tokio::fs::read(self.decision_path(id)).await {
+            Ok(bytes) => bincode::deserialize(&bytes)
+                .map(Some)
+                .map_err(BoxedActorError::new),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(BoxedActorError::new(e)),
+        }
+    }
+}
+
+impl TransactionLog for FileTransactionLog {
+    fn decide(
+        &self,
+        id: TransactionId,
+        decision: TransactionDecision,
+    ) -> BoxFuture<'_, Result<TransactionDecision, BoxedActorError>> {
+        Box::pin(self.write_decision(id, decision))
+    }
+
+    fn decision(
+        &self,
+        id: TransactionId,
+    ) -> BoxFuture<'_, Result<Option<TransactionDecision>, BoxedActorError>> {
+        Box::pin(self.read_decision(id))
+    }
+
+    fn forget(&self, id: TransactionId) -> BoxFuture<'_, Result<(), BoxedActorError>> {
+        Box::pin(async move {
+            match tokio::fs::remove_file(self.decision_path(id)).await {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(BoxedActorError::new(e)),
+                _ => Ok(()),
+            }
+        })
+    }
+}
@@ -0,0 +1,129 @@
+//! Transactions across states of several actors
+
+mod coordinator;
+mod log;
+mod recovery;
+
+use std::{fmt, time::SystemTime};
+
+use serde::{Deserialize, Serialize};
+use virtual_actor_runtime::prelude::*;
+
+use super::actor_persistence_trait::ETag;
+
+pub use coordinator::{Transaction, TransactionCoordinator};
+pub use log::{FileTransactionLog, InmemoryTransactionLog, TransactionDecision, TransactionLog};
+pub use recovery::resolve_transactions;
+
+/// Transaction identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TransactionId(pub u64);
+
+impl fmt::Display for TransactionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Transaction passed to participants in transactional messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionScope {
+    /// Transaction identifier
+    pub id: TransactionId,
+    /// Transaction is rolled back if it is not finished until deadline
+    pub deadline: SystemTime,
+}
+
+impl TransactionScope {
+    /// Deadline has passed
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        SystemTime::now() >= self.deadline
+    }
+}
+
+/// Transaction error
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, Serialize, Deserialize)]
+pub enum TransactionError {
+    /// State is enlisted in another transaction
+    #[error("State is locked by transaction {holder}, transaction {transaction} is rejected")]
+    Conflict {
+        /// Rejected transaction
+        transaction: TransactionId,
+        /// Transaction holding state
+        holder: TransactionId,
+    },
+    /// State is not enlisted in transaction
+    #[error("State is not enlisted in transaction {0}")]
+    NotEnlisted(TransactionId),
+    /// Commit requested before state was prepared
+    #[error("State is not prepared in transaction {0}")]
+    NotPrepared(TransactionId),
+    /// State is prepared in transaction and can not be changed until transaction is finished
+    #[error("State is prepared in transaction {0}")]
+    Prepared(TransactionId),
+    /// State was changed outside of transaction after it was enlisted
+    #[error("State was changed outside of transaction {0}")]
+    StateChanged(TransactionId),
+    /// Transaction is not finished until deadline
+    #[error("Transaction {0} timed out")]
+    Timeout(TransactionId),
+    /// Transactional operation failed, transaction is rolled back
+    #[error("Transaction {transaction} is aborted: {reason}")]
+    Aborted {
+        /// Aborted transaction
+        transaction: TransactionId,
+        /// Error of failed operation
+        reason: String,
+    },
+    /// Participant is not reachable
+    #[error("Participant {participant} of transaction {transaction} failed: {error}")]
+    Participant {
+        /// Transaction
+        transaction: TransactionId,
+        /// Participant actor
+        participant: String,
+        /// Delivery error
+        error: String,
+    },
+    /// Persistence layer error
+    #[error("Persistence error: {0}")]
+    Persistence(String),
+}
+
+/// Requests participant to verify its enlisted state and vote for commit
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<(), TransactionError>)]
+pub struct PrepareTransaction(pub TransactionId);
+
+/// Requests participant to persist and apply its prepared state
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<(), TransactionError>)]
+pub struct CommitTransaction(pub TransactionId);
+
+/// Requests participant to discard its enlisted state
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<(), TransactionError>)]
+pub struct AbortTransaction(pub TransactionId);
+
+/// Prepared state of participant, persisted until transaction is finished,
+/// so decided outcome is applied by later activation if participant is deactivated or restarted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreparedTransaction {
+    /// Transaction state is prepared in
+    pub scope: TransactionScope,
+    /// Version of state when it was enlisted
+    pub etag: Option<ETag>,
+    /// State changed by transaction, serialized with bincode
+    pub staged: Vec<u8>,
+}
+
+/// State changed by transaction, kept aside until transaction is finished
+pub(crate) struct Enlistment<S> {
+    pub(crate) scope: TransactionScope,
+    pub(crate) staged: S,
+    /// Version of state when it was enlisted
+    pub(crate) etag: Option<ETag>,
+    /// Voted for commit, prepared enlistment is persisted and never expires
+    pub(crate) prepared: bool,
+}
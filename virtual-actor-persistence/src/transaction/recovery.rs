@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::*};
+
+use super::{
+    AbortTransaction, CommitTransaction, TransactionDecision, TransactionError, TransactionLog,
+};
+use crate::actor_persistence_trait::ActorPersistence;
+
+/// Finishes transactions left in doubt by stopped coordinator, call it once runtime is started.
+/// Returns number of finished participants.
+///
+/// Actors with prepared transaction stored in `persistence` are activated
+/// and receive outcome recorded in `log`.
+/// Transaction which is not decided until its deadline is aborted,
+/// abort is recorded first, so coordinator can not commit it later.
+/// Participants of transactions which are not decided yet are left prepared.
+/// Decisions are kept in log, other participants of transaction may be stored elsewhere.
+///
+/// # Errors
+///
+/// Returns error if prepared transactions can not be listed,
+/// log is not available or participant fails to apply outcome
+pub async fn resolve_transactions<A, S>(
+    runtime: &Runtime,
+    persistence: &Arc<dyn ActorPersistence<A, S>>,
+    log: &Arc<dyn TransactionLog>,
+) -> Result<usize, BoxedActorError>
+where
+    A: VirtualActor + MessageHandler<CommitTransaction> + MessageHandler<AbortTransaction>,
+    A::MessagesEnvelope:
+        MessageEnvelopeFactory<A, CommitTransaction> + MessageEnvelopeFactory<A, AbortTransaction>,
+    S: 'static,
+{
+    let prepared_transactions = persistence.prepared_transactions();
+    let mut resolved = 0;
+    for id in prepared_transactions.ids().await? {
+        let Some(prepared) = prepared_transactions.load(&id).await? else {
+            continue;
+        };
+        let transaction = prepared.scope.id;
+        let decision = match log.decision(transaction).await? {
+            Some(decision) => decision,
+            None if prepared.scope.is_expired() => {
+                log.decide(transaction, TransactionDecision::Abort).await?
+            }
+            None => continue,
+        };
+        let addr = runtime
+            .spawn_virtual::<A>(&id)
+            .await
+            .map_err(BoxedActorError::new)?;
+        let result = match decision {
+            TransactionDecision::Commit => addr.send(CommitTransaction(transaction)).await,
+            TransactionDecision::Abort => addr.send(AbortTransaction(transaction)).await,
+        }
+        .map_err(BoxedActorError::new)?;
+        match result {
+            Ok(()) | Err(TransactionError::NotEnlisted(_)) => resolved += 1,
+            Err(e) => return Err(BoxedActorError::new(e)),
+        }
+    }
+    Ok(resolved)
+}
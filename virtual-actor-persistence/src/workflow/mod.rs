@@ -0,0 +1,164 @@
+//! Durable workflows executed by virtual actors
+
+mod recovery;
+mod workflow_state;
+
+use std::time::Duration;
+
+use futures::future::{self, BoxFuture};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use virtual_actor_runtime::{prelude::*, RuntimeContext};
+
+pub use recovery::resume_workflows;
+pub use workflow_state::WorkflowState;
+
+use crate::actor_persistence_trait::SlotName;
+
+/// Slot of workflow persistence indexing active workflows
+const ACTIVE_WORKFLOWS_SLOT: SlotName = "active";
+
+/// Workflow definition, implemented by data of workflow
+///
+/// Steps are executed in order, one attempt per message,
+/// data changed by step is persisted once step completes.
+/// Step interrupted by restart is executed again, so steps have to be idempotent.
+pub trait Workflow<A: VirtualActor>:
+    Serialize + DeserializeOwned + Default + Send + Sync + 'static
+{
+    /// Names of steps in execution order
+    const STEPS: &'static [&'static str];
+
+    /// Executes step with index `step`, usually sends message to another actor.
+    /// Failed step is retried according to `StepRetryPolicy`.
+    fn execute<'a>(
+        &'a mut self,
+        step: usize,
+        ctx: &'a RuntimeContext<A>,
+    ) -> BoxFuture<'a, Result<(), String>>;
+
+    /// Undoes completed step with index `step`,
+    /// completed steps are compensated in reverse order once step fails permanently
+    fn compensate<'a>(
+        &'a mut self,
+        _step: usize,
+        _ctx: &'a RuntimeContext<A>,
+    ) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(future::ready(Ok(())))
+    }
+}
+
+/// Retries of failed step or compensation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepRetryPolicy {
+    /// Maximal number of attempts, including first one
+    pub max_attempts: u32,
+    /// Delay before second attempt, doubled for every next attempt
+    pub backoff: Duration,
+    /// Maximal delay between attempts
+    pub max_backoff: Duration,
+}
+
+impl Default for StepRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Status of workflow
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorkflowStatus {
+    /// Workflow is not started
+    #[default]
+    Pending,
+    /// Workflow executes step with index `step`
+    Running {
+        /// Index of next step
+        step: usize,
+    },
+    /// Step failed, completed steps are compensated
+    Compensating {
+        /// Number of completed steps which are not compensated yet
+        step: usize,
+        /// Error of failed step
+        error: String,
+    },
+    /// All steps are completed
+    Completed,
+    /// Step failed and completed steps are compensated
+    Compensated {
+        /// Error of failed step
+        error: String,
+    },
+    /// Compensation failed, workflow requires manual intervention
+    Failed {
+        /// Error of failed compensation
+        error: String,
+    },
+}
+
+impl WorkflowStatus {
+    /// Workflow is started and not finished yet
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Running { .. } | Self::Compensating { .. })
+    }
+}
+
+/// Outcome of step recorded in workflow history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StepOutcome {
+    /// Step is completed
+    Completed,
+    /// Step failed after all attempts
+    Failed(String),
+    /// Step is compensated
+    Compensated,
+    /// Compensation failed after all attempts
+    CompensationFailed(String),
+}
+
+/// Entry of workflow history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepEvent {
+    /// Name of step
+    pub step: String,
+    /// Number of attempts made
+    pub attempts: u32,
+    /// Outcome of step
+    pub outcome: StepOutcome,
+}
+
+/// Persisted state of workflow, written after every step
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowLog<W> {
+    /// Workflow data
+    pub data: W,
+    /// Current status
+    pub status: WorkflowStatus,
+    /// Finished steps and compensations
+    pub history: Vec<StepEvent>,
+}
+
+/// Workflow error
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, Serialize, Deserialize)]
+pub enum WorkflowError {
+    /// Workflow can be started once
+    #[error("Workflow is already started: {0:?}")]
+    AlreadyStarted(WorkflowStatus),
+    /// Persistence layer error, workflow is resumed from last persisted step
+    #[error("Persistence error: {0}")]
+    Persistence(String),
+}
+
+/// Continues workflow, sent by `resume_workflows` to interrupted workflows
+/// and by workflow itself to make next attempt of step
+#[derive(Debug, Default, Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<WorkflowStatus, WorkflowError>)]
+pub struct ResumeWorkflow {
+    /// Continuation scheduled by workflow, `None` if sent by other actors
+    continuation: Option<u64>,
+}
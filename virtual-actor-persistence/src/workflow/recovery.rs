@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::*};
+
+use super::{ResumeWorkflow, Workflow, WorkflowLog, ACTIVE_WORKFLOWS_SLOT};
+use crate::actor_persistence_trait::ActorPersistence;
+
+/// Resumes workflows interrupted by restart, call it once runtime is started.
+/// Returns number of resumed workflows.
+///
+/// Started workflows are indexed in slot of workflow persistence until they are finished,
+/// so workflow logs have to be stored in persistence which is not bound to another slot.
+/// Actors of indexed workflows are activated and receive `ResumeWorkflow`.
+///
+/// # Errors
+///
+/// Returns error if indexed workflows can not be listed or actor can not be activated
+pub async fn resume_workflows<A, W>(
+    runtime: &Runtime,
+    persistence: &Arc<dyn ActorPersistence<A, WorkflowLog<W>>>,
+) -> Result<usize, BoxedActorError>
+where
+    A: VirtualActor + MessageHandler<ResumeWorkflow>,
+    A::MessagesEnvelope: MessageEnvelopeFactory<A, ResumeWorkflow>,
+    W: Workflow<A>,
+{
+    let ids = persistence.slot(ACTIVE_WORKFLOWS_SLOT).ids().await?;
+    for id in &ids {
+        let addr = runtime
+            .spawn_virtual::<A>(id)
+            .await
+            .map_err(BoxedActorError::new)?;
+        addr.dispatch(ResumeWorkflow::default())
+            .await
+            .map_err(BoxedActorError::new)?;
+    }
+    Ok(ids.len())
+}
@@ -0,0 +1,272 @@
+use std::{sync::Arc, time::Duration};
+
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::*, RuntimeContext};
+
+use super::{
+    ResumeWorkflow, StepEvent, StepOutcome, StepRetryPolicy, Workflow, WorkflowError, WorkflowLog,
+    WorkflowStatus, ACTIVE_WORKFLOWS_SLOT,
+};
+use crate::actor_persistence_trait::ActorPersistence;
+use crate::actor_state::ActorState;
+
+/// Workflow executed by virtual actor, progress is persisted after every step
+///
+/// Actor starts workflow with `start` and handles `ResumeWorkflow` with `resume`.
+/// Every attempt of step runs in its own `ResumeWorkflow` message sent by workflow to its actor,
+/// so actor keeps handling other messages between steps and during backoff.
+/// Actor is held active until workflow is finished, workflow interrupted by deactivation
+/// is continued by next `ResumeWorkflow`, see `resume_workflows`.
+pub struct WorkflowState<A: VirtualActor, W: Workflow<A>> {
+    id: A::ActorId,
+    log: ActorState<A, WorkflowLog<W>>,
+    /// Index of active workflows, see `resume_workflows`
+    active: Arc<dyn ActorPersistence<A, WorkflowLog<W>>>,
+    retry_policy: StepRetryPolicy,
+    /// Attempts of current step or compensation made by this activation
+    attempts: u32,
+    /// Continuation which is sent to actor and not handled yet
+    scheduled: Option<u64>,
+    /// Number of continuations sent by this activation
+    continuations: u64,
+    /// Activation is held until workflow is finished
+    held: bool,
+}
+
+impl<A: VirtualActor, W: Workflow<A>> WorkflowState<A, W> {
+    /// Loads workflow log of actor
+    ///
+    /// # Errors
+    ///
+    /// Returns error from persistence layer
+    pub async fn load(
+        persistence: &Arc<dyn ActorPersistence<A, WorkflowLog<W>>>,
+        id: &A::ActorId,
+    ) -> Result<Self, BoxedActorError> {
+        Ok(Self {
+            id: id.clone(),
+            log: ActorState::load(persistence, id).await?,
+            active: persistence.slot(ACTIVE_WORKFLOWS_SLOT),
+            retry_policy: StepRetryPolicy::default(),
+            attempts: 0,
+            scheduled: None,
+            continuations: 0,
+            held: false,
+        })
+    }
+
+    /// Sets retries of failed steps and compensations
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry_policy: StepRetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Current status
+    pub fn status(&self) -> &WorkflowStatus {
+        &self.log.status
+    }
+
+    /// Workflow data
+    pub fn data(&self) -> &W {
+        &self.log.data
+    }
+
+    /// Finished steps and compensations
+    pub fn history(&self) -> &[StepEvent] {
+        &self.log.history
+    }
+}
+
+impl<A, W> WorkflowState<A, W>
+where
+    A: VirtualActor + MessageHandler<ResumeWorkflow>,
+    A::MessagesEnvelope: MessageEnvelopeFactory<A, ResumeWorkflow>,
+    W: Workflow<A>,
+{
+    /// Starts workflow with data, returns status of started workflow.
+    /// Steps are executed by `ResumeWorkflow` messages sent by workflow.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WorkflowError::AlreadyStarted` if workflow is not pending
+    /// or `WorkflowError::Persistence` if progress can not be saved
+    pub async fn start(
+        &mut self,
+        data: W,
+        ctx: &RuntimeContext<A>,
+    ) -> Result<WorkflowStatus, WorkflowError> {
+        if self.log.status != WorkflowStatus::Pending {
+            return Err(WorkflowError::AlreadyStarted(self.log.status.clone()));
+        }
+        // indexed before it is started, so started workflow is never missed by recovery
+        self.index().await?;
+        self.log.data = data;
+        self.log.status = settle::<A, W>(WorkflowStatus::Running { step: 0 });
+        self.save().await?;
+        self.proceed(ctx).await?;
+        Ok(self.log.status.clone())
+    }
+
+    /// Makes next attempt of step or compensation of started workflow,
+    /// call it from `ResumeWorkflow` handler. Returns current status.
+    ///
+    /// `ResumeWorkflow` sent by `resume_workflows` or other actors continues workflow
+    /// unless workflow has already scheduled its next attempt.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WorkflowError::Persistence` if progress can not be saved
+    pub async fn resume(
+        &mut self,
+        msg: ResumeWorkflow,
+        ctx: &RuntimeContext<A>,
+    ) -> Result<WorkflowStatus, WorkflowError> {
+        match (msg.continuation, self.scheduled) {
+            (Some(continuation), Some(scheduled)) if continuation == scheduled => {
+                self.scheduled = None;
+            }
+            // stale continuation or next attempt is already scheduled
+            (Some(_), _) | (None, Some(_)) => return Ok(self.log.status.clone()),
+            (None, None) => {}
+        }
+
+        let status = match self.log.status.clone() {
+            WorkflowStatus::Running { step } => match self.attempt(step, false, ctx).await {
+                Some(Ok(())) => {
+                    self.record(step, StepOutcome::Completed);
+                    WorkflowStatus::Running { step: step + 1 }
+                }
+                Some(Err(error)) => {
+                    self.record(step, StepOutcome::Failed(error.clone()));
+                    WorkflowStatus::Compensating { step, error }
+                }
+                None => return Ok(self.log.status.clone()),
+            },
+            WorkflowStatus::Compensating { step, error } => {
+                match self.attempt(step - 1, true, ctx).await {
+                    Some(Ok(())) => {
+                        self.record(step - 1, StepOutcome::Compensated);
+                        WorkflowStatus::Compensating {
+                            step: step - 1,
+                            error,
+                        }
+                    }
+                    Some(Err(e)) => {
+                        self.record(step - 1, StepOutcome::CompensationFailed(e.clone()));
+                        WorkflowStatus::Failed { error: e }
+                    }
+                    None => return Ok(self.log.status.clone()),
+                }
+            }
+            status => status,
+        };
+        if status != self.log.status {
+            self.log.status = settle::<A, W>(status);
+            self.save().await?;
+        }
+        self.proceed(ctx).await?;
+        Ok(self.log.status.clone())
+    }
+
+    /// Executes or compensates step once, failed attempt is retried after backoff.
+    /// Returns `None` if attempt is retried, last result otherwise.
+    async fn attempt(
+        &mut self,
+        step: usize,
+        compensate: bool,
+        ctx: &RuntimeContext<A>,
+    ) -> Option<Result<(), String>> {
+        self.attempts += 1;
+        let data = &mut self.log.data;
+        let result = if compensate {
+            data.compensate(step, ctx).await
+        } else {
+            data.execute(step, ctx).await
+        };
+        let policy = self.retry_policy;
+        if result.is_err() && self.attempts < policy.max_attempts {
+            let backoff = policy
+                .backoff
+                .saturating_mul(2u32.saturating_pow(self.attempts - 1))
+                .min(policy.max_backoff);
+            self.schedule(backoff, ctx);
+            return None;
+        }
+        Some(result)
+    }
+
+    /// Schedules next step of active workflow, removes finished workflow from index
+    async fn proceed(&mut self, ctx: &RuntimeContext<A>) -> Result<(), WorkflowError> {
+        if self.log.status.is_active() {
+            self.schedule(Duration::ZERO, ctx);
+            return Ok(());
+        }
+        self.active
+            .clear(&self.id)
+            .await
+            .map_err(|e| WorkflowError::Persistence(e.to_string()))?;
+        if std::mem::take(&mut self.held) {
+            ctx.release_activation();
+        }
+        Ok(())
+    }
+
+    /// Sends continuation to actor after `delay`
+    fn schedule(&mut self, delay: Duration, ctx: &RuntimeContext<A>) {
+        self.continuations += 1;
+        let continuation = self.continuations;
+        self.scheduled = Some(continuation);
+        if !std::mem::replace(&mut self.held, true) {
+            ctx.hold_activation();
+        }
+
+        let ctx = ctx.clone();
+        tokio::task::spawn_local(async move {
+            // continuation lost on deactivation is sent again by recovery
+            if ctx.sleep(delay).await.is_err() {
+                return;
+            }
+            let Some(addr) = ctx.self_addr().upgrade() else {
+                return;
+            };
+            let msg = ResumeWorkflow {
+                continuation: Some(continuation),
+            };
+            if let Err(e) = addr.dispatch(msg).await {
+                eprintln!("Failed to continue workflow of {}: {e:?}", A::name());
+            }
+        });
+    }
+
+    fn record(&mut self, step: usize, outcome: StepOutcome) {
+        let attempts = std::mem::take(&mut self.attempts);
+        self.log.history.push(StepEvent {
+            step: W::STEPS[step].to_string(),
+            attempts,
+            outcome,
+        });
+    }
+
+    async fn index(&self) -> Result<(), WorkflowError> {
+        self.active
+            .save(&self.id, &WorkflowLog::default())
+            .await
+            .map_err(|e| WorkflowError::Persistence(e.to_string()))
+    }
+
+    async fn save(&mut self) -> Result<(), WorkflowError> {
+        self.log
+            .save()
+            .await
+            .map_err(|e| WorkflowError::Persistence(e.to_string()))
+    }
+}
+
+/// Finishes workflow which has no steps left to execute or compensate
+fn settle<A: VirtualActor, W: Workflow<A>>(status: WorkflowStatus) -> WorkflowStatus {
+    match status {
+        WorkflowStatus::Running { step } if step >= W::STEPS.len() => WorkflowStatus::Completed,
+        WorkflowStatus::Compensating { step: 0, error } => WorkflowStatus::Compensated { error },
+        status => status,
+    }
+}
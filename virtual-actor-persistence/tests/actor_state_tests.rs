@@ -0,0 +1,69 @@
+use std::{sync::Arc, time::Duration};
+
+use virtual_actor_persistence::{prelude::*, InmemoryPersistence, PersistenceError};
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::counter_actor::{CounterActor, CounterActorFactory, Increment};
+
+mod actors;
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+#[tokio::test]
+async fn reactivated_actor_save_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<CounterActor>> = Arc::new(InmemoryPersistence::new());
+
+    for expected in 1..=2 {
+        let mut runtime = Runtime::new()?;
+        let executor = runtime.create_executor()?;
+        runtime.register_actor_with_factory(
+            CounterActorFactory::new(persistence.clone()),
+            &executor,
+        )?;
+
+        let addr: VirtualAddr<CounterActor> = runtime.spawn_virtual(&1).await?;
+        assert_eq!(
+            addr.send(Increment).await??,
+            expected,
+            "Reloaded state should be saved with its etag"
+        );
+
+        runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn concurrent_activations_save_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<CounterActor>> = Arc::new(InmemoryPersistence::new());
+
+    // two activations of the same actor, e.g. after split brain
+    let mut first = ActorState::<CounterActor>::load(&persistence, &1).await?;
+    let mut second = ActorState::<CounterActor>::load(&persistence, &1).await?;
+
+    *first += 1;
+    first.save().await?;
+    *first += 1;
+    first.save().await?;
+
+    *second += 10;
+    let err = second
+        .save()
+        .await
+        .expect_err("Stale activation should not overwrite state");
+    let err = err.downcast_error::<PersistenceError>()?;
+    assert!(
+        matches!(
+            *err,
+            PersistenceError::InconsistentState {
+                expected: None,
+                actual: Some(etag),
+            } if Some(etag) == first.etag()
+        ),
+        "Unexpected error: {err:?}"
+    );
+    assert_eq!(persistence.load(&1).await?, Some(2));
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use virtual_actor_persistence::{
+    prelude::*, AbortTransaction, CommitTransaction, PrepareTransaction, TransactionScope,
+};
+use virtual_actor_runtime::prelude::*;
+
+/// Non-transactional deposit, saved immediately
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<i64, String>)]
+pub struct Credit(pub i64);
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<i64, String>)]
+pub struct Withdraw {
+    pub tx: TransactionScope,
+    pub amount: i64,
+}
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<i64, String>)]
+pub struct Deposit {
+    pub tx: TransactionScope,
+    pub amount: i64,
+}
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(i64)]
+pub struct Balance;
+
+/// Clears persisted state of account
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<(), String>)]
+pub struct Close;
+
+/// Deactivates actor once its state is prepared, so transaction is committed by next activation
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(())]
+pub struct DeactivateAfterPrepare;
+
+#[derive(Actor, VirtualActor, ActorWithState)]
+#[message(Credit)]
+#[message(Withdraw)]
+#[message(Deposit)]
+#[message(Balance)]
+#[message(Close)]
+#[message(DeactivateAfterPrepare)]
+#[message(PrepareTransaction)]
+#[message(CommitTransaction)]
+#[message(AbortTransaction)]
+#[state_type(i64)]
+pub struct AccountActor {
+    id: u32,
+    state: ActorState<Self>,
+    deactivate_after_prepare: bool,
+}
+
+impl MessageHandler<Credit> for AccountActor {
+    async fn handle(
+        &mut self,
+        msg: Credit,
+        _ctx: &Self::ActorContext,
+    ) -> <Credit as Message>::Result {
+        *self.state += msg.0;
+        self.state.save().await.map_err(|e| e.to_string())?;
+        Ok(*self.state)
+    }
+}
+
+impl MessageHandler<Withdraw> for AccountActor {
+    async fn handle(
+        &mut self,
+        msg: Withdraw,
+        _ctx: &Self::ActorContext,
+    ) -> <Withdraw as Message>::Result {
+        let balance = self.state.enlist(msg.tx).map_err(|e| e.to_string())?;
+        if *balance < msg.amount {
+            return Err(format!("Insufficient funds: {balance}"));
+        }
+        *balance -= msg.amount;
+        Ok(*balance)
+    }
+}
+
+impl MessageHandler<Deposit> for AccountActor {
+    async fn handle(
+        &mut self,
+        msg: Deposit,
+        _ctx: &Self::ActorContext,
+    ) -> <Deposit as Message>::Result {
+        let balance = self.state.enlist(msg.tx).map_err(|e| e.to_string())?;
+        *balance += msg.amount;
+        Ok(*balance)
+    }
+}
+
+impl MessageHandler<Balance> for AccountActor {
+    async fn handle(
+        &mut self,
+        _msg: Balance,
+        _ctx: &Self::ActorContext,
+    ) -> <Balance as Message>::Result {
+        *self.state
+    }
+}
+
+impl MessageHandler<Close> for AccountActor {
+    async fn handle(
+        &mut self,
+        _msg: Close,
+        _ctx: &Self::ActorContext,
+    ) -> <Close as Message>::Result {
+        self.state.clear().await.map_err(|e| e.to_string())
+    }
+}
+
+impl MessageHandler<DeactivateAfterPrepare> for AccountActor {
+    async fn handle(
+        &mut self,
+        _msg: DeactivateAfterPrepare,
+        _ctx: &Self::ActorContext,
+    ) -> <DeactivateAfterPrepare as Message>::Result {
+        self.deactivate_after_prepare = true;
+    }
+}
+
+impl MessageHandler<PrepareTransaction> for AccountActor {
+    async fn handle(
+        &mut self,
+        msg: PrepareTransaction,
+        ctx: &Self::ActorContext,
+    ) -> <PrepareTransaction as Message>::Result {
+        self.state.prepare(msg.0, ctx).await?;
+        if self.deactivate_after_prepare {
+            ctx.deactivate_on_idle();
+        }
+        Ok(())
+    }
+}
+
+impl MessageHandler<CommitTransaction> for AccountActor {
+    async fn handle(
+        &mut self,
+        msg: CommitTransaction,
+        ctx: &Self::ActorContext,
+    ) -> <CommitTransaction as Message>::Result {
+        self.state.commit(msg.0, ctx).await
+    }
+}
+
+impl MessageHandler<AbortTransaction> for AccountActor {
+    async fn handle(
+        &mut self,
+        msg: AbortTransaction,
+        ctx: &Self::ActorContext,
+    ) -> <AbortTransaction as Message>::Result {
+        self.state.rollback(msg.0, ctx).await
+    }
+}
+
+impl PersistentActorConstructor for AccountActor {
+    fn new(id: &u32, state: ActorState<Self>) -> Self {
+        Self {
+            id: *id,
+            state,
+            deactivate_after_prepare: false,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use virtual_actor_persistence::prelude::*;
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::*};
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(u32)]
+pub struct Add(pub u32);
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(u32)]
+pub struct Get;
+
+#[derive(Actor, VirtualActor, ActorWithState)]
+#[message(Add)]
+#[message(Get)]
+#[after_message(save_state)]
+#[state_type(u32)]
+#[state_field(counter)]
+pub struct AutoSaveActor {
+    id: u32,
+    counter: ActorState<Self>,
+}
+
+impl AutoSaveActor {
+    async fn save_state(
+        &mut self,
+        ctx: &<Self as Actor>::ActorContext,
+    ) -> Result<(), BoxedActorError> {
+        self.counter.after_message(ctx).await
+    }
+}
+
+impl MessageHandler<Add> for AutoSaveActor {
+    async fn handle(&mut self, msg: Add, _ctx: &Self::ActorContext) -> <Add as Message>::Result {
+        *self.counter += msg.0;
+        *self.counter
+    }
+}
+
+impl MessageHandler<Get> for AutoSaveActor {
+    async fn handle(&mut self, _msg: Get, _ctx: &Self::ActorContext) -> <Get as Message>::Result {
+        *self.counter
+    }
+}
+
+impl PersistentActorConstructor for AutoSaveActor {
+    fn new(id: &u32, counter: ActorState<Self>) -> Self {
+        Self { id: *id, counter }
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use virtual_actor_persistence::prelude::*;
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::*};
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<u32, String>)]
+pub struct Increment;
+
+#[derive(Actor, VirtualActor)]
+#[message(Increment)]
+pub struct CounterActor {
+    id: u32,
+    state: ActorState<Self>,
+}
+
+impl ActorWithState for CounterActor {
+    type State = u32;
+
+    fn state(&self) -> &ActorState<Self> {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut ActorState<Self> {
+        &mut self.state
+    }
+}
+
+impl MessageHandler<Increment> for CounterActor {
+    async fn handle(
+        &mut self,
+        _msg: Increment,
+        _ctx: &Self::ActorContext,
+    ) -> <Increment as Message>::Result {
+        *self.state += 1;
+        self.state.save().await.map_err(|e| e.to_string())?;
+        Ok(*self.state)
+    }
+}
+
+pub struct CounterActorFactory {
+    persistence: Arc<dyn ActorPersistence<CounterActor>>,
+}
+
+impl CounterActorFactory {
+    pub fn new(persistence: Arc<dyn ActorPersistence<CounterActor>>) -> Self {
+        Self { persistence }
+    }
+}
+
+impl ActorFactory for CounterActorFactory {
+    type Actor = CounterActor;
+}
+
+impl VirtualActorFactory for CounterActorFactory {
+    type Error = BoxedActorError;
+
+    async fn create_actor(&self, id: &u32) -> Result<Self::Actor, Self::Error> {
+        let state = ActorState::load(&self.persistence, id).await?;
+        Ok(CounterActor { id: *id, state })
+    }
+
+    async fn deactivate_actor(&self, actor: &mut Self::Actor) -> Result<(), Self::Error> {
+        actor.state.flush().await
+    }
+}
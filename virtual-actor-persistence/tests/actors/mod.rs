@@ -0,0 +1,10 @@
+#![allow(clippy::no_effect_underscore_binding)]
+#![allow(dead_code)]
+
+pub mod account_actor;
+pub mod auto_save_actor;
+pub mod counter_actor;
+pub mod order_workflow_actor;
+pub mod profile_actor;
+pub mod service_actor;
+pub mod shopper_actor;
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use virtual_actor_persistence::{
+    prelude::*, ResumeWorkflow, StepEvent, StepRetryPolicy, Workflow, WorkflowError, WorkflowLog,
+    WorkflowState, WorkflowStatus,
+};
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::*, RuntimeContext};
+
+use super::service_actor::{Call, ServiceActor};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Order {
+    pub item: String,
+    pub amount: u32,
+}
+
+async fn call(
+    ctx: &RuntimeContext<OrderWorkflowActor>,
+    service: &str,
+    op: String,
+) -> Result<(), String> {
+    let addr = ctx
+        .get_or_create::<ServiceActor>(&service.to_string())
+        .await
+        .map_err(|e| e.to_string())?;
+    addr.send(Call(op)).await.map_err(|e| e.to_string())?
+}
+
+impl Workflow<OrderWorkflowActor> for Order {
+    const STEPS: &'static [&'static str] = &["reserve", "charge", "ship"];
+
+    fn execute<'a>(
+        &'a mut self,
+        step: usize,
+        ctx: &'a RuntimeContext<OrderWorkflowActor>,
+    ) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            match step {
+                0 => call(ctx, "inventory", format!("reserve {}", self.item)).await,
+                1 => call(ctx, "payment", format!("charge {}", self.amount)).await,
+                _ => call(ctx, "shipping", format!("ship {}", self.item)).await,
+            }
+        })
+    }
+
+    fn compensate<'a>(
+        &'a mut self,
+        step: usize,
+        ctx: &'a RuntimeContext<OrderWorkflowActor>,
+    ) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            match step {
+                0 => call(ctx, "inventory", format!("release {}", self.item)).await,
+                1 => call(ctx, "payment", format!("refund {}", self.amount)).await,
+                _ => Ok(()),
+            }
+        })
+    }
+}
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<WorkflowStatus, WorkflowError>)]
+pub struct PlaceOrder(pub Order);
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(WorkflowStatus)]
+pub struct GetStatus;
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Vec<StepEvent>)]
+pub struct GetHistory;
+
+#[derive(Actor, VirtualActor)]
+#[message(PlaceOrder)]
+#[message(ResumeWorkflow)]
+#[message(GetStatus)]
+#[message(GetHistory)]
+pub struct OrderWorkflowActor {
+    id: u32,
+    workflow: WorkflowState<Self, Order>,
+}
+
+impl MessageHandler<PlaceOrder> for OrderWorkflowActor {
+    async fn handle(
+        &mut self,
+        msg: PlaceOrder,
+        ctx: &Self::ActorContext,
+    ) -> <PlaceOrder as Message>::Result {
+        self.workflow.start(msg.0, ctx).await
+    }
+}
+
+impl MessageHandler<ResumeWorkflow> for OrderWorkflowActor {
+    async fn handle(
+        &mut self,
+        msg: ResumeWorkflow,
+        ctx: &Self::ActorContext,
+    ) -> <ResumeWorkflow as Message>::Result {
+        self.workflow.resume(msg, ctx).await
+    }
+}
+
+impl MessageHandler<GetStatus> for OrderWorkflowActor {
+    async fn handle(
+        &mut self,
+        _msg: GetStatus,
+        _ctx: &Self::ActorContext,
+    ) -> <GetStatus as Message>::Result {
+        self.workflow.status().clone()
+    }
+}
+
+impl MessageHandler<GetHistory> for OrderWorkflowActor {
+    async fn handle(
+        &mut self,
+        _msg: GetHistory,
+        _ctx: &Self::ActorContext,
+    ) -> <GetHistory as Message>::Result {
+        self.workflow.history().to_vec()
+    }
+}
+
+pub struct OrderWorkflowFactory {
+    pub persistence: Arc<dyn ActorPersistence<OrderWorkflowActor, WorkflowLog<Order>>>,
+    pub retry_policy: StepRetryPolicy,
+}
+
+impl ActorFactory for OrderWorkflowFactory {
+    type Actor = OrderWorkflowActor;
+}
+
+impl VirtualActorFactory for OrderWorkflowFactory {
+    type Error = BoxedActorError;
+
+    async fn create_actor(&self, id: &u32) -> Result<OrderWorkflowActor, Self::Error> {
+        let workflow = WorkflowState::load(&self.persistence, id)
+            .await?
+            .with_retry_policy(self.retry_policy);
+        Ok(OrderWorkflowActor { id: *id, workflow })
+    }
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use virtual_actor_persistence::{prelude::*, InitialVersion, MigrationPolicy};
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::*};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileV1 {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileV2 {
+    pub name: String,
+    pub visits: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileV3 {
+    pub first_name: String,
+    pub last_name: String,
+    pub visits: u64,
+}
+
+impl StateVersion for ProfileV1 {
+    const VERSION: u32 = 1;
+    type Previous = InitialVersion;
+}
+
+impl StateVersion for ProfileV2 {
+    const VERSION: u32 = 2;
+    type Previous = ProfileV1;
+}
+
+impl StateVersion for ProfileV3 {
+    const VERSION: u32 = 3;
+    type Previous = ProfileV2;
+}
+
+impl Migrate<ProfileV1> for ProfileV2 {
+    fn migrate(from: ProfileV1) -> Self {
+        Self {
+            name: from.name,
+            visits: 0,
+        }
+    }
+}
+
+impl Migrate<ProfileV2> for ProfileV3 {
+    fn migrate(from: ProfileV2) -> Self {
+        let (first_name, last_name) = from
+            .name
+            .split_once(' ')
+            .map(|(first, last)| (first.to_string(), last.to_string()))
+            .unwrap_or((from.name, String::new()));
+        Self {
+            first_name,
+            last_name,
+            visits: u64::from(from.visits),
+        }
+    }
+}
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<ProfileV3, String>)]
+pub struct Visit;
+
+#[derive(Actor, VirtualActor)]
+#[message(Visit)]
+pub struct ProfileActor {
+    id: u32,
+    state: ActorState<Self>,
+}
+
+impl ActorWithState for ProfileActor {
+    type State = Versioned<ProfileV3>;
+
+    fn state(&self) -> &ActorState<Self> {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut ActorState<Self> {
+        &mut self.state
+    }
+}
+
+impl MessageHandler<Visit> for ProfileActor {
+    async fn handle(
+        &mut self,
+        _msg: Visit,
+        _ctx: &Self::ActorContext,
+    ) -> <Visit as Message>::Result {
+        self.state.visits += 1;
+        self.state.save().await.map_err(|e| e.to_string())?;
+        Ok(ProfileV3::clone(&self.state))
+    }
+}
+
+pub struct ProfileActorFactory {
+    persistence: Arc<dyn ActorPersistence<ProfileActor>>,
+    policy: MigrationPolicy,
+}
+
+impl ProfileActorFactory {
+    pub fn new(
+        persistence: Arc<dyn ActorPersistence<ProfileActor>>,
+        policy: MigrationPolicy,
+    ) -> Self {
+        Self {
+            persistence,
+            policy,
+        }
+    }
+}
+
+impl ActorFactory for ProfileActorFactory {
+    type Actor = ProfileActor;
+}
+
+impl VirtualActorFactory for ProfileActorFactory {
+    type Error = BoxedActorError;
+
+    async fn create_actor(&self, id: &u32) -> Result<Self::Actor, Self::Error> {
+        let state = ActorState::load_with_policy(&self.persistence, id, self.policy).await?;
+        Ok(ProfileActor { id: *id, state })
+    }
+
+    async fn deactivate_actor(&self, actor: &mut Self::Actor) -> Result<(), Self::Error> {
+        actor.state.flush().await
+    }
+}
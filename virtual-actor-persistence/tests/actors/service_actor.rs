@@ -0,0 +1,59 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use virtual_actor_runtime::prelude::*;
+
+/// Successful calls of all services
+pub type Journal = Arc<Mutex<Vec<String>>>;
+
+/// Number of failures of operation before it succeeds
+pub type Failures = Arc<Mutex<HashMap<String, u32>>>;
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<(), String>)]
+pub struct Call(pub String);
+
+/// Remote service called by workflow steps
+#[derive(Actor, VirtualActor)]
+#[message(Call)]
+pub struct ServiceActor {
+    id: String,
+    journal: Journal,
+    failures: Failures,
+}
+
+impl MessageHandler<Call> for ServiceActor {
+    async fn handle(&mut self, msg: Call, _ctx: &Self::ActorContext) -> <Call as Message>::Result {
+        let mut failures = self.failures.lock().map_err(|e| e.to_string())?;
+        if let Some(remaining) = failures.get_mut(&msg.0).filter(|r| **r > 0) {
+            *remaining -= 1;
+            return Err(format!("{} failed {}", self.id, msg.0));
+        }
+        self.journal.lock().map_err(|e| e.to_string())?.push(msg.0);
+        Ok(())
+    }
+}
+
+pub struct ServiceActorFactory {
+    pub journal: Journal,
+    pub failures: Failures,
+}
+
+impl ActorFactory for ServiceActorFactory {
+    type Actor = ServiceActor;
+}
+
+impl VirtualActorFactory for ServiceActorFactory {
+    type Error = std::convert::Infallible;
+
+    async fn create_actor(&self, id: &String) -> Result<ServiceActor, Self::Error> {
+        Ok(ServiceActor {
+            id: id.clone(),
+            journal: self.journal.clone(),
+            failures: self.failures.clone(),
+        })
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use virtual_actor_persistence::prelude::*;
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::*};
+
+pub const PROFILE_SLOT: &str = "profile";
+pub const CART_SLOT: &str = "cart";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cart {
+    pub items: Vec<String>,
+}
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<(), String>)]
+pub struct Rename(pub String);
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<usize, String>)]
+pub struct AddItem(pub String);
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result((Profile, Cart))]
+pub struct GetShopper;
+
+#[derive(Actor, VirtualActor)]
+#[message(Rename)]
+#[message(AddItem)]
+#[message(GetShopper)]
+pub struct ShopperActor {
+    id: u32,
+    profile: ActorState<Self, Profile>,
+    cart: ActorState<Self, Cart>,
+}
+
+impl MessageHandler<Rename> for ShopperActor {
+    async fn handle(
+        &mut self,
+        msg: Rename,
+        _ctx: &Self::ActorContext,
+    ) -> <Rename as Message>::Result {
+        self.profile.name = msg.0;
+        self.profile.save().await.map_err(|e| e.to_string())
+    }
+}
+
+impl MessageHandler<AddItem> for ShopperActor {
+    async fn handle(
+        &mut self,
+        msg: AddItem,
+        _ctx: &Self::ActorContext,
+    ) -> <AddItem as Message>::Result {
+        self.cart.items.push(msg.0);
+        self.cart.save().await.map_err(|e| e.to_string())?;
+        Ok(self.cart.items.len())
+    }
+}
+
+impl MessageHandler<GetShopper> for ShopperActor {
+    async fn handle(
+        &mut self,
+        _msg: GetShopper,
+        _ctx: &Self::ActorContext,
+    ) -> <GetShopper as Message>::Result {
+        (Profile::clone(&self.profile), Cart::clone(&self.cart))
+    }
+}
+
+pub struct ShopperActorFactory {
+    profiles: Arc<dyn ActorPersistence<ShopperActor, Profile>>,
+    carts: Arc<dyn ActorPersistence<ShopperActor, Cart>>,
+}
+
+impl ShopperActorFactory {
+    pub fn new(
+        profiles: Arc<dyn ActorPersistence<ShopperActor, Profile>>,
+        carts: Arc<dyn ActorPersistence<ShopperActor, Cart>>,
+    ) -> Self {
+        Self { profiles, carts }
+    }
+}
+
+impl ActorFactory for ShopperActorFactory {
+    type Actor = ShopperActor;
+}
+
+impl VirtualActorFactory for ShopperActorFactory {
+    type Error = BoxedActorError;
+
+    async fn create_actor(&self, id: &u32) -> Result<Self::Actor, Self::Error> {
+        Ok(ShopperActor {
+            id: *id,
+            profile: ActorState::load_slot(&self.profiles, id, PROFILE_SLOT).await?,
+            cart: ActorState::load_slot(&self.carts, id, CART_SLOT).await?,
+        })
+    }
+
+    async fn deactivate_actor(&self, actor: &mut Self::Actor) -> Result<(), Self::Error> {
+        actor.profile.flush().await?;
+        actor.cart.flush().await
+    }
+}
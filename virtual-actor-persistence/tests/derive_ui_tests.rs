@@ -0,0 +1,5 @@
+#[test]
+fn actor_with_state_derive_errors_test() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,318 @@
+use std::{sync::Arc, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use virtual_actor_persistence::{
+    prelude::*, FileJournal, InmemoryJournal, InmemoryPersistence, JournalError,
+};
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<(), String>)]
+pub struct Deposit(pub u32);
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result((u32, u64))]
+pub struct GetBalance;
+
+#[derive(Serialize, Deserialize)]
+pub enum AccountEvent {
+    Deposited(u32),
+    Withdrawn(u32),
+}
+
+#[derive(Actor, VirtualActor)]
+#[message(Deposit)]
+#[message(GetBalance)]
+pub struct AccountActor {
+    id: u32,
+    balance: EventSourcedState<Self>,
+}
+
+impl EventSourcedActor for AccountActor {
+    type State = u32;
+    type Event = AccountEvent;
+
+    fn apply(state: &mut Self::State, event: &Self::Event) {
+        match event {
+            AccountEvent::Deposited(amount) => *state += amount,
+            AccountEvent::Withdrawn(amount) => *state -= amount,
+        }
+    }
+
+    fn recovered(id: &Self::ActorId, balance: EventSourcedState<Self>) -> Self {
+        Self { id: *id, balance }
+    }
+
+    fn state_mut(&mut self) -> &mut EventSourcedState<Self> {
+        &mut self.balance
+    }
+}
+
+impl MessageHandler<Deposit> for AccountActor {
+    async fn handle(
+        &mut self,
+        msg: Deposit,
+        _ctx: &Self::ActorContext,
+    ) -> <Deposit as Message>::Result {
+        self.balance
+            .persist(AccountEvent::Deposited(msg.0))
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl MessageHandler<GetBalance> for AccountActor {
+    async fn handle(
+        &mut self,
+        _msg: GetBalance,
+        _ctx: &Self::ActorContext,
+    ) -> <GetBalance as Message>::Result {
+        (*self.balance, self.balance.sequence())
+    }
+}
+
+async fn run_account(
+    journal: &Arc<dyn EventJournal<AccountActor>>,
+    deposits: &[u32],
+) -> Result<(u32, u64), Box<dyn std::error::Error>> {
+    run_account_with_factory(EventSourcedActorFactory::new(journal.clone()), deposits).await
+}
+
+async fn run_account_with_factory(
+    factory: EventSourcedActorFactory<AccountActor>,
+    deposits: &[u32],
+) -> Result<(u32, u64), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor_with_factory(factory, &executor)?;
+
+    let addr: VirtualAddr<AccountActor> = runtime.spawn_virtual(&1).await?;
+    for amount in deposits {
+        addr.send(Deposit(*amount)).await??;
+    }
+    let balance = addr.send(GetBalance).await?;
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(balance)
+}
+
+#[tokio::test]
+async fn inmemory_journal_recovery_test() -> Result<(), Box<dyn std::error::Error>> {
+    let journal: Arc<dyn EventJournal<AccountActor>> = Arc::new(InmemoryJournal::new());
+
+    assert_eq!(run_account(&journal, &[10, 20]).await?, (30, 2));
+    assert_eq!(
+        run_account(&journal, &[5]).await?,
+        (35, 3),
+        "State should be recovered from journal"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_journal_recovery_test() -> Result<(), Box<dyn std::error::Error>> {
+    let root = std::env::temp_dir().join(format!("file_journal_test_{}", std::process::id()));
+    let journal: Arc<dyn EventJournal<AccountActor>> = Arc::new(FileJournal::new(&root));
+
+    assert_eq!(run_account(&journal, &[10, 20]).await?, (30, 2));
+
+    // new journal instance reads events written by previous one
+    let journal: Arc<dyn EventJournal<AccountActor>> = Arc::new(FileJournal::new(&root));
+    assert_eq!(
+        run_account(&journal, &[5]).await?,
+        (35, 3),
+        "State should be recovered from journal file"
+    );
+
+    let events = journal.read(&1, 3).await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].sequence, 3);
+
+    journal.clear(&1).await?;
+    assert_eq!(run_account(&journal, &[]).await?, (0, 0));
+
+    tokio::fs::remove_dir_all(&root).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_journal_partial_record_test() -> Result<(), Box<dyn std::error::Error>> {
+    let root = std::env::temp_dir().join(format!("partial_journal_test_{}", std::process::id()));
+    let journal: Arc<dyn EventJournal<AccountActor>> = Arc::new(FileJournal::new(&root));
+    assert_eq!(run_account(&journal, &[10, 20]).await?, (30, 2));
+
+    // simulate crash in the middle of last append
+    let dir = std::fs::read_dir(&root)?
+        .next()
+        .expect("Actor directory")?
+        .path();
+    let path = std::fs::read_dir(dir)?
+        .next()
+        .expect("Journal file")?
+        .path();
+    let len = std::fs::metadata(&path)?.len();
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(&path)?
+        .set_len(len - 3)?;
+
+    let journal: Arc<dyn EventJournal<AccountActor>> = Arc::new(FileJournal::new(&root));
+    assert_eq!(
+        run_account(&journal, &[5]).await?,
+        (15, 2),
+        "Partial record should be dropped and journal should stay writable"
+    );
+    let events = journal.read(&1, 0).await?;
+    assert_eq!(
+        events.iter().map(|e| e.sequence).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+
+    tokio::fs::remove_dir_all(&root).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_journal_partial_batch_test() -> Result<(), Box<dyn std::error::Error>> {
+    let root = std::env::temp_dir().join(format!("partial_batch_test_{}", std::process::id()));
+    let journal: Arc<dyn EventJournal<AccountActor>> = Arc::new(FileJournal::new(&root));
+    journal
+        .append(&1, 0, &[AccountEvent::Deposited(10)])
+        .await?;
+    let events = [1, 2, 3].map(AccountEvent::Deposited);
+    assert_eq!(journal.append(&1, 1, &events).await?, 4);
+
+    // simulate crash after first events of batch are written
+    let dir = std::fs::read_dir(&root)?
+        .next()
+        .expect("Actor directory")?
+        .path();
+    let path = std::fs::read_dir(dir)?
+        .next()
+        .expect("Journal file")?
+        .path();
+    let last_record = bincode::serialize(&AccountEvent::Deposited(3))?.len() + 4;
+    let len = std::fs::metadata(&path)?.len();
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(&path)?
+        .set_len(len - last_record as u64)?;
+
+    let journal: Arc<dyn EventJournal<AccountActor>> = Arc::new(FileJournal::new(&root));
+    let events = journal.read(&1, 0).await?;
+    assert_eq!(
+        events.iter().map(|e| e.sequence).collect::<Vec<_>>(),
+        vec![1],
+        "Incomplete batch should be dropped as a whole"
+    );
+    assert_eq!(run_account(&journal, &[5]).await?, (15, 2));
+
+    tokio::fs::remove_dir_all(&root).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn journal_sequence_mismatch_test() -> Result<(), Box<dyn std::error::Error>> {
+    let journal: Arc<dyn EventJournal<AccountActor>> = Arc::new(InmemoryJournal::new());
+
+    let sequence = journal
+        .append(&1, 0, &[AccountEvent::Deposited(10)])
+        .await?;
+    assert_eq!(sequence, 1);
+
+    let err = journal
+        .append(&1, 0, &[AccountEvent::Withdrawn(10)])
+        .await
+        .expect_err("Stale writer should be rejected");
+    let err = err.downcast_error::<JournalError>()?;
+    assert!(
+        matches!(
+            *err,
+            JournalError::SequenceMismatch {
+                expected: 0,
+                actual: 1
+            }
+        ),
+        "Unexpected error: {err:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn snapshot_every_events_test() -> Result<(), Box<dyn std::error::Error>> {
+    let root = std::env::temp_dir().join(format!("snapshot_journal_test_{}", std::process::id()));
+    let journal: Arc<dyn EventJournal<AccountActor>> = Arc::new(FileJournal::new(&root));
+    let snapshots: Arc<dyn ActorPersistence<AccountActor, Snapshot<u32>>> =
+        Arc::new(InmemoryPersistence::new());
+    let policy = SnapshotPolicy {
+        every_events: Some(2),
+        truncate_journal: true,
+        ..Default::default()
+    };
+
+    let factory = EventSourcedActorFactory::with_snapshots(
+        journal.clone(),
+        snapshots.clone(),
+        policy.clone(),
+    );
+    assert_eq!(
+        run_account_with_factory(factory, &[10, 20, 30]).await?,
+        (60, 3)
+    );
+
+    let snapshot = snapshots.load(&1).await?.expect("Snapshot should be taken");
+    assert_eq!((snapshot.sequence, snapshot.state), (2, 30));
+    let events = journal.read(&1, 1).await?;
+    assert_eq!(
+        events.iter().map(|e| e.sequence).collect::<Vec<_>>(),
+        vec![3],
+        "Journal should be truncated up to snapshot"
+    );
+
+    // recovery loads snapshot and replays the tail
+    let factory = EventSourcedActorFactory::with_snapshots(journal.clone(), snapshots, policy);
+    assert_eq!(run_account_with_factory(factory, &[1]).await?, (61, 4));
+
+    let events = journal.read(&1, 1).await?;
+    assert!(events.is_empty(), "Journal should be truncated");
+
+    tokio::fs::remove_dir_all(&root).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn snapshot_on_deactivation_test() -> Result<(), Box<dyn std::error::Error>> {
+    let journal: Arc<dyn EventJournal<AccountActor>> = Arc::new(InmemoryJournal::new());
+    let snapshots: Arc<dyn ActorPersistence<AccountActor, Snapshot<u32>>> =
+        Arc::new(InmemoryPersistence::new());
+    let policy = SnapshotPolicy {
+        on_deactivation: true,
+        ..Default::default()
+    };
+
+    let factory = EventSourcedActorFactory::with_snapshots(
+        journal.clone(),
+        snapshots.clone(),
+        policy.clone(),
+    );
+    assert_eq!(run_account_with_factory(factory, &[10, 20]).await?, (30, 2));
+
+    let snapshot = snapshots.load(&1).await?.expect("Snapshot should be taken");
+    assert_eq!((snapshot.sequence, snapshot.state), (2, 30));
+    assert_eq!(
+        journal.read(&1, 1).await?.len(),
+        2,
+        "Journal should not be truncated"
+    );
+
+    Ok(())
+}
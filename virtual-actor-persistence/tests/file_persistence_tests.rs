@@ -0,0 +1,116 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use virtual_actor_persistence::{
+    prelude::*, FilePersistence, FilePersistencePreferences, FsyncPolicy, KeyEncoding,
+    PersistenceError,
+};
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::counter_actor::{CounterActor, CounterActorFactory, Increment};
+
+mod actors;
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+fn test_root(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{name}_{}", std::process::id()))
+}
+
+#[tokio::test]
+async fn file_persistence_restart_test() -> Result<(), Box<dyn std::error::Error>> {
+    let root = test_root("file_persistence_restart_test");
+    let preferences = FilePersistencePreferences {
+        key_encoding: KeyEncoding::Display,
+        fsync: FsyncPolicy::Data,
+    };
+
+    for expected in 1..=2 {
+        let persistence: Arc<dyn ActorPersistence<CounterActor>> = Arc::new(
+            FilePersistence::with_preferences(&root, preferences.clone()),
+        );
+        let mut runtime = Runtime::new()?;
+        let executor = runtime.create_executor()?;
+        runtime.register_actor_with_factory(CounterActorFactory::new(persistence), &executor)?;
+
+        let addr: VirtualAddr<CounterActor> = runtime.spawn_virtual(&42).await?;
+        assert_eq!(
+            addr.send(Increment).await??,
+            expected,
+            "State should survive restart"
+        );
+
+        runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    }
+
+    assert!(
+        root.join("CounterActor").join("42.state").exists(),
+        "State should be stored in actor type directory"
+    );
+
+    tokio::fs::remove_dir_all(&root).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_persistence_partial_write_test() -> Result<(), Box<dyn std::error::Error>> {
+    let root = test_root("file_persistence_partial_write_test");
+    let persistence: Arc<dyn ActorPersistence<CounterActor>> =
+        Arc::new(FilePersistence::new(&root));
+
+    persistence.save(&1, &5).await?;
+
+    // write interrupted by crash leaves temporary file
+    let state_path = std::fs::read_dir(root.join("CounterActor"))?
+        .next()
+        .expect("State file should exist")?
+        .path();
+    let tmp_path = state_path.with_extension("state.tmp");
+    tokio::fs::write(&tmp_path, [0xff]).await?;
+
+    assert_eq!(persistence.load(&1).await?, Some(5));
+    assert!(!tmp_path.exists(), "Partial write should be removed");
+
+    persistence.clear(&1).await?;
+    assert_eq!(persistence.load(&1).await?, None);
+
+    tokio::fs::remove_dir_all(&root).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_persistence_inconsistent_state_test() -> Result<(), Box<dyn std::error::Error>> {
+    let root = test_root("file_persistence_inconsistent_state_test");
+    let persistence: Arc<dyn ActorPersistence<CounterActor>> =
+        Arc::new(FilePersistence::new(&root));
+
+    let mut first = ActorState::<CounterActor>::load(&persistence, &1).await?;
+    let mut second = ActorState::<CounterActor>::load(&persistence, &1).await?;
+
+    *first += 1;
+    first.save().await?;
+
+    *second += 1;
+    let err = second
+        .save()
+        .await
+        .expect_err("Stale activation should not overwrite state");
+    assert!(
+        matches!(
+            *err.downcast_error::<PersistenceError>()?,
+            PersistenceError::InconsistentState { expected: None, .. }
+        ),
+        "Unexpected error"
+    );
+
+    // reloaded state is saved
+    let mut reloaded = ActorState::<CounterActor>::load(&persistence, &1).await?;
+    *reloaded += 1;
+    reloaded.save().await?;
+    assert_eq!(persistence.load(&1).await?, Some(2));
+
+    tokio::fs::remove_dir_all(&root).await?;
+
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use virtual_actor_persistence::{
+    prelude::*, Compression, CompressionAlgorithm, Encryption, EncryptionKey, InmemoryPersistence,
+    LayeredPersistence, StaticKeys,
+};
+
+use crate::actors::counter_actor::CounterActor;
+
+mod actors;
+
+const SECRET: &str = "very secret note";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Notes {
+    notes: Vec<String>,
+}
+
+fn notes(count: usize) -> Notes {
+    Notes {
+        notes: vec![SECRET.to_string(); count],
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[tokio::test]
+async fn compression_test() -> Result<(), Box<dyn std::error::Error>> {
+    for algorithm in [CompressionAlgorithm::default(), CompressionAlgorithm::Lz4] {
+        let inner: Arc<dyn ActorPersistence<CounterActor, Vec<u8>>> =
+            Arc::new(InmemoryPersistence::new());
+        let persistence =
+            LayeredPersistence::new(inner.clone()).with_layer(Compression::new(algorithm, 64));
+
+        persistence.save(&1, &notes(100)).await?;
+        persistence.save(&2, &notes(1)).await?;
+        assert_eq!(persistence.load(&1).await?, Some(notes(100)));
+        assert_eq!(persistence.load(&2).await?, Some(notes(1)));
+
+        let large = inner
+            .load(&1)
+            .await?
+            .ok_or("Large state should be stored")?;
+        assert!(
+            large.len() < SECRET.len() * 10,
+            "Large state should be compressed with {algorithm:?}"
+        );
+        let small = inner
+            .load(&2)
+            .await?
+            .ok_or("Small state should be stored")?;
+        assert!(
+            contains(&small, SECRET.as_bytes()),
+            "State below threshold should be stored as is"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn encryption_test() -> Result<(), Box<dyn std::error::Error>> {
+    let inner: Arc<dyn ActorPersistence<CounterActor, Vec<u8>>> =
+        Arc::new(InmemoryPersistence::new());
+    let keys = Arc::new(StaticKeys::new(1, EncryptionKey::generate()));
+    let persistence =
+        LayeredPersistence::new(inner.clone()).with_layer(Encryption::new(keys.clone()));
+
+    persistence.save(&1, &notes(1)).await?;
+    assert_eq!(persistence.load(&1).await?, Some(notes(1)));
+    let stored = inner.load(&1).await?.ok_or("State should be stored")?;
+    assert!(
+        !contains(&stored, SECRET.as_bytes()),
+        "State should not be stored as plain text"
+    );
+
+    // state of one actor can not be moved to another
+    inner.save(&2, &stored).await?;
+    assert!(
+        ActorPersistence::<CounterActor, Notes>::load(&persistence, &2)
+            .await
+            .is_err(),
+        "State should be bound to actor id"
+    );
+
+    let mut tampered = stored.clone();
+    if let Some(byte) = tampered.last_mut() {
+        *byte ^= 1;
+    }
+    inner.save(&1, &tampered).await?;
+    assert!(
+        ActorPersistence::<CounterActor, Notes>::load(&persistence, &1)
+            .await
+            .is_err(),
+        "Tampered state should be rejected"
+    );
+
+    let other_keys = Arc::new(StaticKeys::new(1, EncryptionKey::generate()));
+    let other = LayeredPersistence::new(inner.clone()).with_layer(Encryption::new(other_keys));
+    inner.save(&1, &stored).await?;
+    assert!(
+        ActorPersistence::<CounterActor, Notes>::load(&other, &1)
+            .await
+            .is_err(),
+        "State should not be decrypted with another key"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn key_rotation_test() -> Result<(), Box<dyn std::error::Error>> {
+    let inner: Arc<dyn ActorPersistence<CounterActor, Vec<u8>>> =
+        Arc::new(InmemoryPersistence::new());
+    let keys = Arc::new(StaticKeys::new(1, EncryptionKey::generate()));
+    let persistence =
+        LayeredPersistence::new(inner.clone()).with_layer(Encryption::new(keys.clone()));
+
+    persistence.save(&1, &notes(1)).await?;
+    keys.rotate(2, EncryptionKey::generate());
+    assert_eq!(
+        persistence.load(&1).await?,
+        Some(notes(1)),
+        "State encrypted with previous key should be readable"
+    );
+
+    persistence.save(&1, &notes(2)).await?;
+    let stored = inner.load(&1).await?.ok_or("State should be stored")?;
+    assert_eq!(
+        stored.get(1..5),
+        Some(&2u32.to_le_bytes()[..]),
+        "State should be re-encrypted with current key"
+    );
+
+    keys.retire(1);
+    assert_eq!(persistence.load(&1).await?, Some(notes(2)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn composed_layers_test() -> Result<(), Box<dyn std::error::Error>> {
+    let inner: Arc<dyn ActorPersistence<CounterActor, Vec<u8>>> =
+        Arc::new(InmemoryPersistence::new());
+    let keys = Arc::new(StaticKeys::new(1, EncryptionKey::generate()));
+    let persistence = LayeredPersistence::new(inner.clone())
+        .with_layer(Compression::new(CompressionAlgorithm::default(), 64))
+        .with_layer(Encryption::new(keys));
+
+    persistence.save(&1, &notes(100)).await?;
+    assert_eq!(persistence.load(&1).await?, Some(notes(100)));
+    let stored = inner.load(&1).await?.ok_or("State should be stored")?;
+    assert!(
+        stored.len() < SECRET.len() * 10,
+        "State should be compressed before encryption"
+    );
+
+    let slot = ActorPersistence::<CounterActor, Notes>::slot(&persistence, "notes");
+    assert_eq!(
+        slot.load(&1).await?,
+        None,
+        "Slot should be stored separately"
+    );
+    slot.save(&1, &notes(1)).await?;
+    assert_eq!(slot.load(&1).await?, Some(notes(1)));
+    assert_eq!(persistence.load(&1).await?, Some(notes(100)));
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use std::{sync::Arc, time::Duration};
+
+use virtual_actor_persistence::{prelude::*, InmemoryPersistence};
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::auto_save_actor::{Add, AutoSaveActor};
+
+mod actors;
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+#[tokio::test]
+async fn persistent_actor_restart_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<AutoSaveActor>> =
+        Arc::new(InmemoryPersistence::new());
+
+    for expected in [3, 6] {
+        let mut runtime = Runtime::new()?;
+        let executor = runtime.create_executor()?;
+        runtime.register_actor_with_factory(
+            PersistentActorFactory::new(persistence.clone()),
+            &executor,
+        )?;
+
+        let addr: VirtualAddr<AutoSaveActor> = runtime.spawn_virtual(&1).await?;
+        addr.send(Add(1)).await?;
+        addr.send(Add(2)).await?;
+
+        runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+        assert_eq!(
+            persistence.load(&1).await?,
+            Some(expected),
+            "Manually saved state should be flushed on deactivation"
+        );
+    }
+
+    Ok(())
+}
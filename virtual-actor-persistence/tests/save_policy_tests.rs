@@ -0,0 +1,189 @@
+use std::{sync::Arc, time::Duration};
+
+use virtual_actor_persistence::{prelude::*, InmemoryPersistence};
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::auto_save_actor::{Add, AutoSaveActor, Get};
+
+mod actors;
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+const HOUR: Duration = Duration::from_secs(3600);
+
+fn start(
+    persistence: &Arc<dyn ActorPersistence<AutoSaveActor>>,
+    save_policy: SavePolicy,
+) -> Result<Runtime, Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor_with_factory(
+        PersistentActorFactory::new(persistence.clone()).with_save_policy(save_policy),
+        &executor,
+    )?;
+    Ok(runtime)
+}
+
+/// Response is sent before `after_message` hook, next message waits for it
+async fn after_message(
+    addr: &VirtualAddr<AutoSaveActor>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    addr.send(Get).await?;
+    Ok(())
+}
+
+/// Waits for change saved by idle actor
+async fn wait_saved(
+    persistence: &Arc<dyn ActorPersistence<AutoSaveActor>>,
+    expected: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while persistence.load(&1).await? != Some(expected) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        Ok::<_, BoxedActorError>(())
+    })
+    .await
+    .map_err(|_| "Delayed change should be saved without next message")??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn save_every_message_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<AutoSaveActor>> =
+        Arc::new(InmemoryPersistence::new());
+    let runtime = start(&persistence, SavePolicy::EveryMessage)?;
+
+    let addr: VirtualAddr<AutoSaveActor> = runtime.spawn_virtual(&1).await?;
+    addr.send(Add(2)).await?;
+    after_message(&addr).await?;
+    let (_, etag) = persistence
+        .load_versioned(&1)
+        .await?
+        .ok_or("State should be saved after message")?;
+
+    after_message(&addr).await?;
+    let (state, unchanged) = persistence
+        .load_versioned(&1)
+        .await?
+        .ok_or("State should exist")?;
+    assert_eq!(state, 2);
+    assert_eq!(unchanged, etag, "Unchanged state should not be saved");
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn save_debounce_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<AutoSaveActor>> =
+        Arc::new(InmemoryPersistence::new());
+    let runtime = start(&persistence, SavePolicy::Debounce(HOUR))?;
+
+    let addr: VirtualAddr<AutoSaveActor> = runtime.spawn_virtual(&1).await?;
+    addr.send(Add(1)).await?;
+    addr.send(Add(1)).await?;
+    after_message(&addr).await?;
+    assert_eq!(
+        persistence.load(&1).await?,
+        Some(1),
+        "Second change should wait for debounce interval"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    assert_eq!(
+        persistence.load(&1).await?,
+        Some(2),
+        "Changes should be flushed on deactivation"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn write_behind_batch_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<AutoSaveActor>> =
+        Arc::new(InmemoryPersistence::new());
+    let runtime = start(
+        &persistence,
+        SavePolicy::WriteBehind {
+            max_delay: HOUR,
+            max_messages: 3,
+        },
+    )?;
+
+    let addr: VirtualAddr<AutoSaveActor> = runtime.spawn_virtual(&1).await?;
+    addr.send(Add(1)).await?;
+    after_message(&addr).await?;
+    addr.send(Add(1)).await?;
+    after_message(&addr).await?;
+    assert_eq!(
+        persistence.load(&1).await?,
+        None,
+        "Batch should not be written yet"
+    );
+    addr.send(Add(1)).await?;
+    after_message(&addr).await?;
+    assert_eq!(
+        persistence.load(&1).await?,
+        Some(3),
+        "Full batch should be written"
+    );
+    addr.send(Add(1)).await?;
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    assert_eq!(
+        persistence.load(&1).await?,
+        Some(4),
+        "Changes should be flushed on deactivation"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn write_behind_max_delay_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<AutoSaveActor>> =
+        Arc::new(InmemoryPersistence::new());
+    let runtime = start(
+        &persistence,
+        SavePolicy::WriteBehind {
+            max_delay: Duration::from_millis(50),
+            max_messages: 100,
+        },
+    )?;
+
+    let addr: VirtualAddr<AutoSaveActor> = runtime.spawn_virtual(&1).await?;
+    addr.send(Add(5)).await?;
+    after_message(&addr).await?;
+    assert_eq!(persistence.load(&1).await?, None);
+
+    // no more messages, idle actor is woken up to save delayed change
+    wait_saved(&persistence, 5).await?;
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn save_debounce_idle_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<AutoSaveActor>> =
+        Arc::new(InmemoryPersistence::new());
+    let runtime = start(
+        &persistence,
+        SavePolicy::Debounce(Duration::from_millis(50)),
+    )?;
+
+    let addr: VirtualAddr<AutoSaveActor> = runtime.spawn_virtual(&1).await?;
+    addr.send(Add(1)).await?;
+    addr.send(Add(1)).await?;
+    after_message(&addr).await?;
+    assert_eq!(persistence.load(&1).await?, Some(1));
+
+    wait_saved(&persistence, 2).await?;
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
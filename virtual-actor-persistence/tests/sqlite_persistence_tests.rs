@@ -0,0 +1,110 @@
+use std::{sync::Arc, time::Duration};
+
+use futures::future::try_join_all;
+use virtual_actor_persistence::{
+    prelude::*, PersistenceError, SqlitePersistence, SqlitePersistencePreferences, TableLayout,
+};
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::counter_actor::{CounterActor, CounterActorFactory, Increment};
+
+mod actors;
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+#[tokio::test]
+async fn sqlite_persistence_restart_test() -> Result<(), Box<dyn std::error::Error>> {
+    let root = std::env::temp_dir().join(format!(
+        "sqlite_persistence_restart_test_{}",
+        std::process::id()
+    ));
+    tokio::fs::create_dir_all(&root).await?;
+    let path = root.join("state.db");
+
+    for expected in 1..=2 {
+        let persistence: Arc<dyn ActorPersistence<CounterActor>> =
+            Arc::new(SqlitePersistence::open_with_preferences(
+                &path,
+                &SqlitePersistencePreferences {
+                    table_layout: TableLayout::PerActorType,
+                    ..Default::default()
+                },
+            )?);
+        let mut runtime = Runtime::new()?;
+        let executor = runtime.create_executor()?;
+        runtime.register_actor_with_factory(CounterActorFactory::new(persistence), &executor)?;
+
+        let addr: VirtualAddr<CounterActor> = runtime.spawn_virtual(&1).await?;
+        assert_eq!(
+            addr.send(Increment).await??,
+            expected,
+            "State should survive restart"
+        );
+
+        runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    }
+
+    let connection = rusqlite::Connection::open(&path)?;
+    let rows: i64 = connection.query_row(
+        "SELECT count(*) FROM \"actor_state_CounterActor\"",
+        [],
+        |row| row.get(0),
+    )?;
+    assert_eq!(rows, 1, "State should be stored in actor type table");
+    drop(connection);
+
+    tokio::fs::remove_dir_all(&root).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sqlite_persistence_batch_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<CounterActor>> =
+        Arc::new(SqlitePersistence::open_in_memory()?);
+
+    try_join_all((0..100).map(|id| persistence.save(&id, &(id * 2)))).await?;
+
+    for id in 0..100 {
+        assert_eq!(persistence.load(&id).await?, Some(id * 2));
+    }
+
+    persistence.clear(&1).await?;
+    assert_eq!(persistence.load(&1).await?, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sqlite_persistence_inconsistent_state_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<CounterActor>> =
+        Arc::new(SqlitePersistence::open_in_memory()?);
+
+    let mut first = ActorState::<CounterActor>::load(&persistence, &1).await?;
+    let mut second = ActorState::<CounterActor>::load(&persistence, &1).await?;
+
+    *first += 1;
+    first.save().await?;
+    *first += 1;
+    first.save().await?;
+
+    *second += 1;
+    let err = second
+        .save()
+        .await
+        .expect_err("Stale activation should not overwrite state");
+    let err = err.downcast_error::<PersistenceError>()?;
+    assert!(
+        matches!(
+            *err,
+            PersistenceError::InconsistentState {
+                expected: None,
+                actual: Some(etag),
+            } if Some(etag) == first.etag()
+        ),
+        "Unexpected error: {err:?}"
+    );
+    assert_eq!(persistence.load(&1).await?, Some(2));
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::{sync::Arc, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use virtual_actor_persistence::{
+    prelude::*, CborCodec, FilePersistence, FilePersistencePreferences, InmemoryPersistence,
+    JsonCodec, KeyEncoding, MessagePackCodec, SqlitePersistence, StateFormat,
+};
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::counter_actor::{CounterActor, CounterActorFactory, Increment};
+
+mod actors;
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Profile {
+    name: String,
+    visits: u64,
+    tags: Vec<String>,
+}
+
+fn profile() -> Profile {
+    Profile {
+        name: "alice".to_string(),
+        visits: 3,
+        tags: vec!["a".to_string(), "b".to_string()],
+    }
+}
+
+async fn round_trip(
+    persistence: &dyn ActorPersistence<CounterActor, Profile>,
+) -> Result<Option<Profile>, Box<dyn std::error::Error>> {
+    persistence.save(&1, &profile()).await?;
+    Ok(persistence.load(&1).await?)
+}
+
+#[tokio::test]
+async fn json_file_persistence_test() -> Result<(), Box<dyn std::error::Error>> {
+    let root =
+        std::env::temp_dir().join(format!("json_file_persistence_test_{}", std::process::id()));
+    let persistence: Arc<dyn ActorPersistence<CounterActor>> = Arc::new(
+        FilePersistence::with_preferences(
+            &root,
+            FilePersistencePreferences {
+                key_encoding: KeyEncoding::Display,
+                ..Default::default()
+            },
+        )
+        .with_codec(JsonCodec { pretty: false }),
+    );
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor_with_factory(CounterActorFactory::new(persistence), &executor)?;
+
+    let addr: VirtualAddr<CounterActor> = runtime.spawn_virtual(&7).await?;
+    addr.send(Increment).await??;
+    addr.send(Increment).await??;
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    let content = tokio::fs::read(root.join("CounterActor").join("7.state")).await?;
+    assert_eq!(
+        &content[8..],
+        b"2",
+        "State should be stored as JSON after etag"
+    );
+
+    tokio::fs::remove_dir_all(&root).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn codec_round_trip_test() -> Result<(), Box<dyn std::error::Error>> {
+    let expected = Some(profile());
+
+    let cbor = InmemoryPersistence::new().with_codec(CborCodec);
+    assert_eq!(round_trip(&cbor).await?, expected, "CBOR round trip");
+
+    let msgpack = InmemoryPersistence::new().with_codec(MessagePackCodec);
+    assert_eq!(
+        round_trip(&msgpack).await?,
+        expected,
+        "MessagePack round trip"
+    );
+
+    let json = SqlitePersistence::open_in_memory()?.with_codec(JsonCodec { pretty: true });
+    assert_eq!(round_trip(&json).await?, expected, "JSON round trip");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn per_actor_codec_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence = InmemoryPersistence::new()
+        .with_codec(StateFormat::Bincode)
+        .with_actor_codec::<CounterActor>(StateFormat::Json(JsonCodec::default()));
+    assert_eq!(
+        round_trip(&persistence).await?,
+        Some(profile()),
+        "Overridden codec round trip"
+    );
+
+    // state encoded with JSON can not be read with bincode
+    let bincode = persistence.with_codec(StateFormat::Bincode);
+    assert!(
+        ActorPersistence::<CounterActor, Profile>::load(&bincode, &1)
+            .await
+            .is_err(),
+        "Codec override should be used for actor type"
+    );
+
+    Ok(())
+}
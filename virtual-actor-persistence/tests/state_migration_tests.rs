@@ -0,0 +1,179 @@
+use std::{sync::Arc, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use virtual_actor_persistence::{
+    prelude::*, InmemoryPersistence, JsonCodec, MigrationPolicy, StateFormat,
+};
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::profile_actor::{
+    ProfileActor, ProfileActorFactory, ProfileV1, ProfileV2, ProfileV3, Visit,
+};
+
+mod actors;
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+#[tokio::test]
+async fn multi_step_migration_test() -> Result<(), Box<dyn std::error::Error>> {
+    for format in [
+        StateFormat::Bincode,
+        StateFormat::Json(JsonCodec::default()),
+    ] {
+        let inmemory = InmemoryPersistence::new().with_codec(format);
+        let v1 = Versioned::new(ProfileV1 {
+            name: "Ada Lovelace".to_string(),
+        });
+        ActorPersistence::<ProfileActor, _>::save(&inmemory, &1, &v1).await?;
+        let v2 = Versioned::new(ProfileV2 {
+            name: "Alan".to_string(),
+            visits: 7,
+        });
+        ActorPersistence::<ProfileActor, _>::save(&inmemory, &2, &v2).await?;
+
+        let persistence: Arc<dyn ActorPersistence<ProfileActor>> = Arc::new(inmemory);
+        let state = ActorState::<ProfileActor>::load(&persistence, &1).await?;
+        assert_eq!(state.migrated_from(), Some(1), "{format:?}");
+        assert_eq!(
+            ProfileV3::clone(&state),
+            ProfileV3 {
+                first_name: "Ada".to_string(),
+                last_name: "Lovelace".to_string(),
+                visits: 0,
+            },
+            "State should be migrated through all versions, {format:?}"
+        );
+
+        let state = ActorState::<ProfileActor>::load(&persistence, &2).await?;
+        assert_eq!(state.migrated_from(), Some(2), "{format:?}");
+        assert_eq!(state.first_name, "Alan", "{format:?}");
+        assert_eq!(state.visits, 7, "{format:?}");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unversioned_state_test() -> Result<(), Box<dyn std::error::Error>> {
+    for format in [
+        StateFormat::Bincode,
+        StateFormat::Json(JsonCodec::default()),
+    ] {
+        // state persisted before versioning was introduced has no envelope
+        let inmemory = InmemoryPersistence::new().with_codec(format);
+        let unversioned = ProfileV1 {
+            name: "Barbara Liskov".to_string(),
+        };
+        ActorPersistence::<ProfileActor, _>::save(&inmemory, &1, &unversioned).await?;
+
+        let persistence: Arc<dyn ActorPersistence<ProfileActor>> = Arc::new(inmemory);
+        let state = ActorState::<ProfileActor>::load(&persistence, &1).await?;
+        assert_eq!(state.migrated_from(), Some(0), "{format:?}");
+        assert_eq!(state.first_name, "Barbara", "{format:?}");
+        assert_eq!(state.last_name, "Liskov", "{format:?}");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migration_policy_test() -> Result<(), Box<dyn std::error::Error>> {
+    let inmemory = InmemoryPersistence::new();
+    let v1 = Versioned::new(ProfileV1 {
+        name: "Grace Hopper".to_string(),
+    });
+    for id in 1..=2 {
+        ActorPersistence::<ProfileActor, _>::save(&inmemory, &id, &v1).await?;
+    }
+    let persistence: Arc<dyn ActorPersistence<ProfileActor>> = Arc::new(inmemory);
+
+    ActorState::load_with_policy(&persistence, &1, MigrationPolicy::Lazy).await?;
+    ActorState::load_with_policy(&persistence, &2, MigrationPolicy::Rewrite).await?;
+
+    let lazy = persistence.load(&1).await?.ok_or("State should exist")?;
+    assert_eq!(
+        lazy.migrated_from(),
+        Some(1),
+        "Lazy policy should keep old version"
+    );
+    let rewritten = persistence.load(&2).await?.ok_or("State should exist")?;
+    assert_eq!(
+        rewritten.migrated_from(),
+        None,
+        "Rewrite policy should store current version"
+    );
+    assert_eq!(rewritten.last_name, "Hopper");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrated_actor_test() -> Result<(), Box<dyn std::error::Error>> {
+    let inmemory = InmemoryPersistence::new();
+    let v2 = Versioned::new(ProfileV2 {
+        name: "Edsger Dijkstra".to_string(),
+        visits: 41,
+    });
+    ActorPersistence::<ProfileActor, _>::save(&inmemory, &1, &v2).await?;
+    let persistence: Arc<dyn ActorPersistence<ProfileActor>> = Arc::new(inmemory);
+
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor_with_factory(
+        ProfileActorFactory::new(persistence.clone(), MigrationPolicy::Lazy),
+        &executor,
+    )?;
+
+    let addr: VirtualAddr<ProfileActor> = runtime.spawn_virtual(&1).await?;
+    let profile = addr.send(Visit).await??;
+    assert_eq!(profile.visits, 42);
+    assert_eq!(profile.last_name, "Dijkstra");
+
+    let saved = persistence.load(&1).await?.ok_or("State should exist")?;
+    assert_eq!(
+        saved.migrated_from(),
+        None,
+        "Saved state should have current version"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProfileV4 {
+    visits: u64,
+}
+
+impl StateVersion for ProfileV4 {
+    const VERSION: u32 = 4;
+    type Previous = ProfileV3;
+}
+
+impl Migrate<ProfileV3> for ProfileV4 {
+    fn migrate(from: ProfileV3) -> Self {
+        Self {
+            visits: from.visits,
+        }
+    }
+}
+
+#[tokio::test]
+async fn unknown_version_test() -> Result<(), Box<dyn std::error::Error>> {
+    let inmemory = InmemoryPersistence::new();
+    ActorPersistence::<ProfileActor, _>::save(&inmemory, &1, &Versioned::new(ProfileV4::default()))
+        .await?;
+    let persistence: Arc<dyn ActorPersistence<ProfileActor>> = Arc::new(inmemory);
+
+    let err = ActorState::<ProfileActor>::load(&persistence, &1)
+        .await
+        .err()
+        .ok_or("State of newer version should not be loaded")?;
+    assert!(
+        err.to_string().contains("Unknown state version 4"),
+        "Unexpected error: {err}"
+    );
+
+    Ok(())
+}
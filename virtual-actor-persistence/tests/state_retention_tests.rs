@@ -0,0 +1,224 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use virtual_actor_persistence::{
+    prelude::*, FilePersistence, FilePersistencePreferences, InmemoryPersistence, KeyEncoding,
+    SqlitePersistence, StateRetention, StateSweeperFactory, Sweep,
+};
+use virtual_actor_runtime::{errors::BoxedActorError, prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::counter_actor::{CounterActor, CounterActorFactory, Increment};
+
+mod actors;
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+const TTL: Duration = Duration::from_millis(100);
+
+/// Saves state 1, then state 2 after returned time
+async fn save_old_and_new(
+    persistence: &dyn ActorPersistence<CounterActor>,
+) -> Result<SystemTime, Box<dyn std::error::Error>> {
+    persistence.save(&1, &1).await?;
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let saved_before = SystemTime::now();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    persistence.save(&2, &2).await?;
+    Ok(saved_before)
+}
+
+async fn assert_expired(
+    persistence: &dyn ActorPersistence<CounterActor>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let saved_before = save_old_and_new(persistence).await?;
+    let (_, etag) = persistence
+        .load_versioned(&1)
+        .await?
+        .ok_or("State should exist")?;
+    assert_eq!(
+        persistence.expired(saved_before, 10).await?,
+        vec![(1, etag)]
+    );
+    assert_eq!(persistence.expired(saved_before, 0).await?, Vec::new());
+
+    let mut ids = persistence.ids().await?;
+    ids.sort_unstable();
+    assert_eq!(ids, [1, 2]);
+    assert!(persistence.slot("other").ids().await?.is_empty());
+
+    // state saved after it was listed must not be cleared
+    persistence.save(&1, &3).await?;
+    assert!(
+        persistence.clear_versioned(&1, etag).await.is_err(),
+        "Clear should fail on changed state"
+    );
+    assert_eq!(persistence.load(&1).await?, Some(3));
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_expired_test() -> Result<(), Box<dyn std::error::Error>> {
+    assert_expired(&InmemoryPersistence::new()).await?;
+    assert_expired(&SqlitePersistence::open_in_memory()?).await?;
+
+    for key_encoding in [KeyEncoding::Hex, KeyEncoding::Display] {
+        let root = std::env::temp_dir().join(format!(
+            "list_expired_test_{key_encoding:?}_{}",
+            std::process::id()
+        ));
+        let persistence = FilePersistence::with_preferences(
+            &root,
+            FilePersistencePreferences {
+                key_encoding,
+                ..Default::default()
+            },
+        );
+        assert_expired(&persistence).await?;
+        tokio::fs::remove_dir_all(&root).await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sqlite_saved_at_migration_test() -> Result<(), Box<dyn std::error::Error>> {
+    let root = std::env::temp_dir().join(format!(
+        "sqlite_saved_at_migration_test_{}",
+        std::process::id()
+    ));
+    tokio::fs::create_dir_all(&root).await?;
+    let path = root.join("state.db");
+    let connection = rusqlite::Connection::open(&path)?;
+    connection.execute_batch(
+        "CREATE TABLE actor_state (
+            actor_name TEXT NOT NULL,
+            actor_id BLOB NOT NULL,
+            state BLOB NOT NULL,
+            etag INTEGER NOT NULL,
+            PRIMARY KEY (actor_name, actor_id)
+        ) WITHOUT ROWID",
+    )?;
+    connection.execute(
+        "INSERT INTO actor_state VALUES ('CounterActor', ?1, ?2, 1)",
+        (bincode::serialize(&1u32)?, bincode::serialize(&3u32)?),
+    )?;
+    drop(connection);
+
+    let saved_before = SystemTime::now();
+    let persistence = SqlitePersistence::open(&path)?;
+    assert_eq!(
+        ActorPersistence::<CounterActor>::load(&persistence, &1).await?,
+        Some(3)
+    );
+    assert_eq!(
+        ActorPersistence::<CounterActor>::expired(&persistence, saved_before, 10).await?,
+        Vec::new(),
+        "Existing states should be considered saved on migration"
+    );
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    assert_eq!(
+        ActorPersistence::<CounterActor>::expired(&persistence, SystemTime::now(), 10)
+            .await?
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>(),
+        vec![1]
+    );
+    drop(persistence);
+
+    tokio::fs::remove_dir_all(&root).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn state_sweeper_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<CounterActor>> = Arc::new(InmemoryPersistence::new());
+    let archived = Arc::new(Mutex::new(Vec::new()));
+    let archive = archived.clone();
+    let retention = StateRetention::new(persistence.clone(), TTL).on_expire(move |id, state| {
+        if let Ok(mut archive) = archive.lock() {
+            archive.push((id, state));
+        }
+        async { Ok(()) }
+    });
+
+    persistence.save(&1, &5).await?;
+    tokio::time::sleep(TTL + Duration::from_millis(50)).await;
+    persistence.save(&2, &7).await?;
+
+    let runtime = Runtime::new()?;
+    let sweeper = StateSweeperFactory::new(Duration::from_millis(50))
+        .with_retention(retention)
+        .start(&runtime)
+        .await?;
+    // waits for first sweep
+    sweeper.send(Sweep).await?;
+    assert_eq!(persistence.load(&1).await?, None, "State should be expired");
+    assert_eq!(persistence.load(&2).await?, Some(7), "State should be kept");
+
+    tokio::time::sleep(TTL * 3).await;
+    assert_eq!(
+        persistence.load(&2).await?,
+        None,
+        "State should be expired by periodic sweep"
+    );
+    assert_eq!(
+        *archived.lock().map_err(|e| e.to_string())?,
+        vec![(1, 5), (2, 7)],
+        "Expired states should be archived"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn failed_archive_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<CounterActor>> = Arc::new(InmemoryPersistence::new());
+    let retention = StateRetention::new(persistence.clone(), Duration::ZERO)
+        .on_expire(|_, _| async { Err(BoxedActorError::new(std::fmt::Error)) });
+
+    persistence.save(&1, &1).await?;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(retention.sweep(|_| false).await?, 0);
+    assert_eq!(
+        persistence.load(&1).await?,
+        Some(1),
+        "State should be kept if archive fails"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn active_actor_state_kept_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Arc<dyn ActorPersistence<CounterActor>> = Arc::new(InmemoryPersistence::new());
+    persistence.save(&2, &7).await?;
+
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    runtime
+        .register_actor_with_factory(CounterActorFactory::new(persistence.clone()), &executor)?;
+    let counter: VirtualAddr<CounterActor> = runtime.spawn_virtual(&1).await?;
+    assert_eq!(counter.send(Increment).await??, 1);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let sweeper = StateSweeperFactory::new(Duration::from_secs(3600))
+        .with_retention(StateRetention::new(persistence.clone(), Duration::ZERO))
+        .start(&runtime)
+        .await?;
+    sweeper.send(Sweep).await?;
+    assert_eq!(
+        persistence.load(&1).await?,
+        Some(1),
+        "State of active actor should be kept"
+    );
+    assert_eq!(persistence.load(&2).await?, None, "State should be expired");
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
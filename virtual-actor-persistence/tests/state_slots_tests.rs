@@ -0,0 +1,111 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use virtual_actor_persistence::{
+    prelude::*, FilePersistence, FilePersistencePreferences, InmemoryPersistence, KeyEncoding,
+    SqlitePersistence,
+};
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::shopper_actor::{
+    AddItem, Cart, GetShopper, Profile, Rename, ShopperActor, ShopperActorFactory, CART_SLOT,
+    PROFILE_SLOT,
+};
+
+mod actors;
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+fn test_root(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{name}_{}", std::process::id()))
+}
+
+#[tokio::test]
+async fn state_slots_restart_test() -> Result<(), Box<dyn std::error::Error>> {
+    let root = test_root("state_slots_restart_test");
+    let profiles: Arc<dyn ActorPersistence<ShopperActor, Profile>> =
+        Arc::new(InmemoryPersistence::new());
+    let carts: Arc<dyn ActorPersistence<ShopperActor, Cart>> =
+        Arc::new(FilePersistence::with_preferences(
+            &root,
+            FilePersistencePreferences {
+                key_encoding: KeyEncoding::Display,
+                ..Default::default()
+            },
+        ));
+
+    for expected_items in 1..=2 {
+        let mut runtime = Runtime::new()?;
+        let executor = runtime.create_executor()?;
+        runtime.register_actor_with_factory(
+            ShopperActorFactory::new(profiles.clone(), carts.clone()),
+            &executor,
+        )?;
+
+        let addr: VirtualAddr<ShopperActor> = runtime.spawn_virtual(&1).await?;
+        addr.send(Rename(format!("shopper {expected_items}")))
+            .await??;
+        assert_eq!(
+            addr.send(AddItem("book".to_string())).await??,
+            expected_items,
+            "Cart should survive restart"
+        );
+
+        runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    }
+
+    assert!(
+        root.join(format!("ShopperActor.{CART_SLOT}"))
+            .join("1.state")
+            .exists(),
+        "Slot name should be part of storage key"
+    );
+    assert_eq!(
+        profiles.load(&1).await?,
+        None,
+        "Default state should be independent of slots"
+    );
+    assert_eq!(
+        profiles.slot(PROFILE_SLOT).load(&1).await?,
+        Some(Profile {
+            name: "shopper 2".to_string()
+        })
+    );
+
+    tokio::fs::remove_dir_all(&root).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn shared_provider_slots_test() -> Result<(), Box<dyn std::error::Error>> {
+    let sqlite = SqlitePersistence::open_in_memory()?;
+    let profiles: Arc<dyn ActorPersistence<ShopperActor, Profile>> = Arc::new(sqlite.clone());
+    let carts: Arc<dyn ActorPersistence<ShopperActor, Cart>> = Arc::new(sqlite);
+
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor_with_factory(
+        ShopperActorFactory::new(profiles.clone(), carts.clone()),
+        &executor,
+    )?;
+
+    let addr: VirtualAddr<ShopperActor> = runtime.spawn_virtual(&7).await?;
+    addr.send(Rename("alice".to_string())).await??;
+    addr.send(AddItem("pen".to_string())).await??;
+    let (profile, cart) = addr.send(GetShopper).await?;
+
+    assert_eq!(
+        profiles.slot(PROFILE_SLOT).load(&7).await?,
+        Some(profile),
+        "Profile should be saved in its slot"
+    );
+    assert_eq!(
+        carts.slot(CART_SLOT).load(&7).await?,
+        Some(cart),
+        "Cart should be saved in its slot"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
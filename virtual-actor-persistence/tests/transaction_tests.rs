@@ -0,0 +1,453 @@
+use std::{sync::Arc, time::Duration};
+
+use virtual_actor_persistence::{
+    prelude::*, resolve_transactions, FileTransactionLog, InmemoryPersistence,
+    InmemoryTransactionLog, PrepareTransaction, Transaction, TransactionCoordinator,
+    TransactionDecision, TransactionError, TransactionId, TransactionLog,
+};
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::account_actor::{
+    AccountActor, Balance, Close, Credit, DeactivateAfterPrepare, Deposit, Withdraw,
+};
+
+mod actors;
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Bank {
+    runtime: Runtime,
+    persistence: Arc<dyn ActorPersistence<AccountActor>>,
+    from: VirtualAddr<AccountActor>,
+    to: VirtualAddr<AccountActor>,
+}
+
+async fn open_bank(balance: i64) -> Result<Bank, Box<dyn std::error::Error>> {
+    let bank = start_bank(Arc::new(InmemoryPersistence::new())).await?;
+    bank.from.send(Credit(balance)).await??;
+    Ok(bank)
+}
+
+async fn start_bank(
+    persistence: Arc<dyn ActorPersistence<AccountActor>>,
+) -> Result<Bank, Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor_with_factory(
+        PersistentActorFactory::new(persistence.clone()).with_transactions(),
+        &executor,
+    )?;
+
+    let from: VirtualAddr<AccountActor> = runtime.spawn_virtual(&1).await?;
+    let to: VirtualAddr<AccountActor> = runtime.spawn_virtual(&2).await?;
+    Ok(Bank {
+        runtime,
+        persistence,
+        from,
+        to,
+    })
+}
+
+/// Moves `amount` from first account to second one
+async fn transfer(mut tx: Transaction, bank: &Bank, amount: i64) -> Result<(), TransactionError> {
+    let scope = tx.scope();
+    tx.execute(&bank.from, Withdraw { tx: scope, amount })
+        .await?;
+    tx.execute(&bank.to, Deposit { tx: scope, amount }).await?;
+    tx.commit().await
+}
+
+async fn balances(bank: &Bank) -> Result<(i64, i64), Box<dyn std::error::Error>> {
+    Ok((bank.from.send(Balance).await?, bank.to.send(Balance).await?))
+}
+
+#[tokio::test]
+async fn transfer_commit_test() -> Result<(), Box<dyn std::error::Error>> {
+    let bank = open_bank(100).await?;
+    let coordinator = TransactionCoordinator::new(TIMEOUT);
+
+    transfer(coordinator.begin(), &bank, 30).await?;
+    assert_eq!(balances(&bank).await?, (70, 30));
+    assert_eq!(bank.persistence.load(&1).await?, Some(70));
+    assert_eq!(bank.persistence.load(&2).await?, Some(30));
+
+    bank.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn failed_operation_rollback_test() -> Result<(), Box<dyn std::error::Error>> {
+    let bank = open_bank(100).await?;
+    let coordinator = TransactionCoordinator::new(TIMEOUT);
+
+    let mut tx = coordinator.begin();
+    let scope = tx.scope();
+    tx.execute(
+        &bank.to,
+        Deposit {
+            tx: scope,
+            amount: 200,
+        },
+    )
+    .await?;
+    let result = tx
+        .execute(
+            &bank.from,
+            Withdraw {
+                tx: scope,
+                amount: 200,
+            },
+        )
+        .await;
+    assert!(
+        matches!(result, Err(TransactionError::Aborted { .. })),
+        "Failed operation should abort transaction: {result:?}"
+    );
+    assert_eq!(
+        balances(&bank).await?,
+        (100, 0),
+        "Deposit should be rolled back"
+    );
+    assert_eq!(bank.persistence.load(&2).await?, None);
+
+    transfer(coordinator.begin(), &bank, 10).await?;
+    assert_eq!(
+        balances(&bank).await?,
+        (90, 10),
+        "States should be released after rollback"
+    );
+
+    bank.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn conflicting_transactions_test() -> Result<(), Box<dyn std::error::Error>> {
+    let bank = open_bank(100).await?;
+    let coordinator = TransactionCoordinator::new(TIMEOUT);
+
+    let mut first = coordinator.begin();
+    let scope = first.scope();
+    first
+        .execute(
+            &bank.from,
+            Withdraw {
+                tx: scope,
+                amount: 50,
+            },
+        )
+        .await?;
+
+    let result = transfer(coordinator.begin(), &bank, 80).await;
+    assert!(
+        matches!(&result, Err(TransactionError::Aborted { reason, .. }) if reason.contains("locked")),
+        "State enlisted in another transaction should be rejected: {result:?}"
+    );
+
+    first
+        .execute(
+            &bank.to,
+            Deposit {
+                tx: scope,
+                amount: 50,
+            },
+        )
+        .await?;
+    first.commit().await?;
+    assert_eq!(balances(&bank).await?, (50, 50));
+
+    bank.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn timeout_rollback_test() -> Result<(), Box<dyn std::error::Error>> {
+    let bank = open_bank(100).await?;
+    let coordinator = TransactionCoordinator::new(Duration::from_millis(50));
+
+    let mut tx = coordinator.begin();
+    let scope = tx.scope();
+    tx.execute(
+        &bank.from,
+        Withdraw {
+            tx: scope,
+            amount: 30,
+        },
+    )
+    .await?;
+    tx.execute(
+        &bank.to,
+        Deposit {
+            tx: scope,
+            amount: 30,
+        },
+    )
+    .await?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(tx.commit().await, Err(TransactionError::Timeout(scope.id)));
+    assert_eq!(balances(&bank).await?, (100, 0));
+
+    // states of abandoned transaction are released after deadline
+    let mut abandoned = coordinator.begin();
+    let scope = abandoned.scope();
+    abandoned
+        .execute(
+            &bank.from,
+            Withdraw {
+                tx: scope,
+                amount: 30,
+            },
+        )
+        .await?;
+    drop(abandoned);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    transfer(coordinator.begin(), &bank, 20).await?;
+    assert_eq!(balances(&bank).await?, (80, 20));
+
+    bank.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn state_changed_outside_transaction_test() -> Result<(), Box<dyn std::error::Error>> {
+    let bank = open_bank(100).await?;
+    let coordinator = TransactionCoordinator::new(TIMEOUT);
+
+    let mut tx = coordinator.begin();
+    let scope = tx.scope();
+    tx.execute(
+        &bank.from,
+        Withdraw {
+            tx: scope,
+            amount: 30,
+        },
+    )
+    .await?;
+    bank.from.send(Credit(5)).await??;
+    tx.execute(
+        &bank.to,
+        Deposit {
+            tx: scope,
+            amount: 30,
+        },
+    )
+    .await?;
+    assert_eq!(
+        tx.commit().await,
+        Err(TransactionError::StateChanged(scope.id))
+    );
+    assert_eq!(balances(&bank).await?, (105, 0));
+    assert_eq!(bank.persistence.load(&1).await?, Some(105));
+
+    bank.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn non_transactional_state_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    let persistence: Arc<dyn ActorPersistence<AccountActor>> = Arc::new(InmemoryPersistence::new());
+    runtime.register_actor_with_factory(PersistentActorFactory::new(persistence), &executor)?;
+    let addr: VirtualAddr<AccountActor> = runtime.spawn_virtual(&1).await?;
+
+    let mut tx = TransactionCoordinator::new(TIMEOUT).begin();
+    let scope = tx.scope();
+    let result = tx
+        .execute(
+            &addr,
+            Deposit {
+                tx: scope,
+                amount: 10,
+            },
+        )
+        .await;
+    assert!(
+        matches!(&result, Err(TransactionError::Aborted { reason, .. }) if reason.contains("load_transactional")),
+        "State loaded without transactions should not be enlisted: {result:?}"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn clear_changed_state_test() -> Result<(), Box<dyn std::error::Error>> {
+    let bank = open_bank(100).await?;
+
+    bank.persistence.save(&1, &500).await?;
+    let result = bank.from.send(Close).await?;
+    assert!(
+        matches!(&result, Err(e) if e.contains("Inconsistent")),
+        "State changed by another writer should not be cleared: {result:?}"
+    );
+    assert_eq!(bank.persistence.load(&1).await?, Some(500));
+
+    bank.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn participant_deactivated_after_prepare_test() -> Result<(), Box<dyn std::error::Error>> {
+    let bank = open_bank(100).await?;
+    let coordinator = TransactionCoordinator::new(TIMEOUT);
+
+    bank.to.send(DeactivateAfterPrepare).await?;
+    transfer(coordinator.begin(), &bank, 30).await?;
+    assert_eq!(
+        balances(&bank).await?,
+        (70, 30),
+        "Prepared state should be committed by next activation"
+    );
+    assert_eq!(bank.persistence.load(&2).await?, Some(30));
+    assert!(
+        bank.persistence
+            .prepared_transactions()
+            .load(&2)
+            .await?
+            .is_none(),
+        "Prepared state should be cleared on commit"
+    );
+
+    bank.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn prepared_state_recovery_test() -> Result<(), Box<dyn std::error::Error>> {
+    let bank = open_bank(100).await?;
+    let log: Arc<dyn TransactionLog> = Arc::new(InmemoryTransactionLog::new());
+    let coordinator = TransactionCoordinator::new(Duration::from_millis(50)).with_log(log.clone());
+
+    let mut tx = coordinator.begin();
+    let scope = tx.scope();
+    tx.execute(
+        &bank.to,
+        Deposit {
+            tx: scope,
+            amount: 30,
+        },
+    )
+    .await?;
+    bank.to.send(PrepareTransaction(scope.id)).await??;
+    bank.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let bank = start_bank(bank.persistence).await?;
+    let result = bank.to.send(Credit(5)).await?;
+    assert!(
+        matches!(&result, Err(e) if e.contains("prepared")),
+        "Prepared state should not be saved outside of transaction: {result:?}"
+    );
+    let result = bank.to.send(Close).await?;
+    assert!(
+        matches!(&result, Err(e) if e.contains("prepared")),
+        "Prepared state should not be cleared outside of transaction: {result:?}"
+    );
+    let result = transfer(coordinator.begin(), &bank, 10).await;
+    assert!(
+        matches!(&result, Err(TransactionError::Aborted { reason, .. }) if reason.contains("locked")),
+        "Prepared state should not expire: {result:?}"
+    );
+
+    // coordinator stopped after commit was decided
+    log.decide(scope.id, TransactionDecision::Commit).await?;
+    assert_eq!(
+        resolve_transactions(&bank.runtime, &bank.persistence, &log).await?,
+        1
+    );
+    assert_eq!(balances(&bank).await?, (100, 30));
+    assert_eq!(bank.persistence.load(&2).await?, Some(30));
+    assert_eq!(
+        resolve_transactions(&bank.runtime, &bank.persistence, &log).await?,
+        0,
+        "Committed transaction should not be resolved again"
+    );
+
+    bank.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn undecided_transaction_abort_test() -> Result<(), Box<dyn std::error::Error>> {
+    let bank = open_bank(100).await?;
+    let log: Arc<dyn TransactionLog> = Arc::new(InmemoryTransactionLog::new());
+    let coordinator = TransactionCoordinator::new(Duration::from_millis(50)).with_log(log.clone());
+
+    let mut tx = coordinator.begin();
+    let scope = tx.scope();
+    tx.execute(
+        &bank.to,
+        Deposit {
+            tx: scope,
+            amount: 30,
+        },
+    )
+    .await?;
+    bank.to.send(PrepareTransaction(scope.id)).await??;
+    assert_eq!(
+        resolve_transactions(&bank.runtime, &bank.persistence, &log).await?,
+        0,
+        "Transaction should not be resolved before its deadline"
+    );
+
+    // coordinator stopped before commit was decided
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(
+        resolve_transactions(&bank.runtime, &bank.persistence, &log).await?,
+        1
+    );
+    assert_eq!(balances(&bank).await?, (100, 0));
+    assert_eq!(
+        log.decision(scope.id).await?,
+        Some(TransactionDecision::Abort),
+        "Abort should be recorded, so coordinator can not commit transaction"
+    );
+    transfer(coordinator.begin(), &bank, 10).await?;
+    assert_eq!(balances(&bank).await?, (90, 10));
+
+    bank.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_transaction_log_test() -> Result<(), Box<dyn std::error::Error>> {
+    let root = std::env::temp_dir().join(format!("transaction_log_test_{}", std::process::id()));
+    let id = TransactionId(1);
+
+    let log = FileTransactionLog::new(&root);
+    assert_eq!(log.decision(id).await?, None);
+    assert_eq!(
+        log.decide(id, TransactionDecision::Commit).await?,
+        TransactionDecision::Commit
+    );
+    assert_eq!(
+        log.decide(id, TransactionDecision::Abort).await?,
+        TransactionDecision::Commit,
+        "Recorded decision should not be replaced"
+    );
+
+    let log = FileTransactionLog::new(&root);
+    assert_eq!(
+        log.decision(id).await?,
+        Some(TransactionDecision::Commit),
+        "Decision should survive restart"
+    );
+    log.forget(id).await?;
+    assert_eq!(log.decision(id).await?, None);
+
+    tokio::fs::remove_dir_all(&root).await?;
+
+    Ok(())
+}
@@ -0,0 +1,10 @@
+use virtual_actor_persistence::prelude::*;
+
+#[derive(ActorWithState)]
+#[state_type(u32)]
+#[state_field("counter")]
+pub struct CounterActor {
+    counter: u32,
+}
+
+fn main() {}
@@ -0,0 +1,232 @@
+use std::{sync::Arc, time::Duration};
+
+use virtual_actor_persistence::{
+    prelude::*, resume_workflows, InmemoryPersistence, StepEvent, StepOutcome, StepRetryPolicy,
+    WorkflowError, WorkflowLog, WorkflowStatus,
+};
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::{
+    order_workflow_actor::{
+        GetHistory, GetStatus, Order, OrderWorkflowActor, OrderWorkflowFactory, PlaceOrder,
+    },
+    service_actor::{Failures, Journal, ServiceActorFactory},
+};
+
+mod actors;
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+type Persistence = Arc<dyn ActorPersistence<OrderWorkflowActor, WorkflowLog<Order>>>;
+
+struct Shop {
+    runtime: Runtime,
+    journal: Journal,
+}
+
+fn start_shop(
+    persistence: &Persistence,
+    failures: &[(&str, u32)],
+) -> Result<Shop, Box<dyn std::error::Error>> {
+    start_shop_with_backoff(persistence, failures, Duration::from_millis(5))
+}
+
+fn start_shop_with_backoff(
+    persistence: &Persistence,
+    failures: &[(&str, u32)],
+    backoff: Duration,
+) -> Result<Shop, Box<dyn std::error::Error>> {
+    let journal = Journal::default();
+    let failures: Failures = Arc::new(
+        failures
+            .iter()
+            .map(|(op, count)| ((*op).to_string(), *count))
+            .collect::<std::collections::HashMap<_, _>>()
+            .into(),
+    );
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor_with_factory(
+        ServiceActorFactory {
+            journal: journal.clone(),
+            failures,
+        },
+        &executor,
+    )?;
+    runtime.register_actor_with_factory(
+        OrderWorkflowFactory {
+            persistence: persistence.clone(),
+            retry_policy: StepRetryPolicy {
+                max_attempts: 3,
+                backoff,
+                max_backoff: backoff * 4,
+            },
+        },
+        &executor,
+    )?;
+    Ok(Shop { runtime, journal })
+}
+
+fn order() -> Order {
+    Order {
+        item: "book".to_string(),
+        amount: 42,
+    }
+}
+
+fn journal(shop: &Shop) -> Vec<String> {
+    shop.journal.lock().expect("Journal lock poisoned").clone()
+}
+
+/// Waits until workflow executed by actor is finished
+async fn wait_finished(
+    addr: &VirtualAddr<OrderWorkflowActor>,
+) -> Result<WorkflowStatus, Box<dyn std::error::Error>> {
+    for _ in 0..400 {
+        let status = addr.send(GetStatus).await?;
+        if !status.is_active() {
+            return Ok(status);
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    Err("Workflow is not finished".into())
+}
+
+#[tokio::test]
+async fn workflow_completed_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Persistence = Arc::new(InmemoryPersistence::new());
+    let shop = start_shop(&persistence, &[])?;
+
+    let addr: VirtualAddr<OrderWorkflowActor> = shop.runtime.spawn_virtual(&1).await?;
+    assert_eq!(
+        addr.send(PlaceOrder(order())).await??,
+        WorkflowStatus::Running { step: 0 }
+    );
+    assert_eq!(wait_finished(&addr).await?, WorkflowStatus::Completed);
+    assert_eq!(journal(&shop), ["reserve book", "charge 42", "ship book"]);
+
+    let log = persistence.load(&1).await?.expect("Workflow log is saved");
+    assert_eq!(log.status, WorkflowStatus::Completed);
+    assert_eq!(log.data, order());
+    assert_eq!(log.history.len(), 3);
+
+    assert_eq!(
+        addr.send(PlaceOrder(order())).await?,
+        Err(WorkflowError::AlreadyStarted(WorkflowStatus::Completed))
+    );
+
+    shop.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn workflow_step_retry_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Persistence = Arc::new(InmemoryPersistence::new());
+    let shop = start_shop(&persistence, &[("charge 42", 2)])?;
+
+    let addr: VirtualAddr<OrderWorkflowActor> = shop.runtime.spawn_virtual(&1).await?;
+    assert_eq!(
+        addr.send(PlaceOrder(order())).await??,
+        WorkflowStatus::Running { step: 0 }
+    );
+    assert_eq!(wait_finished(&addr).await?, WorkflowStatus::Completed);
+    assert_eq!(journal(&shop), ["reserve book", "charge 42", "ship book"]);
+    assert_eq!(
+        addr.send(GetHistory).await?[1],
+        StepEvent {
+            step: "charge".to_string(),
+            attempts: 3,
+            outcome: StepOutcome::Completed,
+        }
+    );
+
+    shop.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn workflow_compensation_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Persistence = Arc::new(InmemoryPersistence::new());
+    let shop = start_shop(&persistence, &[("ship book", u32::MAX)])?;
+
+    let addr: VirtualAddr<OrderWorkflowActor> = shop.runtime.spawn_virtual(&1).await?;
+    addr.send(PlaceOrder(order())).await??;
+    let status = wait_finished(&addr).await?;
+    assert!(matches!(status, WorkflowStatus::Compensated { .. }));
+    assert_eq!(
+        journal(&shop),
+        ["reserve book", "charge 42", "refund 42", "release book"]
+    );
+
+    let outcomes: Vec<_> = addr
+        .send(GetHistory)
+        .await?
+        .into_iter()
+        .map(|event| (event.step, event.outcome))
+        .collect();
+    assert_eq!(
+        outcomes[..2],
+        [
+            ("reserve".to_string(), StepOutcome::Completed),
+            ("charge".to_string(), StepOutcome::Completed),
+        ]
+    );
+    assert_eq!(outcomes[2].0, "ship");
+    assert!(matches!(outcomes[2].1, StepOutcome::Failed(_)));
+    assert_eq!(
+        outcomes[3..],
+        [
+            ("charge".to_string(), StepOutcome::Compensated),
+            ("reserve".to_string(), StepOutcome::Compensated),
+        ]
+    );
+
+    shop.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn workflow_resume_after_restart_test() -> Result<(), Box<dyn std::error::Error>> {
+    let persistence: Persistence = Arc::new(InmemoryPersistence::new());
+    persistence
+        .save(
+            &8,
+            &WorkflowLog {
+                data: order(),
+                status: WorkflowStatus::Completed,
+                history: Vec::new(),
+            },
+        )
+        .await?;
+
+    // Runtime is stopped while second step waits for retry
+    let shop = start_shop_with_backoff(&persistence, &[("charge 42", 1)], Duration::from_secs(60))?;
+    let addr: VirtualAddr<OrderWorkflowActor> = shop.runtime.spawn_virtual(&7).await?;
+    addr.send(PlaceOrder(order())).await??;
+    while addr.send(GetHistory).await?.is_empty() {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert_eq!(
+        addr.send(GetStatus).await?,
+        WorkflowStatus::Running { step: 1 },
+        "Actor should handle messages during backoff"
+    );
+    assert_eq!(journal(&shop), ["reserve book"]);
+    shop.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    let shop = start_shop(&persistence, &[])?;
+    assert_eq!(resume_workflows(&shop.runtime, &persistence).await?, 1);
+
+    let addr: VirtualAddr<OrderWorkflowActor> = shop.runtime.spawn_virtual(&7).await?;
+    assert_eq!(wait_finished(&addr).await?, WorkflowStatus::Completed);
+    assert_eq!(journal(&shop), ["charge 42", "ship book"]);
+    assert_eq!(addr.send(GetHistory).await?.len(), 3);
+    assert_eq!(
+        resume_workflows(&shop.runtime, &persistence).await?,
+        0,
+        "Finished workflow should be removed from index"
+    );
+
+    shop.runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
@@ -10,8 +10,10 @@ use virtual_actor::{
 };
 
 use crate::{
-    executor::errors::ActorTaskError,
-    messaging::{errors::DispatcherError, MessageDispatcher},
+    executor::{errors::ActorTaskError, ExecutorMonitor},
+    messaging::{
+        errors::DispatcherError, CallChain, CallChainEntry, DispatchPermit, MessageDispatcher,
+    },
     utils::{atomic_counter::AtomicCounter, GracefulShutdown},
     utils::{
         notify_once::NotifyOnce,
@@ -20,7 +22,8 @@ use crate::{
     LocalAddr,
 };
 
-use super::errors::ActorTaskContainerError;
+use super::deactivation::Deactivation;
+use super::errors::{ActorTaskContainerError, DeadlockDetected};
 use super::{
     actor_task_container::ActorTaskContainer, errors::ActorStartError, errors::LocalAddrError,
     ActorTask,
@@ -61,8 +64,16 @@ struct ActorInner<A: Actor> {
     dispatched_msg_counter: AtomicCounter,
     /// Counter of messages processed by actor
     processed_msg_counter: AtomicCounter,
+    /// Memory used by actor state in bytes, as reported by actor
+    memory_usage: AtomicCounter,
+    /// Deactivation requested by actor
+    deactivation: Arc<Deactivation>,
     /// Actor task
     actor_task: ActorTaskContainer,
+    /// Actor instance entry in call chains
+    call_chain_entry: CallChainEntry,
+    /// Monitor of executor running actor
+    executor_monitor: ExecutorMonitor,
 }
 
 /// Actor handler
@@ -114,6 +125,8 @@ impl<A: Actor> ActorHandle<A> {
         execution_cancellation: CancellationToken,
         mailbox_cancellation: CancellationToken,
         dispatched_msg_counter: AtomicCounter,
+        call_chain_entry: CallChainEntry,
+        executor_monitor: ExecutorMonitor,
     ) -> Self {
         Self {
             inner: Arc::new(ActorInner {
@@ -125,7 +138,11 @@ impl<A: Actor> ActorHandle<A> {
                 mailbox_cancellation,
                 dispatched_msg_counter,
                 processed_msg_counter: AtomicCounter::default(),
+                memory_usage: AtomicCounter::default(),
+                deactivation: Arc::new(Deactivation::default()),
                 actor_task: ActorTaskContainer::default(),
+                call_chain_entry,
+                executor_monitor,
             }),
         }
     }
@@ -153,6 +170,19 @@ impl<A: Actor> ActorHandle<A> {
         &self.inner.processed_msg_counter
     }
 
+    pub(crate) fn memory_usage(&self) -> &AtomicCounter {
+        &self.inner.memory_usage
+    }
+
+    pub(crate) fn deactivation(&self) -> &Arc<Deactivation> {
+        &self.inner.deactivation
+    }
+
+    /// Handles refer to the same actor instance
+    pub(crate) fn is_same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
     pub(crate) fn dispatched_msg_counter(&self) -> &AtomicCounter {
         &self.inner.dispatched_msg_counter
     }
@@ -175,11 +205,33 @@ impl<A: Actor> ActorHandle<A> {
         &self.inner.actor_started
     }
 
+    pub(crate) fn call_chain_entry(&self) -> &CallChainEntry {
+        &self.inner.call_chain_entry
+    }
+
+    pub(crate) fn executor_monitor(&self) -> &ExecutorMonitor {
+        &self.inner.executor_monitor
+    }
+
     /// Is finished
     pub fn is_finished(&self) -> bool {
         self.inner.actor_stopped.is_notified()
     }
 
+    /// Wait for actor to be stopped
+    pub(crate) async fn wait_for_stop(
+        &self,
+        timeout: std::time::Duration,
+    ) -> Result<(), WaitError> {
+        waiter(
+            "wait_for_stop",
+            self.inner.actor_stopped.inner(),
+            timeout,
+            None,
+        )
+        .await
+    }
+
     /// Wait for actor to be ready
     pub async fn wait_for_ready(
         &self,
@@ -258,19 +310,11 @@ impl<A: Actor> ActorHandle<A> {
         A: MessageHandler<M>,
         <A as Actor>::MessagesEnvelope: MessageEnvelopeFactory<A, M>,
     {
-        if self.is_finished() {
-            return Err(LocalAddrError::Stopped);
-        }
-        let dispatcher = self
-            .inner
-            .dispatcher
-            .get()
-            .ok_or(LocalAddrError::ActorNotReady)?;
-        select! {
-            biased;
-            () = self.inner.mailbox_cancellation.cancelled() => Err(LocalAddrError::Stopped),
-            res = dispatcher.send(msg) => Self::map_actor_response::<M>(res),
-        }
+        let call_chain = self.call_chain()?;
+        let permit = self.reserve()?;
+        // queued messages are still processed after mailbox cancellation,
+        // response is lost only if actor execution is cancelled
+        Self::map_actor_response::<M>(permit.send(msg, call_chain).await)
     }
 
     /// Impl for trait
@@ -280,24 +324,37 @@ impl<A: Actor> ActorHandle<A> {
         A: MessageHandler<M>,
         <A as Actor>::MessagesEnvelope: MessageEnvelopeFactory<A, M>,
     {
+        self.reserve()?.dispatch(msg);
+        Ok(())
+    }
+
+    /// Call chain of message sent to actor from current scope
+    pub(crate) fn call_chain(&self) -> Result<CallChain, LocalAddrError> {
+        // fail fast if actor is already waiting for response in current call chain
+        let call_chain = CallChain::current();
+        if let Some(cycle) = call_chain.cycle_to(&self.inner.call_chain_entry) {
+            return Err(LocalAddrError::DeadlockDetected(DeadlockDetected::new(
+                cycle,
+            )));
+        }
+        Ok(call_chain)
+    }
+
+    /// Reserves place in actor mailbox, message is kept by caller on error
+    pub(crate) fn reserve(&self) -> Result<DispatchPermit<'_, A>, LocalAddrError> {
         if self.is_finished() {
             return Err(LocalAddrError::Stopped);
         }
-
         let dispatcher = self
             .inner
             .dispatcher
             .get()
             .ok_or(LocalAddrError::ActorNotReady)?;
-        if self.inner.mailbox_cancellation.is_cancelled() {
+        if self.is_cancelled() {
             return Err(LocalAddrError::Stopped);
         }
-        if self.inner.execution_cancellation.is_cancelled() {
-            return Err(LocalAddrError::Stopped);
-        }
-
         dispatcher
-            .dispatch(msg)
+            .reserve()
             .map_err(LocalAddrError::DispatcherError)
     }
 
@@ -318,7 +375,7 @@ impl<A: Actor> ActorHandle<A> {
         }
     }
 
-    fn map_actor_response<M: Message>(
+    pub(crate) fn map_actor_response<M: Message>(
         res: Result<MessageProcessingResult<M>, DispatcherError>,
     ) -> Result<M::Result, LocalAddrError> {
         match res {
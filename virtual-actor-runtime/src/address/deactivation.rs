@@ -0,0 +1,109 @@
+//! Deactivation and wakeup requests of virtual actor activation
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+/// Detaches activation from registry, so next message activates fresh instance
+pub type DeactivationHook = Box<dyn Fn() + Send + Sync>;
+
+/// Deactivation state requested by actor through context
+#[derive(Default)]
+pub struct Deactivation {
+    /// Actor is deactivated once mailbox is empty
+    on_idle: AtomicBool,
+    /// Actor is not collected as idle until this time
+    delayed_until: Mutex<Option<Instant>>,
+    /// Number of holds keeping actor from idle collection and eviction
+    holds: AtomicUsize,
+    /// Idle actor runs `after_message` hook at this time
+    wake_at: Mutex<Option<Instant>>,
+    /// Hook installed by activator
+    hook: OnceLock<DeactivationHook>,
+}
+
+impl Deactivation {
+    /// Sets hook called when deactivation is requested
+    pub fn set_hook(&self, hook: DeactivationHook) {
+        if self.hook.set(hook).is_err() {
+            eprintln!("Deactivation hook is already set");
+        }
+    }
+
+    /// Requests deactivation once mailbox is empty, cancels delay of deactivation
+    pub fn request_on_idle(&self) {
+        self.on_idle.store(true, Ordering::Relaxed);
+        *self.delayed_until() = None;
+        if let Some(hook) = self.hook.get() {
+            hook();
+        }
+    }
+
+    /// Deactivation on idle is requested
+    pub fn is_requested_on_idle(&self) -> bool {
+        self.on_idle.load(Ordering::Relaxed)
+    }
+
+    /// Keeps actor from idle collection for at least `duration`,
+    /// ignored if deactivation on idle is requested
+    pub fn delay(&self, duration: Duration) {
+        if self.is_requested_on_idle() {
+            return;
+        }
+        *self.delayed_until() = Some(Instant::now() + duration);
+    }
+
+    /// Idle collection is delayed
+    pub fn is_delayed(&self) -> bool {
+        self.delayed_until()
+            .is_some_and(|delayed_until| delayed_until > Instant::now())
+    }
+
+    /// Keeps actor from idle collection and eviction until hold is released
+    pub fn hold(&self) {
+        self.holds.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Releases hold, unmatched release is ignored
+    pub fn release(&self) {
+        let _ = self
+            .holds
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |holds| {
+                holds.checked_sub(1)
+            });
+    }
+
+    /// Actor is held active
+    pub fn is_held(&self) -> bool {
+        self.holds.load(Ordering::Relaxed) > 0
+    }
+
+    /// Requests `after_message` hook run after `duration`, earliest request wins
+    pub fn wake_after(&self, duration: Duration) {
+        let wake_at = Instant::now() + duration;
+        let mut guard = self.wake_at.lock().expect("Deactivation lock poisoned");
+        if guard.is_none_or(|current| current > wake_at) {
+            *guard = Some(wake_at);
+        }
+    }
+
+    /// Time of requested wakeup
+    pub fn wake_at(&self) -> Option<Instant> {
+        *self.wake_at.lock().expect("Deactivation lock poisoned")
+    }
+
+    /// Clears wakeup which is due
+    pub fn clear_wakeup(&self) {
+        *self.wake_at.lock().expect("Deactivation lock poisoned") = None;
+    }
+
+    fn delayed_until(&self) -> std::sync::MutexGuard<'_, Option<Instant>> {
+        self.delayed_until
+            .lock()
+            .expect("Deactivation lock poisoned")
+    }
+}
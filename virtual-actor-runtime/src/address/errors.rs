@@ -1,7 +1,12 @@
 use virtual_actor::errors::MessageProcessingError;
 
 use crate::{
-    errors::WaitError, executor::errors::ActorTaskError, messaging::errors::DispatcherError,
+    errors::WaitError,
+    executor::errors::ActorTaskError,
+    messaging::{
+        errors::{DispatcherError, MailboxError},
+        CallChainEntry,
+    },
     runtime::errors::RuntimeSpawnError,
 };
 
@@ -27,6 +32,35 @@ pub enum ActorStartError {
     UnexpectedState(String),
 }
 
+/// Sending message would create cycle of actors waiting for each other
+#[derive(thiserror::Error, Debug)]
+#[error("Deadlock detected: {}", format_cycle(.cycle))]
+pub struct DeadlockDetected {
+    /// Actors in the cycle, starting and ending with the message recipient
+    cycle: Vec<CallChainEntry>,
+}
+
+impl DeadlockDetected {
+    /// Creates new deadlock error
+    pub(crate) fn new(cycle: Vec<CallChainEntry>) -> Self {
+        Self { cycle }
+    }
+
+    /// Actors in the cycle, starting and ending with the message recipient
+    #[must_use]
+    pub fn cycle(&self) -> &[CallChainEntry] {
+        &self.cycle
+    }
+}
+
+fn format_cycle(cycle: &[CallChainEntry]) -> String {
+    cycle
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
 /// Actor handler error
 #[derive(thiserror::Error, Debug)]
 pub enum LocalAddrError {
@@ -42,6 +76,29 @@ pub enum LocalAddrError {
     /// Message processing error
     #[error("Message processing error {0:?}")]
     MessageProcessingError(#[from] MessageProcessingError),
+    /// Waiting for response would deadlock
+    #[error("{0}")]
+    DeadlockDetected(#[from] DeadlockDetected),
+}
+
+impl LocalAddrError {
+    /// Message was not delivered because activation is stopped or stopping
+    pub(crate) fn is_stale_activation(&self) -> bool {
+        matches!(
+            self,
+            Self::Stopped
+                | Self::ActorNotReady
+                | Self::DispatcherError(DispatcherError::MailBoxError(MailboxError::Closed))
+        )
+    }
+
+    /// Message was delivered, but activation stopped before replying
+    pub(crate) fn is_response_lost(&self) -> bool {
+        matches!(
+            self,
+            Self::DispatcherError(DispatcherError::ResponseReceiverError(_))
+        )
+    }
 }
 
 /// Actor handler error
@@ -1,15 +1,19 @@
 mod actor_handle;
 mod actor_task;
 mod actor_task_container;
+mod deactivation;
 pub mod errors;
 mod local_addr;
+mod retry_policy;
 mod virtual_addr;
 mod weak_local_addr;
 mod weak_virtual_addr;
 
 pub use actor_handle::ActorHandle;
 pub use actor_task::ActorTask;
+pub use deactivation::Deactivation;
 pub use local_addr::LocalAddr;
+pub use retry_policy::RetryPolicy;
 pub use virtual_addr::VirtualAddr;
 pub use weak_local_addr::WeakLocalAddr;
 pub use weak_virtual_addr::WeakVirtualAddr;
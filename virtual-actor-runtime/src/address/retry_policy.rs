@@ -0,0 +1,28 @@
+//! Retry policy of virtual actor address
+
+use std::time::Duration;
+
+/// Redelivery of messages sent through `VirtualAddr` which raced with deactivation.
+///
+/// Message which was not put into mailbox of stopping activation is always safe to redeliver.
+/// Message which was delivered, but activation stopped before replying,
+/// is redelivered only if it is idempotent, see `Message::idempotent_copy`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximal number of redeliveries, zero disables retries
+    pub max_retries: usize,
+    /// Delay before redelivery
+    pub backoff: Duration,
+    /// Redeliver idempotent messages with unknown outcome
+    pub retry_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            backoff: Duration::from_millis(10),
+            retry_idempotent: true,
+        }
+    }
+}
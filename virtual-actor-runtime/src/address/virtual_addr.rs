@@ -9,6 +9,8 @@ use crate::runtime::ActorActivator;
 use super::{weak_virtual_addr::WeakVirtualAddr, ActorHandle};
 
 /// Virtual actor address
+/// Messages are delivered to actor activated by this runtime,
+/// actor owned by another runtime is addressed by `RemoteAddr`
 pub struct VirtualAddr<A: VirtualActor> {
     id: A::ActorId,
     activator: ActorActivator<A>,
@@ -22,11 +24,29 @@ impl<A: VirtualActor> VirtualAddr<A> {
         }
     }
 
+    /// Id of addressed actor
+    pub fn id(&self) -> &A::ActorId {
+        &self.id
+    }
+
     async fn get_addr(&self) -> Result<ActorHandle<A>, super::errors::VirtualAddrError> {
         let handle = self.activator.get_or_spawn(&self.id).await?;
 
         Ok(handle)
     }
+
+    /// Forgets activation racing with deactivation before next delivery attempt
+    async fn forget_stale(&self, handle: &ActorHandle<A>, retries: &mut usize) {
+        self.activator.remove_stale(&self.id, handle);
+        *retries += 1;
+        tokio::time::sleep(self.activator.retry_policy().backoff).await;
+    }
+}
+
+impl<A: VirtualActor> Clone for VirtualAddr<A> {
+    fn clone(&self) -> Self {
+        Self::new(&self.id, &self.activator)
+    }
 }
 
 impl<A: VirtualActor> ActorAddr<A> for VirtualAddr<A> {
@@ -40,10 +60,33 @@ impl<A: VirtualActor> ActorAddr<A> for VirtualAddr<A> {
         A: MessageHandler<M>,
         A::MessagesEnvelope: MessageEnvelopeFactory<A, M>,
     {
-        let addr = self.get_addr().await?;
-        addr.send(msg)
-            .await
-            .map_err(super::errors::VirtualAddrError::LocalAddrError)
+        let policy = self.activator.retry_policy();
+        let mut msg = msg;
+        let mut retries = 0;
+        loop {
+            let addr = self.get_addr().await?;
+            let call_chain = addr.call_chain()?;
+            let permit = match addr.reserve() {
+                Ok(permit) => permit,
+                Err(e) if e.is_stale_activation() && retries < policy.max_retries => {
+                    self.forget_stale(&addr, &mut retries).await;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let copy = policy
+                .retry_idempotent
+                .then(|| msg.idempotent_copy())
+                .flatten();
+            let res = ActorHandle::<A>::map_actor_response::<M>(permit.send(msg, call_chain).await);
+            match (res, copy) {
+                (Err(e), Some(copy)) if e.is_response_lost() && retries < policy.max_retries => {
+                    msg = copy;
+                    self.forget_stale(&addr, &mut retries).await;
+                }
+                (res, _) => return res.map_err(super::errors::VirtualAddrError::LocalAddrError),
+            }
+        }
     }
 
     async fn dispatch<M>(&self, msg: M) -> Result<(), Self::Error>
@@ -52,11 +95,34 @@ impl<A: VirtualActor> ActorAddr<A> for VirtualAddr<A> {
         A: MessageHandler<M>,
         A::MessagesEnvelope: MessageEnvelopeFactory<A, M>,
     {
-        let addr = self.get_addr().await?;
-
-        addr.dispatch(msg)?;
-
-        Ok(())
+        let policy = self.activator.retry_policy();
+        let mut msg = msg;
+        let mut retries = 0;
+        loop {
+            let addr = self.get_addr().await?;
+            let permit = match addr.reserve() {
+                Ok(permit) => permit,
+                Err(e) if e.is_stale_activation() && retries < policy.max_retries => {
+                    self.forget_stale(&addr, &mut retries).await;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let copy = policy
+                .retry_idempotent
+                .then(|| msg.idempotent_copy())
+                .flatten();
+            permit.dispatch(msg);
+            // activation started stopping right after delivery and may drop the message,
+            // idempotent message is delivered to fresh activation as well
+            match copy {
+                Some(copy) if addr.is_cancelled() && retries < policy.max_retries => {
+                    msg = copy;
+                    self.forget_stale(&addr, &mut retries).await;
+                }
+                _ => return Ok(()),
+            }
+        }
     }
 
     fn weak_ref(&self) -> Self::WeakRef {
@@ -0,0 +1,10 @@
+/// Membership start error
+#[derive(Debug, thiserror::Error)]
+pub enum MembershipError {
+    /// Membership is started once per runtime
+    #[error("Membership is already started")]
+    AlreadyStarted,
+    /// Membership requires `Runtime::start_transport`
+    #[error("Transport is not started")]
+    TransportNotStarted,
+}
@@ -0,0 +1,68 @@
+use std::{fmt::Display, net::SocketAddr};
+
+use serde::{Deserialize, Serialize};
+
+/// Lifecycle status of cluster member, later status supersedes earlier one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum MemberStatus {
+    /// Member is part of cluster
+    Up,
+    /// Member left cluster gracefully
+    Left,
+    /// Member is removed after being unreachable,
+    /// it is up with new incarnation once it is reachable again
+    Down,
+}
+
+/// Node of cluster
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Member {
+    /// Transport address of node
+    pub address: SocketAddr,
+    /// Distinguishes restarts of node with same address,
+    /// increased by node to refute being marked down
+    pub incarnation: u64,
+    /// Lifecycle status
+    pub status: MemberStatus,
+    /// Heartbeats of member are received by local node, not gossiped
+    pub reachable: bool,
+}
+
+impl Display for Member {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}#{} {:?}", self.address, self.incarnation, self.status)?;
+        if !self.reachable {
+            write!(f, " unreachable")?;
+        }
+        Ok(())
+    }
+}
+
+/// Change of cluster membership observed by local node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MembershipEvent {
+    /// Member joined cluster or restarted
+    MemberUp(Member),
+    /// Heartbeats of member are missing
+    MemberUnreachable(Member),
+    /// Heartbeats of unreachable member are received again
+    MemberReachable(Member),
+    /// Member left cluster gracefully
+    MemberLeft(Member),
+    /// Member is removed after being unreachable
+    MemberDown(Member),
+}
+
+impl MembershipEvent {
+    /// Member state after change
+    #[must_use]
+    pub fn member(&self) -> &Member {
+        match self {
+            Self::MemberUp(member)
+            | Self::MemberUnreachable(member)
+            | Self::MemberReachable(member)
+            | Self::MemberLeft(member)
+            | Self::MemberDown(member) => member,
+        }
+    }
+}
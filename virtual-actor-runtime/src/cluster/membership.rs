@@ -0,0 +1,356 @@
+use std::{
+    collections::{hash_map, HashMap},
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use futures::future;
+use serde::{Deserialize, Serialize};
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+use virtual_actor::{actor::ActorName, message::MessageName};
+
+use crate::{
+    errors::RemoteError,
+    runtime::WeakActorRegistry,
+    transport::{RemoteHandler, RemoteMessageRegistry, Request, TransportHandle},
+};
+
+use super::{
+    phi_accrual::PhiAccrualDetector, Member, MemberStatus, MembershipEvent, MembershipPreferences,
+};
+
+/// Receiver of membership messages, addressed like virtual actor
+const MEMBERSHIP: ActorName = "Membership";
+/// View of cluster exchanged by nodes, doubles as heartbeat
+const GOSSIP: MessageName = "Gossip";
+
+#[derive(Serialize, Deserialize)]
+struct Gossip {
+    /// Sending node
+    from: Member,
+    /// Members known by sending node, including itself
+    members: Vec<Member>,
+}
+
+/// Cluster membership of local node.
+/// Every heartbeat interval node exchanges gossip with every live or down member
+/// and unknown seed, received gossip is heartbeat of its sender.
+/// Down member which is reachable again refutes being marked down and is up again.
+pub struct Membership {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    state: Mutex<State>,
+    preferences: MembershipPreferences,
+    transport: TransportHandle,
+    registry: WeakActorRegistry,
+    cancellation: CancellationToken,
+}
+
+struct State {
+    local: Member,
+    members: HashMap<SocketAddr, MemberEntry>,
+    /// Local node left cluster, being marked down is not refuted
+    leaving: bool,
+}
+
+struct MemberEntry {
+    member: Member,
+    detector: PhiAccrualDetector,
+    unreachable_since: Option<Instant>,
+}
+
+impl Membership {
+    /// Registers gossip handler and starts heartbeats on transport thread
+    pub fn start(
+        local_address: SocketAddr,
+        preferences: MembershipPreferences,
+        transport: &TransportHandle,
+        messages: &RemoteMessageRegistry,
+        registry: WeakActorRegistry,
+    ) -> Self {
+        let incarnation = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX));
+        let local = Member {
+            address: local_address,
+            incarnation,
+            status: MemberStatus::Up,
+            reachable: true,
+        };
+        let inner = Arc::new(Inner {
+            state: Mutex::new(State {
+                local: local.clone(),
+                members: HashMap::new(),
+                leaving: false,
+            }),
+            preferences,
+            transport: transport.clone(),
+            registry,
+            cancellation: CancellationToken::new(),
+        });
+
+        let receiver = inner.clone();
+        let handler: RemoteHandler =
+            Arc::new(move |_, _, payload, _| Box::pin(future::ready(receiver.receive(payload))));
+        messages.register_handler(MEMBERSHIP, GOSSIP, handler);
+
+        inner.publish(vec![MembershipEvent::MemberUp(local)]);
+        transport.spawn(inner.clone().run());
+        Self { inner }
+    }
+
+    /// Leaves cluster, members are notified before heartbeats are stopped
+    pub async fn leave(&self) {
+        let inner = &self.inner;
+        let peers = {
+            let mut state = inner.state();
+            state.leaving = true;
+            state.local.status = MemberStatus::Left;
+            state.live_members()
+        };
+        inner.cancellation.cancel();
+        let exchanges = peers
+            .into_iter()
+            .map(|peer| inner.transport.spawn(inner.clone().exchange(peer)));
+        future::join_all(exchanges).await;
+    }
+}
+
+impl Inner {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("Membership lock poisoned")
+    }
+
+    /// Sends heartbeats and checks reachability of members until membership is stopped
+    async fn run(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(self.preferences.heartbeat_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                biased;
+                () = self.cancellation.cancelled() => return,
+                _ = interval.tick() => {}
+            }
+            let (events, peers) = {
+                let mut state = self.state();
+                let events = state.check_reachability(&self.preferences, Instant::now());
+                let mut peers = state.probed_members();
+                peers.extend(self.preferences.seeds.iter().filter(|seed| {
+                    **seed != state.local.address && !state.members.contains_key(seed)
+                }));
+                (events, peers)
+            };
+            self.publish(events);
+            for peer in peers {
+                tokio::spawn(self.clone().exchange(peer));
+            }
+        }
+    }
+
+    /// Sends gossip to peer and merges gossip received in response
+    async fn exchange(self: Arc<Self>, peer: SocketAddr) {
+        let request = match bincode::serialize(&self.gossip()) {
+            Ok(payload) => Request {
+                correlation_id: None,
+                actor_name: MEMBERSHIP.to_owned(),
+                message_name: GOSSIP.to_owned(),
+                actor_id: Vec::new(),
+                payload,
+            },
+            Err(e) => {
+                eprintln!("Failed to serialize gossip: {e}");
+                return;
+            }
+        };
+        // missing response is detected by failure detector
+        let Ok(response) = self.transport.request(peer, request).await else {
+            return;
+        };
+        match bincode::deserialize(&response) {
+            Ok(gossip) => self.merge(&gossip),
+            Err(e) => eprintln!("Failed to deserialize gossip of {peer}: {e}"),
+        }
+    }
+
+    /// Merges gossip received from peer, replies with local gossip
+    fn receive(&self, payload: &[u8]) -> Result<Vec<u8>, RemoteError> {
+        let gossip =
+            bincode::deserialize(payload).map_err(|e| RemoteError::Deserialize(e.to_string()))?;
+        self.merge(&gossip);
+        bincode::serialize(&self.gossip()).map_err(|e| RemoteError::Serialize(e.to_string()))
+    }
+
+    fn merge(&self, gossip: &Gossip) {
+        let now = Instant::now();
+        let events = {
+            let mut state = self.state();
+            let mut events = Vec::new();
+            for member in &gossip.members {
+                state.merge(member, &self.preferences, now, &mut events);
+            }
+            state.heartbeat(&gossip.from, now, &mut events);
+            events
+        };
+        self.publish(events);
+    }
+
+    fn gossip(&self) -> Gossip {
+        let state = self.state();
+        let mut members: Vec<_> = state
+            .members
+            .values()
+            .map(|entry| entry.member.clone())
+            .collect();
+        members.push(state.local.clone());
+        Gossip {
+            from: state.local.clone(),
+            members,
+        }
+    }
+
+    fn publish(&self, events: Vec<MembershipEvent>) {
+        for event in events {
+            self.registry.publish_membership_event(event);
+        }
+    }
+}
+
+impl State {
+    /// Addresses of members receiving heartbeats
+    fn live_members(&self) -> Vec<SocketAddr> {
+        self.members
+            .values()
+            .filter(|entry| entry.member.status == MemberStatus::Up)
+            .map(|entry| entry.member.address)
+            .collect()
+    }
+
+    /// Addresses of live members and down members, which may be reachable again
+    /// after network partition or restart. Members which left are not contacted.
+    fn probed_members(&self) -> Vec<SocketAddr> {
+        self.members
+            .values()
+            .filter(|entry| entry.member.status != MemberStatus::Left)
+            .map(|entry| entry.member.address)
+            .collect()
+    }
+
+    /// Merges member state gossiped by peer.
+    /// Newer incarnation replaces member, otherwise later status wins.
+    fn merge(
+        &mut self,
+        member: &Member,
+        preferences: &MembershipPreferences,
+        now: Instant,
+        events: &mut Vec<MembershipEvent>,
+    ) {
+        // reachability is observed by local node
+        let member = Member {
+            reachable: true,
+            ..*member
+        };
+        if member.address == self.local.address {
+            // refute being marked down or left by another node
+            if member.incarnation >= self.local.incarnation
+                && member.status != MemberStatus::Up
+                && !self.leaving
+            {
+                self.local.incarnation = member.incarnation + 1;
+            }
+            return;
+        }
+        match self.members.entry(member.address) {
+            hash_map::Entry::Vacant(vacant) => {
+                // members which are gone before local node joined are remembered silently
+                if member.status == MemberStatus::Up {
+                    events.push(MembershipEvent::MemberUp(member.clone()));
+                }
+                vacant.insert(MemberEntry::new(member, preferences, now));
+            }
+            hash_map::Entry::Occupied(mut occupied) => {
+                let known = &occupied.get().member;
+                if member.incarnation > known.incarnation {
+                    events.push(status_event(&member));
+                    occupied.insert(MemberEntry::new(member, preferences, now));
+                } else if member.incarnation == known.incarnation && member.status > known.status {
+                    let entry = occupied.get_mut();
+                    entry.member.status = member.status;
+                    events.push(status_event(&entry.member));
+                }
+            }
+        }
+    }
+
+    /// Records heartbeat of live member
+    fn heartbeat(&mut self, from: &Member, now: Instant, events: &mut Vec<MembershipEvent>) {
+        let Some(entry) = self.members.get_mut(&from.address) else {
+            return;
+        };
+        if entry.member.incarnation != from.incarnation || entry.member.status != MemberStatus::Up {
+            return;
+        }
+        entry.detector.heartbeat(now);
+        if !entry.member.reachable {
+            entry.member.reachable = true;
+            entry.unreachable_since = None;
+            events.push(MembershipEvent::MemberReachable(entry.member.clone()));
+        }
+    }
+
+    /// Marks members without heartbeats unreachable, and then down
+    fn check_reachability(
+        &mut self,
+        preferences: &MembershipPreferences,
+        now: Instant,
+    ) -> Vec<MembershipEvent> {
+        let mut events = Vec::new();
+        for entry in self.members.values_mut() {
+            if entry.member.status != MemberStatus::Up {
+                continue;
+            }
+            match entry.unreachable_since {
+                None if entry.detector.phi(now) > preferences.phi_threshold => {
+                    entry.member.reachable = false;
+                    entry.unreachable_since = Some(now);
+                    events.push(MembershipEvent::MemberUnreachable(entry.member.clone()));
+                }
+                Some(since) if now.saturating_duration_since(since) >= preferences.down_after => {
+                    entry.member.status = MemberStatus::Down;
+                    events.push(MembershipEvent::MemberDown(entry.member.clone()));
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+impl MemberEntry {
+    fn new(member: Member, preferences: &MembershipPreferences, now: Instant) -> Self {
+        Self {
+            member,
+            detector: PhiAccrualDetector::new(
+                preferences.heartbeat_interval,
+                preferences.min_std_deviation,
+                preferences.acceptable_heartbeat_pause,
+                preferences.max_samples,
+                now,
+            ),
+            unreachable_since: None,
+        }
+    }
+}
+
+/// Event announcing member status
+fn status_event(member: &Member) -> MembershipEvent {
+    let member = member.clone();
+    match member.status {
+        MemberStatus::Up => MembershipEvent::MemberUp(member),
+        MemberStatus::Left => MembershipEvent::MemberLeft(member),
+        MemberStatus::Down => MembershipEvent::MemberDown(member),
+    }
+}
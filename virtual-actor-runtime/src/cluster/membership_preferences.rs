@@ -0,0 +1,34 @@
+use std::{net::SocketAddr, time::Duration};
+
+/// Cluster membership settings
+pub struct MembershipPreferences {
+    /// Transport addresses of nodes contacted to join cluster
+    pub seeds: Vec<SocketAddr>,
+    /// Interval between heartbeats sent to every member
+    pub heartbeat_interval: Duration,
+    /// Suspicion level of failure detector at which member is unreachable
+    pub phi_threshold: f64,
+    /// Lower bound of standard deviation of heartbeat intervals,
+    /// avoids false positives when heartbeats are very regular, at least 1 ms is used
+    pub min_std_deviation: Duration,
+    /// Missing heartbeats tolerated before suspicion rises, e.g. GC or network pauses
+    pub acceptable_heartbeat_pause: Duration,
+    /// Number of heartbeat intervals kept by failure detector
+    pub max_samples: usize,
+    /// Time member may stay unreachable before it is marked down
+    pub down_after: Duration,
+}
+
+impl Default for MembershipPreferences {
+    fn default() -> Self {
+        Self {
+            seeds: Vec::new(),
+            heartbeat_interval: Duration::from_secs(1),
+            phi_threshold: 8.0,
+            min_std_deviation: Duration::from_millis(100),
+            acceptable_heartbeat_pause: Duration::from_secs(3),
+            max_samples: 200,
+            down_after: Duration::from_secs(10),
+        }
+    }
+}
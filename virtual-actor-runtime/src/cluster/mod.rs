@@ -0,0 +1,11 @@
+//! Cluster membership with failure detection
+
+pub mod errors;
+mod member;
+mod membership;
+mod membership_preferences;
+mod phi_accrual;
+
+pub use member::{Member, MemberStatus, MembershipEvent};
+pub use membership::Membership;
+pub use membership_preferences::MembershipPreferences;
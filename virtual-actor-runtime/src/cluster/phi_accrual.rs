@@ -0,0 +1,132 @@
+//! Phi accrual failure detector, see
+//! "The φ Accrual Failure Detector" by Hayashibara et al.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Lower bound of standard deviation in milliseconds,
+/// phi is not defined for heartbeats arriving with zero deviation
+const MIN_STD_DEVIATION: f64 = 1.0;
+
+/// Estimates suspicion that member failed from history of heartbeat intervals.
+/// Phi is `-log10` of probability that heartbeat arrives later than now.
+pub struct PhiAccrualDetector {
+    /// Heartbeat intervals in milliseconds
+    intervals: VecDeque<f64>,
+    max_samples: usize,
+    min_std_deviation: f64,
+    acceptable_pause: f64,
+    last_heartbeat: Instant,
+}
+
+impl PhiAccrualDetector {
+    /// Creates detector bootstrapped with expected interval, heartbeat is expected since `now`.
+    /// `min_std_deviation` is at least 1 ms.
+    pub fn new(
+        expected_interval: Duration,
+        min_std_deviation: Duration,
+        acceptable_pause: Duration,
+        max_samples: usize,
+        now: Instant,
+    ) -> Self {
+        let expected = millis(expected_interval);
+        let deviation = expected / 4.0;
+        Self {
+            // first heartbeat is not available, so history starts with expected interval
+            intervals: VecDeque::from([expected - deviation, expected + deviation]),
+            max_samples: max_samples.max(2),
+            min_std_deviation: millis(min_std_deviation).max(MIN_STD_DEVIATION),
+            acceptable_pause: millis(acceptable_pause),
+            last_heartbeat: now,
+        }
+    }
+
+    /// Records heartbeat arrival
+    pub fn heartbeat(&mut self, now: Instant) {
+        let interval = millis(now.saturating_duration_since(self.last_heartbeat));
+        self.last_heartbeat = now;
+        if self.intervals.len() >= self.max_samples {
+            self.intervals.pop_front();
+        }
+        self.intervals.push_back(interval);
+    }
+
+    /// Suspicion level, grows while heartbeat is missing
+    pub fn phi(&self, now: Instant) -> f64 {
+        #[allow(clippy::cast_precision_loss)] // number of samples is small
+        let count = self.intervals.len() as f64;
+        let mean = self.intervals.iter().sum::<f64>() / count;
+        let variance = self
+            .intervals
+            .iter()
+            .map(|i| (i - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        let std_deviation = variance.sqrt().max(self.min_std_deviation);
+
+        let elapsed = millis(now.saturating_duration_since(self.last_heartbeat));
+        // logistic approximation of normal distribution CDF
+        let y = (elapsed - (mean + self.acceptable_pause)) / std_deviation;
+        let e = (-y * (1.5976 + 0.070_566 * y * y)).exp();
+        if elapsed > mean + self.acceptable_pause {
+            -(e / (1.0 + e)).log10()
+        } else {
+            -(1.0 - 1.0 / (1.0 + e)).log10()
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phi_grows_while_heartbeat_is_missing() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(100);
+        let mut detector = PhiAccrualDetector::new(
+            interval,
+            Duration::from_millis(10),
+            Duration::ZERO,
+            100,
+            start,
+        );
+        let mut now = start;
+        for _ in 0..10 {
+            now += interval;
+            detector.heartbeat(now);
+        }
+
+        let on_time = detector.phi(now + interval);
+        let late = detector.phi(now + interval * 2);
+        let very_late = detector.phi(now + interval * 5);
+        assert!(on_time < 1.0, "{on_time}");
+        assert!(late > on_time, "{late} > {on_time}");
+        assert!(very_late > 8.0, "{very_late}");
+
+        detector.heartbeat(now + interval * 5);
+        assert!(detector.phi(now + interval * 5) < 1.0);
+    }
+
+    #[test]
+    fn phi_is_defined_for_regular_heartbeats() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(100);
+        let mut detector =
+            PhiAccrualDetector::new(interval, Duration::ZERO, Duration::ZERO, 2, start);
+        detector.heartbeat(start + interval);
+        detector.heartbeat(start + interval * 2);
+
+        for elapsed in [0, 1, 2, 3] {
+            let phi = detector.phi(start + interval * (2 + elapsed));
+            assert!(!phi.is_nan(), "{phi} after {elapsed} intervals");
+        }
+        assert!(detector.phi(start + interval * 5) > 8.0);
+    }
+}
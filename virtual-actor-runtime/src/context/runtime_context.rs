@@ -1,14 +1,18 @@
 //! Runtime context for actor.
 
+use std::{future::Future, sync::Arc, time::Duration};
+
 use tokio_util::sync::CancellationToken;
 use virtual_actor::actor::{Actor, ActorAddr, ActorContext};
 use virtual_actor::virtual_actor::VirtualActor;
 
-use crate::runtime::errors::ActivateActorError;
+use crate::runtime::errors::{ActivateActorError, BlockingTaskError};
+use crate::runtime::BlockingPools;
+use crate::utils::sleep::{sleep_with_cancel, SleepWaitError};
 use crate::{
-    address::{LocalAddr, VirtualAddr},
+    address::{Deactivation, LocalAddr, VirtualAddr},
     runtime::WeakActorRegistry,
-    utils::cancellation_token_wrapper::CancellationTokenWrapper,
+    utils::{atomic_counter::AtomicCounter, cancellation_token_wrapper::CancellationTokenWrapper},
     WeakLocalAddr,
 };
 
@@ -22,20 +26,32 @@ pub struct RuntimeContext<A: Actor> {
     mailbox_cancellation_token: CancellationToken,
     /// Actor registry
     registry: WeakActorRegistry,
+    /// Pools for offloaded work
+    blocking_pools: BlockingPools,
+    /// Memory used by actor state, as reported by actor
+    memory_usage: AtomicCounter,
+    /// Deactivation requested by actor
+    deactivation: Arc<Deactivation>,
 }
 
 impl<A: Actor> RuntimeContext<A> {
     pub(crate) fn new(
         registry: WeakActorRegistry,
+        blocking_pools: BlockingPools,
         self_addr_weak: WeakLocalAddr<A>,
         mailbox_cancellation_token: &CancellationToken,
         cancellation_token: &CancellationToken,
+        memory_usage: &AtomicCounter,
+        deactivation: &Arc<Deactivation>,
     ) -> Self {
         Self {
             self_addr_weak,
             mailbox_cancellation_token: mailbox_cancellation_token.clone(),
             cancellation_token: CancellationTokenWrapper::new(cancellation_token.clone()),
             registry,
+            blocking_pools,
+            memory_usage: memory_usage.clone(),
+            deactivation: deactivation.clone(),
         }
     }
 
@@ -51,6 +67,101 @@ impl<A: Actor> RuntimeContext<A> {
     ) -> Result<VirtualAddr<VA>, ActivateActorError> {
         self.registry.get_or_create(id)
     }
+
+    /// Virtual actor has activation on this node which is not stopped yet
+    pub fn is_active<VA: VirtualActor>(&self, id: &VA::ActorId) -> bool {
+        self.registry.is_active::<VA>(id)
+    }
+
+    /// Reports estimated memory used by actor state in bytes.
+    /// Estimates are summed up to detect memory pressure,
+    /// see `RuntimePreferences::memory_pressure`.
+    pub fn report_memory_usage(&self, bytes: usize) {
+        self.memory_usage.set(bytes);
+    }
+
+    /// Requests deactivation of virtual actor once its mailbox is empty.
+    /// Activation is detached from registry immediately,
+    /// next message activates fresh instance after this one is stopped.
+    /// Cancels `delay_deactivation`. Has no effect on local actors.
+    pub fn deactivate_on_idle(&self) {
+        self.deactivation.request_on_idle();
+    }
+
+    /// Keeps virtual actor from idle garbage collection for at least `duration`.
+    /// Ignored after `deactivate_on_idle`.
+    pub fn delay_deactivation(&self, duration: Duration) {
+        self.deactivation.delay(duration);
+    }
+
+    /// Keeps virtual actor from idle garbage collection and eviction
+    /// until matching `release_activation`, e.g. while other actors depend on its in-memory state.
+    /// Activation is still stopped on `deactivate_on_idle` and runtime shutdown.
+    pub fn hold_activation(&self) {
+        self.deactivation.hold();
+    }
+
+    /// Releases hold taken by `hold_activation`
+    pub fn release_activation(&self) {
+        self.deactivation.release();
+    }
+
+    /// Sleeps for `duration` unless actor is stopped, cancelled or runtime is shutting down
+    ///
+    /// # Errors
+    ///
+    /// Returns error if sleep is interrupted
+    pub async fn sleep(&self, duration: Duration) -> Result<(), SleepWaitError> {
+        sleep_with_cancel(
+            duration,
+            &self.mailbox_cancellation_token,
+            &self.cancellation_token,
+        )
+        .await
+    }
+
+    /// Runs `after_message` hook of idle virtual actor after `duration`,
+    /// e.g. to save changes buffered by the hook without waiting for next message.
+    /// Earliest of pending requests wins. Has no effect on local actors.
+    pub fn wake_after(&self, duration: Duration) {
+        self.deactivation.wake_after(duration);
+    }
+
+    /// Runs blocking function, e.g. blocking IO, on runtime blocking pool
+    /// without stalling actors on the same executor.
+    /// Returned future resolves on actor's executor and is cancelled with the actor,
+    /// not started function is skipped after cancellation.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if actor is cancelled, function panicked or runtime is stopped
+    pub fn spawn_blocking<F, R>(&self, f: F) -> impl Future<Output = Result<R, BlockingTaskError>>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.blocking_pools
+            .blocking
+            .spawn(f, self.cancellation_token.inner())
+    }
+
+    /// Runs CPU-heavy function on runtime compute pool
+    /// without stalling actors on the same executor.
+    /// Returned future resolves on actor's executor and is cancelled with the actor,
+    /// not started function is skipped after cancellation.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if actor is cancelled, function panicked or runtime is stopped
+    pub fn spawn_compute<F, R>(&self, f: F) -> impl Future<Output = Result<R, BlockingTaskError>>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.blocking_pools
+            .compute
+            .spawn(f, self.cancellation_token.inner())
+    }
 }
 
 impl<A> Clone for RuntimeContext<A>
@@ -63,6 +174,9 @@ where
             mailbox_cancellation_token: self.mailbox_cancellation_token.clone(),
             cancellation_token: self.cancellation_token.clone(),
             registry: self.registry.clone(),
+            blocking_pools: self.blocking_pools.clone(),
+            memory_usage: self.memory_usage.clone(),
+            deactivation: self.deactivation.clone(),
         }
     }
 }
@@ -2,7 +2,11 @@ use std::marker::PhantomData;
 
 use virtual_actor::actor::{Actor, ActorContext};
 
-use crate::{address::ActorHandle, runtime::WeakActorRegistry, LocalAddr, WeakLocalAddr};
+use crate::{
+    address::ActorHandle,
+    runtime::{BlockingPools, WeakActorRegistry},
+    LocalAddr, WeakLocalAddr,
+};
 
 use super::{context_factory_trait::ActorContextFactory, runtime_context::RuntimeContext};
 
@@ -12,15 +16,18 @@ pub struct RuntimeContextFactory<A: Actor> {
     _a: PhantomData<fn(A) -> A>,
     /// Actor registry
     registry: WeakActorRegistry,
+    /// Pools for offloaded work
+    blocking_pools: BlockingPools,
 }
 
 impl<A: Actor> RuntimeContextFactory<A> {
     /// Creates new runtime context factory
     #[must_use]
-    pub fn new(registry: WeakActorRegistry) -> Self {
+    pub fn new(registry: WeakActorRegistry, blocking_pools: BlockingPools) -> Self {
         Self {
             _a: PhantomData,
             registry,
+            blocking_pools,
         }
     }
 }
@@ -34,9 +41,12 @@ where
         let weak_addr = WeakLocalAddr::new(handle);
         RuntimeContext::new(
             self.registry.clone(),
+            self.blocking_pools.clone(),
             weak_addr,
             handle.mailbox_cancellation(),
             handle.cancellation_token(),
+            handle.memory_usage(),
+            handle.deactivation(),
         )
     }
 }
@@ -29,6 +29,9 @@ pub enum ActorTaskError {
     /// After message hook error
     #[error("After message hook error {0:?}")]
     AfterMessageHookError(BoxedActorError),
+    /// Actor deactivation error
+    #[error("Actor deactivation error {0:?}")]
+    ActorDeactivationError(BoxedActorError),
 }
 
 impl ActorTaskError {
@@ -37,6 +40,12 @@ impl ActorTaskError {
     pub fn actor_factory_error<E: std::error::Error + 'static + Send + Sync>(e: E) -> Self {
         Self::ActorFactoryError(BoxedActorError::new(e))
     }
+
+    /// Creates new actor deactivation error
+    #[must_use]
+    pub fn actor_deactivation_error<E: std::error::Error + 'static + Send + Sync>(e: E) -> Self {
+        Self::ActorDeactivationError(BoxedActorError::new(e))
+    }
 }
 
 /// Error occurred during actor spawn
@@ -4,10 +4,12 @@ use tokio::select;
 use virtual_actor::{
     actor::{Actor, ActorContext, ActorFactory},
     local_actor::{LocalActor, LocalActorFactory},
+    message::MessageEnvelope,
 };
 
 use crate::{
-    address::ActorHandle, context::ActorContextFactory, utils::notify_once::NotifyOnce, LocalAddr,
+    address::ActorHandle, context::ActorContextFactory, messaging::CallScope,
+    utils::notify_once::NotifyOnce, LocalAddr,
 };
 
 use super::{super::actor_loop::ActorLoop, super::errors::ActorTaskError, super::mailbox::Mailbox};
@@ -76,15 +78,20 @@ where
 
         let context = context_factory.create_context(&handle);
         let task_ct = handle.cancellation_token();
+        let call_chain_entry = handle.call_chain_entry();
+        let monitor = handle.executor_monitor();
 
         actor_started.notify();
 
-        while let Some(envelope) = mailbox.recv(task_ct).await {
+        while let Some((envelope, metadata)) = mailbox.recv(task_ct).await {
+            let call_scope = CallScope::new(metadata.call_chain, call_chain_entry);
+            let running = monitor.track_handler(call_chain_entry, envelope.message_name(), task_ct);
             select! {
                 biased;
                 () = task_ct.cancelled() => Err(ActorTaskError::Cancelled),
-                r = actor.handle_envelope(envelope, &context) => r.map_err(ActorTaskError::ResponderError),
+                r = call_scope.run(actor.handle_envelope(envelope, &context)) => r.map_err(ActorTaskError::ResponderError),
             }?;
+            drop(running);
         }
         Ok(())
     }
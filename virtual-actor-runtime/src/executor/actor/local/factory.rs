@@ -7,8 +7,8 @@ use virtual_actor::{
 };
 
 use crate::{
-    address::ActorHandle, context::ActorContextFactory, utils::atomic_counter::AtomicCounter,
-    LocalAddr,
+    address::ActorHandle, context::ActorContextFactory, executor::ExecutorMonitor,
+    messaging::CallChainEntry, utils::atomic_counter::AtomicCounter, LocalAddr,
 };
 
 use super::{
@@ -25,6 +25,7 @@ pub fn create_local_actor<AF, CF>(
     context_factory: &Arc<CF>,
     execution_cancellation: CancellationToken,
     mailbox_cancellation: CancellationToken,
+    executor_monitor: &ExecutorMonitor,
 ) -> (
     Box<dyn LocalSpawnedActor>,
     ActorHandle<<AF as ActorFactory>::Actor>,
@@ -43,6 +44,8 @@ where
         execution_cancellation,
         mailbox_cancellation,
         dispatched_msg_counter.clone(),
+        CallChainEntry::new(<AF as ActorFactory>::Actor::name(), None),
+        executor_monitor.clone(),
     );
     let actor_loop = LocalActorLoop::default();
     let spawner = LocalSpawnedActorImpl::new(
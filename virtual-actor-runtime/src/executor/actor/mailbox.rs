@@ -5,12 +5,12 @@ use virtual_actor::actor::Actor;
 use virtual_actor::message::MailboxPreferences;
 
 use crate::messaging::Mailbox as BaseMailbox;
-use crate::messaging::MessageDispatcher;
+use crate::messaging::{EnvelopeWithMetadata, MessageDispatcher};
 use crate::utils::atomic_counter::AtomicCounter;
 
 /// Mailbox for actor
 pub struct Mailbox<A: Actor> {
-    inner: BaseMailbox<A::MessagesEnvelope>,
+    inner: BaseMailbox<EnvelopeWithMetadata<A>>,
 }
 
 impl<A: Actor> Mailbox<A> {
@@ -25,8 +25,13 @@ impl<A: Actor> Mailbox<A> {
         (dispatcher, Self { inner })
     }
 
+    /// Stops receiving new messages, already queued messages are still received
+    pub fn close(&mut self) {
+        self.inner.close();
+    }
+
     /// Receive message from mailbox
-    pub async fn recv(&mut self, ct: &CancellationToken) -> Option<A::MessagesEnvelope> {
+    pub async fn recv(&mut self, ct: &CancellationToken) -> Option<EnvelopeWithMetadata<A>> {
         self.inner.recv(ct).await
     }
 }
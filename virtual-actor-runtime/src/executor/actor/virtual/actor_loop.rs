@@ -3,9 +3,11 @@ use std::{marker::PhantomData, sync::Arc};
 use tokio::select;
 use virtual_actor::{
     actor::{Actor, ActorContext, ActorFactory},
+    message::MessageEnvelope,
     virtual_actor::{VirtualActor, VirtualActorFactory},
 };
 
+use crate::messaging::CallScope;
 use crate::utils::atomic_counter::AtomicCounter;
 use crate::utils::notify_once::NotifyOnce;
 use crate::{address::ActorHandle, context::ActorContextFactory, LocalAddr};
@@ -93,23 +95,73 @@ where
 
         actor_started.notify();
 
-        while let Some(envelope) = mailbox.recv(task_ct).await {
-            select! {
-                biased;
-                () = task_ct.cancelled() => Err(ActorTaskError::Cancelled),
-                r = actor.before_message(&envelope, &context) => r.map_err(ActorTaskError::BeforeMessageHookError),
-            }?;
-            select! {
-                biased;
-                () = task_ct.cancelled() => Err(ActorTaskError::Cancelled),
-                r = actor.handle_envelope(envelope, &context) => r.map_err(ActorTaskError::ResponderError),
-            }?;
+        let call_chain_entry = handle.call_chain_entry();
+        let monitor = handle.executor_monitor();
+        let deactivation = handle.deactivation();
+
+        loop {
+            let received = match deactivation.wake_at() {
+                Some(wake_at) => select! {
+                    biased;
+                    received = mailbox.recv(task_ct) => Some(received),
+                    () = tokio::time::sleep_until(wake_at.into()) => None,
+                },
+                None => Some(mailbox.recv(task_ct).await),
+            };
+            let Some(received) = received else {
+                // idle wakeup requested by actor
+                deactivation.clear_wakeup();
+                let running = monitor.track_handler(call_chain_entry, "wakeup", task_ct);
+                select! {
+                    biased;
+                    () = task_ct.cancelled() => Err(ActorTaskError::Cancelled),
+                    r = actor.after_message(&context) => r.map_err(ActorTaskError::AfterMessageHookError),
+                }?;
+                drop(running);
+                continue;
+            };
+            let Some((envelope, metadata)) = received else {
+                break;
+            };
+            let call_scope = CallScope::new(metadata.call_chain, call_chain_entry);
+            let running = monitor.track_handler(call_chain_entry, envelope.message_name(), task_ct);
+            call_scope
+                .run(async {
+                    select! {
+                        biased;
+                        () = task_ct.cancelled() => Err(ActorTaskError::Cancelled),
+                        r = actor.before_message(&envelope, &context) => r.map_err(ActorTaskError::BeforeMessageHookError),
+                    }?;
+                    select! {
+                        biased;
+                        () = task_ct.cancelled() => Err(ActorTaskError::Cancelled),
+                        r = actor.handle_envelope(envelope, &context) => r.map_err(ActorTaskError::ResponderError),
+                    }?;
+                    select! {
+                        biased;
+                        () = task_ct.cancelled() => Err(ActorTaskError::Cancelled),
+                        r = actor.after_message(&context) => r.map_err(ActorTaskError::AfterMessageHookError),
+                    }
+                })
+                .await?;
+            drop(running);
+            self.processed_msg_counter.increment();
+
+            // activation is already detached from registry, finish once mailbox is drained
+            if deactivation.is_requested_on_idle()
+                && handle.dispatched_msg_counter().get() == self.processed_msg_counter.get()
+            {
+                mailbox.close();
+            }
+        }
+
+        // mailbox is drained, otherwise actor execution is cancelled
+        if !task_ct.is_cancelled() {
             select! {
                 biased;
                 () = task_ct.cancelled() => Err(ActorTaskError::Cancelled),
-                r = actor.after_message(&context) => r.map_err(ActorTaskError::AfterMessageHookError),
+                r = actor_factory.deactivate_actor(&mut actor) => r.map_err(ActorTaskError::actor_deactivation_error),
             }?;
-            self.processed_msg_counter.increment();
         }
         //println!("Actor {id} is finished", id = self.actor_id);
         Ok(())
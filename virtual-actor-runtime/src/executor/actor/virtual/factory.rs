@@ -7,8 +7,8 @@ use virtual_actor::{
 };
 
 use crate::{
-    address::ActorHandle, context::ActorContextFactory, utils::atomic_counter::AtomicCounter,
-    LocalAddr,
+    address::ActorHandle, context::ActorContextFactory, executor::ExecutorMonitor,
+    messaging::CallChainEntry, utils::atomic_counter::AtomicCounter, LocalAddr,
 };
 
 use super::{
@@ -26,6 +26,7 @@ pub fn create_virtual_actor<AF, CF>(
     context_factory: &Arc<CF>,
     execution_cancellation: CancellationToken,
     mailbox_cancellation: CancellationToken,
+    executor_monitor: &ExecutorMonitor,
 ) -> (
     Box<dyn LocalSpawnedActor>,
     ActorHandle<<AF as ActorFactory>::Actor>,
@@ -44,6 +45,11 @@ where
         execution_cancellation,
         mailbox_cancellation,
         dispatched_msg_counter.clone(),
+        CallChainEntry::new(
+            <AF as ActorFactory>::Actor::name(),
+            Some(actor_id.to_string()),
+        ),
+        executor_monitor.clone(),
     );
     let actor_loop = VirtualActorLoop::new(actor_id, handle.processed_msg_counter());
     let spawner = LocalSpawnedActorImpl::new(
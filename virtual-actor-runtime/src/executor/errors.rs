@@ -28,6 +28,9 @@ pub enum LocalExecutorError {
     /// Unable to start thread
     #[error("Unable to spawn thread {0:?}")]
     ThreadSpawnError(std::io::Error),
+    /// Unable to apply thread affinity or priority
+    #[error("Unable to apply thread settings {0:?}")]
+    ThreadSettingsError(std::io::Error),
     /// Actor start error
     #[error("Actor start error {0:?}")]
     ActorStartError(#[from] ActorStartError),
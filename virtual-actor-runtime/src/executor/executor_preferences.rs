@@ -1,5 +1,7 @@
 use virtual_actor::message::MailboxPreferences;
 
+use super::ThreadPriority;
+
 /// Preferences for the Tokio runtime fro executor
 pub struct TokioRuntimePreferences {
     /// Enable I/O
@@ -27,6 +29,10 @@ pub struct ExecutorPreferences {
     pub mailbox_preferences: MailboxPreferences,
     /// Tokio runtime preferences
     pub tokio_runtime_preferences: TokioRuntimePreferences,
+    /// Cores executor thread is pinned to, Linux only
+    pub cpu_affinity: Option<Vec<usize>>,
+    /// Scheduling priority of executor thread, Linux only
+    pub thread_priority: Option<ThreadPriority>,
 }
 
 impl Default for ExecutorPreferences {
@@ -36,6 +42,8 @@ impl Default for ExecutorPreferences {
             thread_stack_size: None,
             mailbox_preferences: MailboxPreferences { size: 1024 },
             tokio_runtime_preferences: TokioRuntimePreferences::default(),
+            cpu_affinity: None,
+            thread_priority: None,
         }
     }
 }
@@ -13,6 +13,7 @@ use super::{
     actor::{self, LocalSpawnedActor},
     errors::LocalExecutorError,
     spawner::SpawnerDispatcher,
+    ExecutorMonitor, ExecutorThreadInfo,
 };
 
 /// Handle to executor
@@ -28,6 +29,10 @@ struct InnerHandle {
     executor_cancellation: CancellationToken,
     /// Cancellation actor message processing
     mailbox_cancellation: CancellationToken,
+    /// Executor monitor
+    monitor: ExecutorMonitor,
+    /// Settings applied to executor thread
+    thread_info: ExecutorThreadInfo,
 }
 
 impl Handle {
@@ -36,12 +41,16 @@ impl Handle {
         spawner_dispatcher: SpawnerDispatcher,
         executor_cancellation: CancellationToken,
         mailbox_cancellation: CancellationToken,
+        monitor: ExecutorMonitor,
+        thread_info: ExecutorThreadInfo,
     ) -> Self {
         Self {
             inner: Arc::new(InnerHandle {
                 spawner_dispatcher,
                 executor_cancellation,
                 mailbox_cancellation,
+                monitor,
+                thread_info,
             }),
         }
     }
@@ -56,6 +65,17 @@ impl Handle {
         &self.inner.mailbox_cancellation
     }
 
+    /// Accessor to executor monitor
+    pub(crate) fn monitor(&self) -> &ExecutorMonitor {
+        &self.inner.monitor
+    }
+
+    /// Settings applied to executor thread
+    #[must_use]
+    pub fn thread_info(&self) -> &ExecutorThreadInfo {
+        &self.inner.thread_info
+    }
+
     /// Spawns local actor on thread
     ///
     /// # Errors
@@ -130,9 +150,13 @@ impl Handle {
         <AF as ActorFactory>::Actor: VirtualActor + 'static,
         CF: ActorContextFactory<<AF as ActorFactory>::Actor> + 'static,
     {
-        self.spawn_actor_no_wait(actor_factory, context_factory, |af, cf, ct, m_ct| {
-            actor::create_virtual_actor(actor_id, af, cf, ct, m_ct)
-        })
+        self.spawn_actor_no_wait(
+            actor_factory,
+            context_factory,
+            |af, cf, ct, m_ct, monitor| {
+                actor::create_virtual_actor(actor_id, af, cf, ct, m_ct, monitor)
+            },
+        )
     }
 
     /// Spawns actor on thread, without waiting for dispatcher to be set
@@ -159,6 +183,7 @@ impl Handle {
             &Arc<CF>,
             CancellationToken,
             CancellationToken,
+            &ExecutorMonitor,
         ) -> (
             Box<dyn LocalSpawnedActor>,
             ActorHandle<<AF as ActorFactory>::Actor>,
@@ -166,8 +191,13 @@ impl Handle {
     {
         let execution_ct = self.inner.executor_cancellation.child_token();
         let mailbox_ct = self.inner.mailbox_cancellation.child_token();
-        let (local_actor, handle) =
-            spawner(actor_factory, context_factory, execution_ct, mailbox_ct);
+        let (local_actor, handle) = spawner(
+            actor_factory,
+            context_factory,
+            execution_ct,
+            mailbox_ct,
+            &self.inner.monitor,
+        );
 
         self.inner
             .spawner_dispatcher
@@ -196,6 +226,7 @@ impl Handle {
             &Arc<CF>,
             CancellationToken,
             CancellationToken,
+            &ExecutorMonitor,
         ) -> (
             Box<dyn LocalSpawnedActor>,
             ActorHandle<<AF as ActorFactory>::Actor>,
@@ -1,5 +1,6 @@
 //! Local executor for actor
 
+use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread::JoinHandle;
 
@@ -18,6 +19,8 @@ use super::executor_preferences::TokioRuntimePreferences;
 use super::handle::Handle;
 use super::local_set_wrapper::LocalSetWrapper;
 use super::spawner::LocalSpawner;
+use super::thread_settings::{apply_thread_settings, ExecutorThreadInfo};
+use super::ExecutorMonitor;
 use super::ExecutorPreferences;
 
 /// Std thread handle
@@ -111,7 +114,7 @@ impl LocalExecutor {
             local_set_stopped.clone(),
             local_set_cancellation.clone(),
         );
-        let thread_handle = Self::start_thread(
+        let (thread_handle, thread_info) = Self::start_thread(
             preferences,
             spawner,
             local_set_stopped.clone(),
@@ -119,10 +122,13 @@ impl LocalExecutor {
             local_set_cancellation.clone(),
         )?;
 
+        let monitor = ExecutorMonitor::new(name.clone(), spawner_dispatcher.clone());
         let self_handle = Handle::new(
             spawner_dispatcher,
             executor_cancellation,
             mailbox_cancellation,
+            monitor,
+            thread_info,
         );
 
         Ok(Self {
@@ -163,7 +169,7 @@ impl LocalExecutor {
         local_set_stopped: Arc<Notify>,
         thread_stopped: Arc<Notify>,
         local_set_cancellation: CancellationToken,
-    ) -> Result<JoinHandle<()>, LocalExecutorError> {
+    ) -> Result<(JoinHandle<()>, ExecutorThreadInfo), LocalExecutorError> {
         let rt = Self::build_runtime(&executor_preferences.tokio_runtime_preferences)?;
 
         let mut thread_builder =
@@ -173,8 +179,23 @@ impl LocalExecutor {
             thread_builder = thread_builder.stack_size(stack_size);
         }
 
+        let thread_name = executor_preferences.thread_name.clone();
+        let cpu_affinity = executor_preferences.cpu_affinity.clone();
+        let thread_priority = executor_preferences.thread_priority;
+        let (settings_tx, settings_rx) = mpsc::channel();
+
         let handle = thread_builder
             .spawn(move || {
+                // settings are applied before any actor is spawned on the thread
+                let settings =
+                    apply_thread_settings(&thread_name, cpu_affinity.as_deref(), thread_priority);
+                let applied = settings.is_ok();
+                let _ = settings_tx.send(settings);
+                if !applied {
+                    thread_stopped.notify_one();
+                    return;
+                }
+
                 let local = LocalSetWrapper::new();
                 local.spawn_local(spawner.run());
                 local.run(&rt, &local_set_stopped, &local_set_cancellation);
@@ -183,7 +204,11 @@ impl LocalExecutor {
             })
             .map_err(LocalExecutorError::ThreadSpawnError)?;
 
-        Ok(handle)
+        let thread_info = settings_rx
+            .recv()?
+            .map_err(LocalExecutorError::ThreadSettingsError)?;
+
+        Ok((handle, thread_info))
     }
 
     fn build_runtime(preferences: &TokioRuntimePreferences) -> Result<Runtime, LocalExecutorError> {
@@ -6,9 +6,13 @@ mod executor_preferences;
 mod handle;
 mod local_executor;
 mod local_set_wrapper;
+mod monitor;
 mod spawner;
+mod thread_settings;
 
 pub use executor_preferences::ExecutorPreferences;
 pub use executor_preferences::TokioRuntimePreferences;
 pub use handle::Handle;
 pub use local_executor::LocalExecutor;
+pub use monitor::ExecutorMonitor;
+pub use thread_settings::{ExecutorThreadInfo, ThreadPriority};
@@ -0,0 +1,191 @@
+//! Executor state observed by watchdog
+
+use std::{
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use tokio_util::sync::CancellationToken;
+use virtual_actor::message::MessageName;
+
+use crate::messaging::CallChainEntry;
+
+use super::{
+    actor::{errors::ActorSpawnError, LocalSpawnedActor},
+    spawner::SpawnerDispatcher,
+};
+
+/// Monitor of executor thread responsiveness and running message handlers
+#[derive(Clone)]
+pub struct ExecutorMonitor {
+    inner: Arc<MonitorInner>,
+}
+
+struct MonitorInner {
+    /// Executor thread name
+    name: String,
+    /// Spawner dispatcher used to deliver heartbeat probes
+    spawner_dispatcher: SpawnerDispatcher,
+    /// Heartbeat probe state
+    heartbeat: Mutex<Heartbeat>,
+    /// Message handlers currently running on executor, by actor instance
+    running_handlers: DashMap<u64, RunningHandler>,
+}
+
+#[derive(Default)]
+struct Heartbeat {
+    /// Time when pending probe was sent
+    probe_sent_at: Option<Instant>,
+    /// Pending probe was already reported as late
+    reported: bool,
+}
+
+struct RunningHandler {
+    actor: CallChainEntry,
+    message_name: MessageName,
+    started_at: Instant,
+    execution_cancellation: CancellationToken,
+    reported: bool,
+}
+
+/// Message handler running longer than budget
+pub struct StuckHandler {
+    /// Actor processing message
+    pub actor: CallChainEntry,
+    /// Name of message being processed
+    pub message_name: MessageName,
+    /// Time since handler started
+    pub running_for: Duration,
+    /// Actor execution cancellation token
+    pub execution_cancellation: CancellationToken,
+}
+
+impl ExecutorMonitor {
+    /// Creates monitor for executor
+    pub(crate) fn new(name: String, spawner_dispatcher: SpawnerDispatcher) -> Self {
+        Self {
+            inner: Arc::new(MonitorInner {
+                name,
+                spawner_dispatcher,
+                heartbeat: Mutex::new(Heartbeat::default()),
+                running_handlers: DashMap::new(),
+            }),
+        }
+    }
+
+    /// Executor thread name
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    /// Marks message handler as running until returned guard is dropped
+    pub(crate) fn track_handler(
+        &self,
+        actor: &CallChainEntry,
+        message_name: MessageName,
+        execution_cancellation: &CancellationToken,
+    ) -> RunningHandlerGuard<'_> {
+        let instance = actor.instance();
+        self.inner.running_handlers.insert(
+            instance,
+            RunningHandler {
+                actor: actor.clone(),
+                message_name,
+                started_at: Instant::now(),
+                execution_cancellation: execution_cancellation.clone(),
+                reported: false,
+            },
+        );
+        RunningHandlerGuard {
+            monitor: self,
+            instance,
+        }
+    }
+
+    /// Checks executor heartbeat.
+    /// Sends new probe if previous one was answered,
+    /// returns time the pending probe waits for executor thread if it exceeds `budget`.
+    /// Late probe is reported once.
+    pub fn check_heartbeat(&self, budget: Duration) -> Option<Duration> {
+        let mut heartbeat = self
+            .inner
+            .heartbeat
+            .lock()
+            .expect("Heartbeat lock poisoned");
+        if let Some(sent_at) = heartbeat.probe_sent_at {
+            let blocked_for = sent_at.elapsed();
+            if blocked_for > budget && !heartbeat.reported {
+                heartbeat.reported = true;
+                return Some(blocked_for);
+            }
+            return None;
+        }
+
+        *heartbeat = Heartbeat {
+            probe_sent_at: Some(Instant::now()),
+            reported: false,
+        };
+        drop(heartbeat);
+
+        let probe = HeartbeatProbe {
+            monitor: Arc::downgrade(&self.inner),
+        };
+        if self.inner.spawner_dispatcher.send(Box::new(probe)).is_err() {
+            // executor is shutting down, nothing to wait for
+            reset_heartbeat(&self.inner);
+        }
+        None
+    }
+
+    /// Returns handlers running longer than `budget`, each handler run is returned once
+    pub fn stuck_handlers(&self, budget: Duration) -> Vec<StuckHandler> {
+        let mut stuck = Vec::new();
+        for mut e in self.inner.running_handlers.iter_mut() {
+            let handler = e.value_mut();
+            let running_for = handler.started_at.elapsed();
+            if handler.reported || running_for <= budget {
+                continue;
+            }
+            handler.reported = true;
+            stuck.push(StuckHandler {
+                actor: handler.actor.clone(),
+                message_name: handler.message_name,
+                running_for,
+                execution_cancellation: handler.execution_cancellation.clone(),
+            });
+        }
+        stuck
+    }
+}
+
+fn reset_heartbeat(inner: &MonitorInner) {
+    *inner.heartbeat.lock().expect("Heartbeat lock poisoned") = Heartbeat::default();
+}
+
+/// Removes running handler from monitor on drop
+pub(crate) struct RunningHandlerGuard<'a> {
+    monitor: &'a ExecutorMonitor,
+    instance: u64,
+}
+
+impl Drop for RunningHandlerGuard<'_> {
+    fn drop(&mut self) {
+        self.monitor.inner.running_handlers.remove(&self.instance);
+    }
+}
+
+/// Heartbeat probe, passes through spawner mailbox
+/// and is answered once executor thread gets to process it
+struct HeartbeatProbe {
+    monitor: Weak<MonitorInner>,
+}
+
+impl LocalSpawnedActor for HeartbeatProbe {
+    fn spawn(&self) -> Result<(), ActorSpawnError> {
+        if let Some(inner) = self.monitor.upgrade() {
+            reset_heartbeat(&inner);
+        }
+        Ok(())
+    }
+}
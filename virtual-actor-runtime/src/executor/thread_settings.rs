@@ -0,0 +1,196 @@
+//! Scheduling settings of executor threads
+
+use std::io;
+
+/// Scheduling priority of executor thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadPriority {
+    /// Normal scheduling with niceness, from -20 (highest) to 19 (lowest)
+    Nice(i32),
+    /// Real-time FIFO scheduling with priority from 1 (lowest) to 99 (highest)
+    Fifo(i32),
+}
+
+/// Settings applied to executor thread, reported by executor handle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutorThreadInfo {
+    /// Executor thread name
+    pub thread_name: String,
+    /// OS thread id
+    pub native_thread_id: Option<i64>,
+    /// Cores thread is allowed to run on, `None` if not known
+    pub cpu_affinity: Option<Vec<usize>>,
+    /// Scheduling priority read back from OS after settings are applied,
+    /// may differ from requested one if OS clamps it, `None` if not known
+    pub thread_priority: Option<ThreadPriority>,
+}
+
+/// Applies affinity and priority to the current thread
+/// and reads back resulting settings
+pub(crate) fn apply_thread_settings(
+    thread_name: &str,
+    cpu_affinity: Option<&[usize]>,
+    thread_priority: Option<ThreadPriority>,
+) -> io::Result<ExecutorThreadInfo> {
+    if let Some(cores) = cpu_affinity {
+        platform::set_cpu_affinity(cores)?;
+    }
+    if let Some(priority) = thread_priority {
+        platform::set_thread_priority(priority)?;
+    }
+    Ok(ExecutorThreadInfo {
+        thread_name: thread_name.to_owned(),
+        native_thread_id: platform::native_thread_id(),
+        cpu_affinity: platform::cpu_affinity(),
+        thread_priority: platform::thread_priority(),
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::io;
+
+    use super::ThreadPriority;
+
+    pub fn set_cpu_affinity(cores: &[usize]) -> io::Result<()> {
+        // SAFETY: cpu_set_t is plain data, zeroed value is an empty set
+        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        let max_cores = std::mem::size_of::<libc::cpu_set_t>() * 8;
+        for &core in cores {
+            if core >= max_cores {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Core {core} is out of range"),
+                ));
+            }
+            // SAFETY: core is checked to be within set size
+            unsafe { libc::CPU_SET(core, &mut set) };
+        }
+        // SAFETY: set is valid cpu_set_t, pid 0 is the calling thread
+        let res = unsafe {
+            libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &raw const set)
+        };
+        if res != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    pub fn cpu_affinity() -> Option<Vec<usize>> {
+        // SAFETY: cpu_set_t is plain data, zeroed value is an empty set
+        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+        // SAFETY: set is valid cpu_set_t, pid 0 is the calling thread
+        let res = unsafe {
+            libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &raw mut set)
+        };
+        if res != 0 {
+            return None;
+        }
+        let max_cores = std::mem::size_of::<libc::cpu_set_t>() * 8;
+        // SAFETY: core is within set size
+        let cores = (0..max_cores)
+            .filter(|&core| unsafe { libc::CPU_ISSET(core, &set) })
+            .collect();
+        Some(cores)
+    }
+
+    pub fn set_thread_priority(priority: ThreadPriority) -> io::Result<()> {
+        match priority {
+            ThreadPriority::Nice(nice) => {
+                let tid = native_thread_id().unwrap_or_default();
+                // SAFETY: setpriority on Linux accepts thread id for PRIO_PROCESS
+                #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+                let res = unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) };
+                if res != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            ThreadPriority::Fifo(priority) => {
+                let param = libc::sched_param {
+                    sched_priority: priority,
+                };
+                // SAFETY: param is valid sched_param, pthread_self is the calling thread
+                let res = unsafe {
+                    libc::pthread_setschedparam(
+                        libc::pthread_self(),
+                        libc::SCHED_FIFO,
+                        &raw const param,
+                    )
+                };
+                if res != 0 {
+                    return Err(io::Error::from_raw_os_error(res));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn thread_priority() -> Option<ThreadPriority> {
+        let mut policy = 0;
+        let mut param = libc::sched_param { sched_priority: 0 };
+        // SAFETY: policy and param are valid, pthread_self is the calling thread
+        let res = unsafe {
+            libc::pthread_getschedparam(libc::pthread_self(), &raw mut policy, &raw mut param)
+        };
+        if res != 0 {
+            return None;
+        }
+        match policy {
+            libc::SCHED_FIFO => Some(ThreadPriority::Fifo(param.sched_priority)),
+            libc::SCHED_OTHER => {
+                let tid = native_thread_id()?;
+                // SAFETY: errno is thread local, it is reset to tell error from niceness -1
+                let nice = unsafe {
+                    *libc::__errno_location() = 0;
+                    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+                    libc::getpriority(libc::PRIO_PROCESS, tid as libc::id_t)
+                };
+                if nice == -1 && io::Error::last_os_error().raw_os_error() != Some(0) {
+                    return None;
+                }
+                Some(ThreadPriority::Nice(nice))
+            }
+            _ => None,
+        }
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn native_thread_id() -> Option<i64> {
+        // SAFETY: gettid has no preconditions
+        Some(i64::from(unsafe { libc::gettid() }))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use std::io;
+
+    use super::ThreadPriority;
+
+    fn unsupported() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Thread affinity and priority are supported on Linux only",
+        )
+    }
+
+    pub fn set_cpu_affinity(_cores: &[usize]) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn cpu_affinity() -> Option<Vec<usize>> {
+        None
+    }
+
+    pub fn set_thread_priority(_priority: ThreadPriority) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn thread_priority() -> Option<ThreadPriority> {
+        None
+    }
+
+    pub fn native_thread_id() -> Option<i64> {
+        None
+    }
+}
@@ -1,22 +1,34 @@
 //! Tokio based runtime for virtual-actor
 
 mod address;
+mod cluster;
 mod context;
 mod executor;
 mod messaging;
 mod runtime;
+mod transport;
 mod utils;
 
-pub use address::{LocalAddr, VirtualAddr, WeakLocalAddr, WeakVirtualAddr};
+pub use address::{LocalAddr, RetryPolicy, VirtualAddr, WeakLocalAddr, WeakVirtualAddr};
+pub use cluster::{Member, MemberStatus, MembershipEvent, MembershipPreferences};
 pub use context::{RuntimeContext, RuntimeContextFactory};
-pub use executor::{ExecutorPreferences, Handle as ExecutorHandle, TokioRuntimePreferences};
+pub use executor::{
+    ExecutorPreferences, ExecutorThreadInfo, Handle as ExecutorHandle, ThreadPriority,
+    TokioRuntimePreferences,
+};
+pub use messaging::CallChainEntry;
+pub use transport::{RemoteAddr, TransportPreferences};
 pub use utils::GracefulShutdown;
 
 pub mod errors {
     //! Virtual actor errors
     pub use crate::address::errors::*;
+    pub use crate::cluster::errors::*;
     pub use crate::executor::errors::*;
+    pub use crate::messaging::errors::*;
     pub use crate::runtime::errors::*;
+    pub use crate::transport::errors::*;
+    pub use crate::utils::sleep::SleepWaitError;
     pub use crate::utils::waiter::WaitError;
 
     pub use virtual_actor::errors::*;
@@ -31,6 +43,8 @@ pub mod prelude {
 
     pub use crate::runtime::Runtime;
     pub use crate::runtime::RuntimePreferences;
+    pub use crate::runtime::{MemoryPressure, MemoryPressureEvent, MemoryPressurePreferences};
+    pub use crate::runtime::{WatchdogPreferences, WatchdogReport};
 
     // Export derive macros
     pub use virtual_actor_derive::Actor;
@@ -0,0 +1,140 @@
+//! Causal call chain of actors awaiting responses
+//!
+//! Used to detect deadlocks: when actor sends message and waits for response,
+//! it is added to the call chain of the message. If message would be sent to an actor
+//! which is already in the chain, the chain would never finish.
+
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use virtual_actor::actor::ActorName;
+
+/// Sequence for unique actor instance numbers
+static INSTANCE_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+tokio::task_local! {
+    /// Call scope of message processed by current actor task
+    static CURRENT_CALL: CallScope;
+}
+
+/// Actor instance participating in call chain
+#[derive(Clone)]
+pub struct CallChainEntry {
+    /// Unique number of actor instance
+    instance: u64,
+    /// Name of the actor
+    actor_name: ActorName,
+    /// Id of the actor, instance number for local actors
+    actor_id: Arc<str>,
+}
+
+impl CallChainEntry {
+    /// Creates entry for new actor instance
+    pub(crate) fn new(actor_name: ActorName, actor_id: Option<String>) -> Self {
+        let instance = INSTANCE_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        let actor_id = actor_id.unwrap_or_else(|| format!("#{instance}"));
+        Self {
+            instance,
+            actor_name,
+            actor_id: actor_id.into(),
+        }
+    }
+
+    /// Unique number of actor instance
+    pub(crate) fn instance(&self) -> u64 {
+        self.instance
+    }
+
+    /// Name of the actor
+    #[must_use]
+    pub fn actor_name(&self) -> ActorName {
+        self.actor_name
+    }
+
+    /// Id of the actor, for local actors it is unique instance number
+    #[must_use]
+    pub fn actor_id(&self) -> &str {
+        &self.actor_id
+    }
+}
+
+impl PartialEq for CallChainEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.instance == other.instance
+    }
+}
+
+impl Eq for CallChainEntry {}
+
+impl fmt::Display for CallChainEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.actor_name, self.actor_id)
+    }
+}
+
+impl fmt::Debug for CallChainEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Chain of actors awaiting response, ordered from the first caller
+#[derive(Clone, Default, Debug)]
+pub struct CallChain {
+    entries: Vec<CallChainEntry>,
+}
+
+impl CallChain {
+    /// Call chain of message processed by current actor task, including current actor.
+    /// Returns empty chain if called outside of actor message processing.
+    pub fn current() -> Self {
+        CURRENT_CALL
+            .try_with(CallScope::outgoing_chain)
+            .unwrap_or_default()
+    }
+
+    /// Returns cycle which would be created by sending message to `target`
+    pub fn cycle_to(&self, target: &CallChainEntry) -> Option<Vec<CallChainEntry>> {
+        let position = self.entries.iter().position(|e| e == target)?;
+        let mut cycle = self.entries[position..].to_vec();
+        cycle.push(target.clone());
+        Some(cycle)
+    }
+}
+
+/// Call scope of message processed by actor
+pub struct CallScope {
+    /// Call chain of received message
+    parent: CallChain,
+    /// Actor processing message
+    current: CallChainEntry,
+}
+
+impl CallScope {
+    /// Creates new call scope
+    pub fn new(parent: CallChain, current: &CallChainEntry) -> Self {
+        Self {
+            parent,
+            current: current.clone(),
+        }
+    }
+
+    /// Runs future within call scope
+    pub async fn run<F: Future>(self, future: F) -> F::Output {
+        CURRENT_CALL.scope(self, future).await
+    }
+
+    /// Call chain for messages sent from this scope
+    fn outgoing_chain(&self) -> CallChain {
+        let mut entries = Vec::with_capacity(self.parent.entries.len() + 1);
+        entries.extend_from_slice(&self.parent.entries);
+        entries.push(self.current.clone());
+        CallChain { entries }
+    }
+}
@@ -1,16 +1,19 @@
 use tokio::sync::{mpsc::error::TrySendError, oneshot::error::RecvError};
 
+/// Mailbox error
 #[derive(thiserror::Error, Debug)]
 pub enum MailboxError {
+    /// Mailbox is closed
     #[error("Mailbox is closed")]
     Closed,
+    /// Mailbox is full
     #[error("Mailbox is full")]
     Full,
 }
 
 impl MailboxError {
     #[allow(clippy::needless_pass_by_value)]
-    pub fn from_try_send_error<T>(e: TrySendError<T>) -> Self {
+    pub(crate) fn from_try_send_error<T>(e: TrySendError<T>) -> Self {
         match e {
             TrySendError::Closed(_) => Self::Closed,
             TrySendError::Full(_) => Self::Full,
@@ -30,7 +33,7 @@ pub enum DispatcherError {
 }
 
 impl DispatcherError {
-    pub fn from_try_send_error<T>(e: TrySendError<T>) -> Self {
+    pub(crate) fn from_try_send_error<T>(e: TrySendError<T>) -> Self {
         Self::MailBoxError(MailboxError::from_try_send_error(e))
     }
 }
@@ -53,6 +53,13 @@ impl<T> Mailbox<T> {
         }
     }
 
+    /// Stops receiving new messages,
+    /// caller will read the rest of messages from channel and then receive None
+    pub fn close(&mut self) {
+        self.receiver.close();
+        self.closed = true;
+    }
+
     /// Receive messages with checking mailbox cancellation token
     /// it will close channel if cancellation token is cancelled
     async fn recv_with_mailbox_ct(&mut self, ct: &CancellationToken) -> Option<T> {
@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use tokio::sync::mpsc::Permit;
 use virtual_actor::{
     actor::Actor,
     message::{Message, MessageEnvelopeFactory, MessageHandler, MessageProcessingResult},
@@ -10,13 +11,17 @@ use virtual_actor::{
 use crate::utils::atomic_counter::AtomicCounter;
 
 use super::{
-    errors::DispatcherError, mailbox::MailboxDispatcher, one_shot_responder::OneshotResponder,
+    call_chain::CallChain, errors::DispatcherError, mailbox::MailboxDispatcher,
+    message_metadata::MessageMetadata, one_shot_responder::OneshotResponder,
 };
 
+/// Mailbox item, message envelope with metadata
+pub type EnvelopeWithMetadata<A> = (<A as Actor>::MessagesEnvelope, MessageMetadata);
+
 /// Message dispatcher for `Actor`
 pub struct MessageDispatcher<A: Actor> {
     /// Sender for mailbox
-    mailbox_sender: Arc<MailboxDispatcher<A::MessagesEnvelope>>,
+    mailbox_sender: Arc<MailboxDispatcher<EnvelopeWithMetadata<A>>>,
     /// Counter of messages dispatched to actor
     dispatched_msg_counter: AtomicCounter,
 }
@@ -33,7 +38,7 @@ impl<A: Actor> Clone for MessageDispatcher<A> {
 impl<A: Actor> MessageDispatcher<A> {
     /// Creates new message dispatcher
     pub fn new(
-        mailbox_sender: MailboxDispatcher<A::MessagesEnvelope>,
+        mailbox_sender: MailboxDispatcher<EnvelopeWithMetadata<A>>,
         dispatched_msg_counter: AtomicCounter,
     ) -> Self {
         Self {
@@ -44,8 +49,62 @@ impl<A: Actor> MessageDispatcher<A> {
 }
 
 impl<A: Actor> MessageDispatcher<A> {
+    /// Reserves place for one message in mailbox.
+    /// Message is not built yet, so it is kept by caller if mailbox is closed or full
+    pub fn reserve(&self) -> Result<DispatchPermit<'_, A>, DispatcherError> {
+        let permit = self
+            .mailbox_sender
+            .try_reserve()
+            .map_err(DispatcherError::from_try_send_error)?;
+        Ok(DispatchPermit {
+            permit,
+            dispatched_msg_counter: &self.dispatched_msg_counter,
+        })
+    }
+
     /// Sends message to actor and waits for response
-    pub async fn send<M>(&self, msg: M) -> Result<MessageProcessingResult<M>, DispatcherError>
+    ///
+    /// `call_chain` is the chain of actors awaiting response, including sender
+    pub async fn send<M>(
+        &self,
+        msg: M,
+        call_chain: CallChain,
+    ) -> Result<MessageProcessingResult<M>, DispatcherError>
+    where
+        M: Message,
+        A: MessageHandler<M>,
+        A::MessagesEnvelope: MessageEnvelopeFactory<A, M>,
+    {
+        self.reserve()?.send(msg, call_chain).await
+    }
+
+    /// Sends message to actor without waiting for response
+    pub fn dispatch<M>(&self, msg: M) -> Result<(), DispatcherError>
+    where
+        M: Message,
+        A: MessageHandler<M>,
+        A::MessagesEnvelope: MessageEnvelopeFactory<A, M>,
+    {
+        self.reserve()?.dispatch(msg);
+        Ok(())
+    }
+}
+
+/// Reserved place in actor mailbox, delivery of message can't fail
+pub struct DispatchPermit<'a, A: Actor> {
+    permit: Permit<'a, EnvelopeWithMetadata<A>>,
+    dispatched_msg_counter: &'a AtomicCounter,
+}
+
+impl<A: Actor> DispatchPermit<'_, A> {
+    /// Puts message to mailbox and waits for response
+    ///
+    /// `call_chain` is the chain of actors awaiting response, including sender
+    pub async fn send<M>(
+        self,
+        msg: M,
+        call_chain: CallChain,
+    ) -> Result<MessageProcessingResult<M>, DispatcherError>
     where
         M: Message,
         A: MessageHandler<M>,
@@ -53,9 +112,8 @@ impl<A: Actor> MessageDispatcher<A> {
     {
         let (responder, receiver) = OneshotResponder::new();
         let envelope = A::MessagesEnvelope::from_message(msg, Some(responder));
-        self.mailbox_sender
-            .try_send(envelope)
-            .map_err(DispatcherError::from_try_send_error)?;
+        let metadata = MessageMetadata::with_call_chain(call_chain);
+        self.permit.send((envelope, metadata));
 
         self.dispatched_msg_counter.increment();
 
@@ -64,20 +122,17 @@ impl<A: Actor> MessageDispatcher<A> {
         Ok(a)
     }
 
-    /// Sends message to actor without waiting for response
-    pub fn dispatch<M>(&self, msg: M) -> Result<(), DispatcherError>
+    /// Puts message to mailbox without waiting for response
+    pub fn dispatch<M>(self, msg: M)
     where
         M: Message,
         A: MessageHandler<M>,
         A::MessagesEnvelope: MessageEnvelopeFactory<A, M>,
     {
         let envelope = A::MessagesEnvelope::from_message(msg, None::<OneshotResponder<M>>);
-        self.mailbox_sender
-            .try_send(envelope)
-            .map_err(DispatcherError::from_try_send_error)?;
+        // sender doesn't wait for response, so message starts new call chain
+        self.permit.send((envelope, MessageMetadata::default()));
 
         self.dispatched_msg_counter.increment();
-
-        Ok(())
     }
 }
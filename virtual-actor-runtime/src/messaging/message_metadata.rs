@@ -0,0 +1,17 @@
+//! Metadata delivered to actor together with message envelope
+
+use super::call_chain::CallChain;
+
+/// Message metadata
+#[derive(Default)]
+pub struct MessageMetadata {
+    /// Causal chain of actors awaiting response for this message
+    pub call_chain: CallChain,
+}
+
+impl MessageMetadata {
+    /// Creates metadata for message sent within call chain
+    pub fn with_call_chain(call_chain: CallChain) -> Self {
+        Self { call_chain }
+    }
+}
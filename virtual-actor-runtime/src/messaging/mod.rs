@@ -1,7 +1,10 @@
+mod call_chain;
 pub mod errors;
 mod mailbox;
 mod message_dispatcher;
+mod message_metadata;
 mod one_shot_responder;
 
+pub use call_chain::{CallChain, CallChainEntry, CallScope};
 pub use mailbox::{Mailbox, MailboxDispatcher};
-pub use message_dispatcher::MessageDispatcher;
+pub use message_dispatcher::{DispatchPermit, EnvelopeWithMetadata, MessageDispatcher};
@@ -1,12 +1,20 @@
+mod offload;
+mod periodic_actor;
 mod registry;
 mod runtime_impl;
 mod runtime_preferences;
+mod watchdog;
 
+pub use offload::BlockingPools;
 pub use registry::WeakActorRegistry;
 pub use registry::{ActorActivator, WeakActorActivator};
+pub use registry::{MemoryPressure, MemoryPressureEvent, MemoryPressurePreferences};
 pub use runtime_impl::Runtime;
 pub use runtime_preferences::RuntimePreferences;
+pub use watchdog::{WatchdogPreferences, WatchdogReport};
 
 pub mod errors {
+    pub use super::offload::errors::*;
     pub use super::registry::errors::*;
+    pub use super::watchdog::errors::*;
 }
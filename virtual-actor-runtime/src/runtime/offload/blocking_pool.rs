@@ -0,0 +1,201 @@
+//! Thread pool for blocking work offloaded from executors
+
+use std::{
+    any::Any,
+    future::Future,
+    panic::AssertUnwindSafe,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
+};
+
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+use super::errors::BlockingTaskError;
+
+/// Task executed on pool thread
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Pool of threads for blocking work.
+/// Threads are started on demand up to pool size.
+#[derive(Clone)]
+pub struct BlockingPool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    /// Pool name, used as thread name prefix
+    name: &'static str,
+    /// Maximal number of threads
+    size: usize,
+    /// Job sender, `None` when pool is closed
+    sender: Mutex<Option<mpsc::Sender<Job>>>,
+    /// Number of started threads
+    threads: AtomicUsize,
+    /// State shared with pool threads
+    workers: Arc<Workers>,
+}
+
+struct Workers {
+    /// Job receiver shared by pool threads
+    receiver: Mutex<mpsc::Receiver<Job>>,
+    /// Number of threads waiting for job
+    idle: AtomicUsize,
+    /// Number of submitted jobs not taken by threads
+    queued: AtomicUsize,
+}
+
+impl BlockingPool {
+    /// Creates new pool
+    pub fn new(name: &'static str, size: usize) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            inner: Arc::new(PoolInner {
+                name,
+                size: size.max(1),
+                sender: Mutex::new(Some(sender)),
+                threads: AtomicUsize::new(0),
+                workers: Arc::new(Workers {
+                    receiver: Mutex::new(receiver),
+                    idle: AtomicUsize::new(0),
+                    queued: AtomicUsize::new(0),
+                }),
+            }),
+        }
+    }
+
+    /// Runs `f` on pool thread.
+    /// Task is skipped if `cancellation` is cancelled before it is started,
+    /// returned future resolves with `BlockingTaskError::Cancelled` once `cancellation` is cancelled.
+    pub fn spawn<F, R>(
+        &self,
+        f: F,
+        cancellation: &CancellationToken,
+    ) -> impl Future<Output = Result<R, BlockingTaskError>>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let job_cancellation = cancellation.clone();
+        let job: Job = Box::new(move || {
+            if job_cancellation.is_cancelled() {
+                return;
+            }
+            let res = std::panic::catch_unwind(AssertUnwindSafe(f));
+            // receiver is dropped if actor is cancelled
+            let _ = tx.send(res);
+        });
+        let submitted = self.submit(job);
+
+        let name = self.inner.name;
+        let cancellation = cancellation.clone();
+        async move {
+            submitted?;
+            tokio::select! {
+                biased;
+                () = cancellation.cancelled() => Err(BlockingTaskError::Cancelled),
+                res = rx => match res {
+                    Ok(Ok(r)) => Ok(r),
+                    Ok(Err(e)) => Err(BlockingTaskError::Panic(panic_message(&e))),
+                    Err(_) => Err(BlockingTaskError::PoolStopped(name)),
+                },
+            }
+        }
+    }
+
+    /// Stops accepting tasks, threads finish after pending tasks are processed
+    pub fn close(&self) {
+        self.inner
+            .sender
+            .lock()
+            .expect("Blocking pool lock poisoned")
+            .take();
+    }
+
+    fn submit(&self, job: Job) -> Result<(), BlockingTaskError> {
+        let inner = &self.inner;
+        let workers = &inner.workers;
+        // counted before sending, so thread taking the job never sees it uncounted
+        let queued = workers.queued.fetch_add(1, Ordering::AcqRel) + 1;
+        let sent = inner
+            .sender
+            .lock()
+            .expect("Blocking pool lock poisoned")
+            .as_ref()
+            .is_some_and(|sender| sender.send(job).is_ok());
+        if !sent {
+            workers.queued.fetch_sub(1, Ordering::AcqRel);
+            return Err(BlockingTaskError::PoolStopped(inner.name));
+        }
+
+        if queued > workers.idle.load(Ordering::Acquire) {
+            if let Err(e) = self.start_thread() {
+                // job is taken by running thread, otherwise it would wait forever
+                if inner.threads.load(Ordering::Acquire) == 0 {
+                    return Err(BlockingTaskError::ThreadStart(inner.name, e));
+                }
+                eprintln!("Failed to start {} thread: {e:?}", inner.name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts thread unless pool has all its threads
+    fn start_thread(&self) -> std::io::Result<()> {
+        let inner = &self.inner;
+        let started = inner
+            .threads
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < inner.size).then_some(n + 1)
+            });
+        let Ok(index) = started else {
+            return Ok(());
+        };
+
+        let workers = inner.workers.clone();
+        let res = std::thread::Builder::new()
+            .name(format!("{}-{index}", inner.name))
+            .spawn(move || workers.run());
+        if let Err(e) = res {
+            inner.threads.fetch_sub(1, Ordering::AcqRel);
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
+impl Workers {
+    /// Pool thread loop, runs jobs until pool is closed
+    fn run(&self) {
+        loop {
+            self.idle.fetch_add(1, Ordering::AcqRel);
+            let job = self
+                .receiver
+                .lock()
+                .expect("Blocking pool lock poisoned")
+                .recv();
+            self.idle.fetch_sub(1, Ordering::AcqRel);
+            match job {
+                Ok(job) => {
+                    self.queued.fetch_sub(1, Ordering::AcqRel);
+                    job();
+                }
+                // pool is closed
+                Err(_) => return,
+            }
+        }
+    }
+}
+
+fn panic_message(e: &Box<dyn Any + Send>) -> String {
+    if let Some(s) = e.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = e.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        format!("Unknown panic {e:?}")
+    }
+}
@@ -0,0 +1,28 @@
+use crate::runtime::RuntimePreferences;
+
+use super::BlockingPool;
+
+/// Runtime managed pools for work offloaded from executors
+#[derive(Clone)]
+pub struct BlockingPools {
+    /// Pool for blocking IO
+    pub blocking: BlockingPool,
+    /// Pool for CPU-heavy work
+    pub compute: BlockingPool,
+}
+
+impl BlockingPools {
+    /// Creates pools sized by runtime preferences
+    pub fn new(preferences: &RuntimePreferences) -> Self {
+        Self {
+            blocking: BlockingPool::new("blocking-pool", preferences.blocking_pool_size),
+            compute: BlockingPool::new("compute-pool", preferences.compute_pool_size),
+        }
+    }
+
+    /// Stops accepting tasks
+    pub fn close(&self) {
+        self.blocking.close();
+        self.compute.close();
+    }
+}
@@ -0,0 +1,16 @@
+/// Blocking task error
+#[derive(Debug, thiserror::Error)]
+pub enum BlockingTaskError {
+    /// Actor is cancelled before task is finished
+    #[error("Blocking task is cancelled")]
+    Cancelled,
+    /// Task panicked
+    #[error("Blocking task panic {0}")]
+    Panic(String),
+    /// Pool is stopped and doesn't accept tasks
+    #[error("Blocking pool {0} is stopped")]
+    PoolStopped(&'static str),
+    /// Pool has no thread and failed to start one
+    #[error("Failed to start thread of blocking pool {0}: {1}")]
+    ThreadStart(&'static str, std::io::Error),
+}
@@ -0,0 +1,6 @@
+mod blocking_pool;
+mod blocking_pools;
+pub mod errors;
+
+pub use blocking_pool::BlockingPool;
+pub use blocking_pools::BlockingPools;
@@ -0,0 +1,120 @@
+//! Context and scheduling shared by runtime actors performing periodic checks
+
+use std::{marker::PhantomData, time::Duration};
+
+use tokio_util::sync::CancellationToken;
+use virtual_actor::{
+    actor::{Actor, ActorAddr, ActorContext, WeakActorAddr},
+    message::{Message, MessageEnvelopeFactory, MessageHandler},
+};
+
+use crate::{
+    address::ActorHandle,
+    context::ActorContextFactory,
+    utils::{
+        cancellation_token_wrapper::CancellationTokenWrapper,
+        sleep::{sleep_with_cancel, SleepWaitError},
+    },
+    LocalAddr, WeakLocalAddr,
+};
+
+/// Context of periodic runtime actor
+pub struct PeriodicActorContext<A: Actor> {
+    weak_addr: WeakLocalAddr<A>,
+    /// Cancellation token
+    cancellation_token: CancellationTokenWrapper,
+    /// Mailbox cancellation token
+    mailbox_cancellation_token: CancellationToken,
+}
+
+impl<A: Actor> Clone for PeriodicActorContext<A> {
+    fn clone(&self) -> Self {
+        Self {
+            weak_addr: self.weak_addr.clone(),
+            cancellation_token: self.cancellation_token.clone(),
+            mailbox_cancellation_token: self.mailbox_cancellation_token.clone(),
+        }
+    }
+}
+
+impl<A: Actor> ActorContext<A> for PeriodicActorContext<A> {
+    type Addr = LocalAddr<A>;
+
+    type CancellationToken = CancellationTokenWrapper;
+
+    fn self_addr(&self) -> &<Self::Addr as ActorAddr<A>>::WeakRef {
+        &self.weak_addr
+    }
+
+    fn stop(&self) {
+        self.mailbox_cancellation_token.cancel();
+    }
+
+    fn cancellation_token(&self) -> &Self::CancellationToken {
+        &self.cancellation_token
+    }
+}
+
+impl<A: Actor + 'static> PeriodicActorContext<A> {
+    /// Dispatches `msg` to actor itself after `interval`,
+    /// skipped if runtime is shutting down or actor is cancelled
+    pub fn schedule<M>(&self, msg: M, interval: Duration, graceful_cancellation: &CancellationToken)
+    where
+        M: Message,
+        A: MessageHandler<M>,
+        A::MessagesEnvelope: MessageEnvelopeFactory<A, M>,
+    {
+        let addr = self.weak_addr.clone();
+        let cancellation_token = self.cancellation_token.clone();
+        let mailbox_cancellation_token = self.mailbox_cancellation_token.clone();
+        let graceful_cancellation = graceful_cancellation.clone();
+        let actor_name = A::name();
+        tokio::task::spawn_local(async move {
+            if let Err(e) =
+                sleep_with_cancel(interval, &graceful_cancellation, &cancellation_token).await
+            {
+                if let SleepWaitError::Cancelled = e {
+                    eprintln!("Failed to sleep for {interval:?} on {actor_name}: {e:?}");
+                }
+                return;
+            }
+            // stopped actor does not receive scheduled messages
+            if mailbox_cancellation_token.is_cancelled() {
+                return;
+            }
+            if let Some(addr) = addr.upgrade() {
+                addr.dispatch(msg)
+                    .await
+                    .expect("Failed to dispatch message");
+            }
+        });
+    }
+}
+
+/// Creates contexts of periodic runtime actor
+pub struct PeriodicActorContextFactory<A: Actor> {
+    _a: PhantomData<fn(A) -> A>,
+    cancellation_token: CancellationToken,
+}
+
+impl<A: Actor> PeriodicActorContextFactory<A> {
+    pub fn new(cancellation_token: CancellationToken) -> Self {
+        Self {
+            _a: PhantomData,
+            cancellation_token,
+        }
+    }
+}
+
+impl<A> ActorContextFactory<A> for PeriodicActorContextFactory<A>
+where
+    A: Actor<ActorContext = PeriodicActorContext<A>> + 'static,
+{
+    fn create_context(&self, handle: &ActorHandle<A>) -> PeriodicActorContext<A> {
+        PeriodicActorContext {
+            weak_addr: WeakLocalAddr::new(handle),
+            cancellation_token: CancellationTokenWrapper::new(self.cancellation_token.clone()),
+            mailbox_cancellation_token: handle.mailbox_cancellation().clone(),
+        }
+    }
+}
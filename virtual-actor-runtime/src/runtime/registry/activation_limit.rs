@@ -0,0 +1,134 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use dashmap::DashMap;
+use futures::future::BoxFuture;
+use virtual_actor::actor::ActorName;
+
+use crate::{
+    address::errors::LocalAddrError,
+    executor::{errors::LocalExecutorError, Handle},
+};
+
+use super::{
+    errors::StartHousekeepingError,
+    housekeeping::EvictionCandidate,
+    memory_pressure::{MemoryPressureMonitor, MemoryPressurePreferences},
+};
+
+/// Evicts activations of one actor type
+pub trait ActivationEvictor: Send + Sync {
+    /// Best candidate for eviction among activations
+    fn eviction_candidate(
+        &self,
+    ) -> BoxFuture<'_, Result<Option<EvictionCandidate>, LocalAddrError>>;
+
+    /// Deactivates `count` least recently used activations, returns ids of deactivated actors
+    fn evict(&self, count: usize) -> BoxFuture<'_, Result<Vec<String>, LocalAddrError>>;
+
+    /// Memory used by states of active actors, as reported by actors
+    fn memory_usage(&self) -> usize;
+}
+
+/// Evictors of registered actor types
+pub type ActivationEvictors = Arc<DashMap<ActorName, Arc<dyn ActivationEvictor>>>;
+
+/// Best candidate for eviction across all actor types
+pub async fn find_eviction_candidate(
+    evictors: &ActivationEvictors,
+) -> Result<Option<(ActorName, Arc<dyn ActivationEvictor>, EvictionCandidate)>, LocalAddrError> {
+    // evictors are cloned to not hold map locks across awaits
+    let evictors = evictors
+        .iter()
+        .map(|e| (*e.key(), e.value().clone()))
+        .collect::<Vec<_>>();
+    let mut best: Option<(ActorName, Arc<dyn ActivationEvictor>, EvictionCandidate)> = None;
+    for (name, evictor) in evictors {
+        let candidate = evictor.eviction_candidate().await?;
+        if let Some(candidate) = candidate {
+            if best.as_ref().is_none_or(|(_, _, b)| candidate < *b) {
+                best = Some((name, evictor, candidate));
+            }
+        }
+    }
+    Ok(best)
+}
+
+/// Limits of active actors of all types in registry
+pub struct ActivationLimit {
+    /// Maximal number of active actors, unlimited if `None`
+    max_active_actors: Option<usize>,
+    /// Number of active actors
+    total_active: Arc<AtomicUsize>,
+    /// Evictors of registered actor types
+    evictors: ActivationEvictors,
+    /// Deactivation of idle actors under memory pressure
+    memory_pressure: Option<MemoryPressureMonitor>,
+}
+
+impl ActivationLimit {
+    pub fn new(
+        max_active_actors: Option<usize>,
+        memory_pressure: Option<&MemoryPressurePreferences>,
+        housekeeping_executor: &Handle,
+    ) -> Result<Self, LocalExecutorError> {
+        let evictors = ActivationEvictors::default();
+        let memory_pressure = memory_pressure
+            .map(|p| MemoryPressureMonitor::new(housekeeping_executor, &evictors, p.clone()))
+            .transpose()?;
+        Ok(Self {
+            max_active_actors,
+            total_active: Arc::new(AtomicUsize::new(0)),
+            evictors,
+            memory_pressure,
+        })
+    }
+
+    /// Counter of active actors, shared with actors caches
+    pub fn total_active(&self) -> &Arc<AtomicUsize> {
+        &self.total_active
+    }
+
+    pub fn register(&self, name: ActorName, evictor: Arc<dyn ActivationEvictor>) {
+        self.evictors.insert(name, evictor);
+    }
+
+    /// Starts memory pressure checks if they are configured
+    pub async fn start_memory_pressure(
+        &self,
+        timeout: Duration,
+    ) -> Result<(), StartHousekeepingError> {
+        match &self.memory_pressure {
+            Some(memory_pressure) => memory_pressure.start(timeout).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Evicts least recently used idle activations across all types
+    /// until there is room for a new activation.
+    /// Limit is exceeded temporarily if only busy activations remain.
+    pub async fn ensure_capacity(&self) -> Result<(), LocalAddrError> {
+        let Some(max_active_actors) = self.max_active_actors else {
+            return Ok(());
+        };
+        let active = self.total_active.load(Ordering::Relaxed);
+        let excess = (active + 1).saturating_sub(max_active_actors);
+        for _ in 0..excess {
+            let Some((_, evictor, candidate)) = find_eviction_candidate(&self.evictors).await?
+            else {
+                break;
+            };
+            // candidates are ordered, so all other actors are busy too
+            if candidate.busy {
+                break;
+            }
+            evictor.evict(1).await?;
+        }
+        Ok(())
+    }
+}
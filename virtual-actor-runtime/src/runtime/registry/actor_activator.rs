@@ -3,6 +3,7 @@ use std::sync::{
     Arc, Weak,
 };
 
+use futures::{future::BoxFuture, FutureExt};
 use tokio::sync::Mutex;
 use virtual_actor::{
     actor::{Actor, ActorAddr, ActorContext, ActorFactory},
@@ -10,19 +11,20 @@ use virtual_actor::{
 };
 
 use crate::{
-    address::ActorHandle,
+    address::{errors::LocalAddrError, ActorHandle},
     context::ActorContextFactory,
     executor::{errors::LocalExecutorError, Handle},
-    runtime::runtime_preferences::RuntimePreferences,
-    ExecutorHandle, LocalAddr,
+    ExecutorHandle, LocalAddr, RetryPolicy,
 };
 
 use super::{
+    activation_limit::{ActivationEvictor, ActivationLimit},
+    actor_preferences::ActorPreferences,
     actors_cache::ActorsCache,
     errors::{RuntimeSpawnError, StartHousekeepingError},
     housekeeping::{
-        GarbageCollectActors, HousekeepingActor, HousekeepingActorFactory,
-        HousekeepingContextFactory,
+        EvictActors, EvictionCandidate, FindEvictionCandidate, GarbageCollectActors,
+        HousekeepingActor, HousekeepingActorFactory, HousekeepingContextFactory,
     },
     virtual_actor_registration::{VirtualActorRegistration, VirtualActorSpawner},
 };
@@ -49,7 +51,9 @@ pub struct Inner<A: VirtualActor> {
     /// Housekeeping sync primitive, to prevent starting multiple garbage collection
     housekeeping_lock: Arc<Mutex<bool>>,
     /// Preferences
-    preferences: Arc<RuntimePreferences>,
+    preferences: Arc<ActorPreferences>,
+    /// Limit of active actors of all types
+    activation_limit: Arc<ActivationLimit>,
 }
 
 impl<A: VirtualActor> ActorActivator<A> {
@@ -58,7 +62,8 @@ impl<A: VirtualActor> ActorActivator<A> {
         context_factory: Arc<CF>,
         executor: &ExecutorHandle,
         housekeeping_executor: &Handle,
-        preferences: Arc<RuntimePreferences>,
+        preferences: Arc<ActorPreferences>,
+        activation_limit: &Arc<ActivationLimit>,
     ) -> Result<Self, LocalExecutorError>
     where
         <<AF as ActorFactory>::Actor as Actor>::ActorContext: ActorContext<
@@ -70,7 +75,7 @@ impl<A: VirtualActor> ActorActivator<A> {
         <AF as ActorFactory>::Actor: VirtualActor + 'static,
         CF: ActorContextFactory<<AF as ActorFactory>::Actor> + 'static,
     {
-        let cache = ActorsCache::new();
+        let cache = ActorsCache::new(activation_limit.total_active());
         let housekeeping_actor_factory = Arc::new(HousekeepingActorFactory::new(
             housekeeping_executor.mailbox_cancellation().child_token(),
             cache.clone(),
@@ -95,6 +100,7 @@ impl<A: VirtualActor> ActorActivator<A> {
                 house_keeping_started: Arc::new(AtomicBool::new(false)),
                 housekeeping_lock: Arc::new(Mutex::new(false)),
                 preferences,
+                activation_limit: activation_limit.clone(),
             }),
         })
     }
@@ -109,8 +115,30 @@ impl<A: VirtualActor> ActorActivator<A> {
         if let Some(handle) = self.inner.cache.get(id) {
             return Ok(handle);
         }
+        let lock = self.inner.cache.activation_lock(id);
+        let guard = lock.lock().await;
+        let activated = self.activate(id).await;
+        drop(guard);
+        self.inner.cache.remove_activation_lock(id, &lock);
+        activated
+    }
+
+    /// Activates actor unless it was activated by concurrent call, runs under activation lock
+    async fn activate(&self, id: &A::ActorId) -> Result<ActorHandle<A>, RuntimeSpawnError> {
+        if let Some(handle) = self.inner.cache.get(id) {
+            return Ok(handle);
+        }
+        if let Some(previous) = self.inner.cache.deactivating(id) {
+            // previous instance requested deactivation, wait until it drains mailbox
+            previous
+                .wait_for_stop(self.inner.preferences.actor_shutdown_interval)
+                .await?;
+            self.inner.cache.remove_deactivated(id, &previous);
+        }
         self.start_housekeeping().await?;
+        self.ensure_capacity().await?;
         let handle = self.inner.registration.spawn_no_wait(id.clone())?;
+        self.set_deactivation_hook(id, &handle);
         handle
             .wait_for_ready(self.inner.preferences.actor_activation_timeout)
             .await?;
@@ -118,11 +146,66 @@ impl<A: VirtualActor> ActorActivator<A> {
         Ok(handle)
     }
 
+    /// Forgets stopped or stopping activation, so next call activates fresh instance
+    pub fn remove_stale(&self, id: &A::ActorId, handle: &ActorHandle<A>) {
+        self.inner.cache.remove_stale(id, handle);
+    }
+
+    /// Actor has activation which is not stopped yet
+    pub fn is_active(&self, id: &A::ActorId) -> bool {
+        self.inner.cache.contains(id)
+            || self
+                .inner
+                .cache
+                .deactivating(id)
+                .is_some_and(|handle| !handle.is_finished())
+    }
+
+    /// Redelivery policy of messages racing with deactivation
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.inner.preferences.retry_policy
+    }
+
+    /// Detaches activation from cache when actor requests deactivation
+    fn set_deactivation_hook(&self, id: &A::ActorId, handle: &ActorHandle<A>) {
+        let weak_activator = self.weak_ref();
+        let weak_handle = handle.weak_ref();
+        let actor_id = id.clone();
+        handle.deactivation().set_hook(Box::new(move || {
+            if let (Some(activator), Some(handle)) =
+                (weak_activator.upgrade(), weak_handle.upgrade())
+            {
+                activator.inner.cache.detach(&actor_id, &handle);
+            }
+        }));
+    }
+
+    /// Evicts least recently used idle activations if new activation would exceed
+    /// per-type or global limit. Limits are soft: concurrent activations may exceed them,
+    /// and busy activations are kept even if limit is exceeded.
+    async fn ensure_capacity(&self) -> Result<(), LocalAddrError> {
+        if let Some(max_active_actors) = self.inner.preferences.max_active_actors {
+            let excess = (self.inner.cache.len() + 1).saturating_sub(max_active_actors);
+            if excess > 0 {
+                self.inner
+                    .housekeeping_actor
+                    .send(EvictActors { count: excess })
+                    .await?;
+            }
+        }
+        self.inner.activation_limit.ensure_capacity().await
+    }
+
     async fn start_housekeeping(&self) -> Result<(), StartHousekeepingError> {
         if self.inner.house_keeping_started.load(Ordering::Relaxed) {
             return Ok(());
         }
 
+        self.inner
+            .activation_limit
+            .start_memory_pressure(self.inner.preferences.actor_activation_timeout)
+            .await?;
+
         let mut lock = self.inner.housekeeping_lock.lock().await;
 
         // double check
@@ -169,3 +252,54 @@ impl<A: VirtualActor> WeakActorActivator<A> {
         Some(ActorActivator { inner })
     }
 }
+
+impl<A: VirtualActor> ActivationEvictor for WeakActorActivator<A> {
+    fn eviction_candidate(
+        &self,
+    ) -> BoxFuture<'_, Result<Option<EvictionCandidate>, LocalAddrError>> {
+        async move {
+            let Some(activator) = self.upgrade() else {
+                return Ok(None);
+            };
+            // no activations before housekeeping is started
+            if !activator
+                .inner
+                .house_keeping_started
+                .load(Ordering::Relaxed)
+            {
+                return Ok(None);
+            }
+            activator
+                .inner
+                .housekeeping_actor
+                .send(FindEvictionCandidate)
+                .await
+        }
+        .boxed()
+    }
+
+    fn evict(&self, count: usize) -> BoxFuture<'_, Result<Vec<String>, LocalAddrError>> {
+        async move {
+            let Some(activator) = self.upgrade() else {
+                return Ok(Vec::new());
+            };
+            activator
+                .inner
+                .housekeeping_actor
+                .send(EvictActors { count })
+                .await
+        }
+        .boxed()
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.upgrade().map_or(0, |activator| {
+            activator
+                .inner
+                .cache
+                .iter()
+                .map(|e| e.value().handle.memory_usage().get())
+                .sum()
+        })
+    }
+}
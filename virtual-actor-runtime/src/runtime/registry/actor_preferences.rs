@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use virtual_actor::virtual_actor::VirtualActorPreferences;
+
+use crate::{runtime::runtime_preferences::RuntimePreferences, RetryPolicy};
+
+/// Preferences of registered virtual actor type,
+/// runtime preferences with per-type overrides applied
+#[derive(Debug, Clone)]
+pub struct ActorPreferences {
+    /// Interval for actors garbage collection
+    pub garbage_collect_interval: Duration,
+    /// Timeout for actor to be idle before it will be collected
+    pub actor_idle_timeout: Duration,
+    /// Timeout for actor to wait for activation before returning an error
+    pub actor_activation_timeout: Duration,
+    /// Timeout for actor to wait for shutdown during graceful shutdown
+    pub actor_shutdown_interval: Duration,
+    /// Maximal number of active actors of the type, unlimited if `None`
+    pub max_active_actors: Option<usize>,
+    /// Redelivery of messages racing with deactivation
+    pub retry_policy: RetryPolicy,
+}
+
+impl ActorPreferences {
+    /// Applies overrides to runtime preferences
+    pub fn new(runtime: &RuntimePreferences, overrides: &VirtualActorPreferences) -> Self {
+        Self {
+            garbage_collect_interval: overrides
+                .garbage_collect_interval
+                .unwrap_or(runtime.garbage_collect_interval),
+            actor_idle_timeout: overrides
+                .actor_idle_timeout
+                .unwrap_or(runtime.actor_idle_timeout),
+            actor_activation_timeout: overrides
+                .actor_activation_timeout
+                .unwrap_or(runtime.actor_activation_timeout),
+            actor_shutdown_interval: overrides
+                .actor_shutdown_interval
+                .unwrap_or(runtime.actor_shutdown_interval),
+            max_active_actors: overrides
+                .max_active_actors
+                .or(runtime.max_active_actors_per_type),
+            retry_policy: runtime.retry_policy.clone(),
+        }
+    }
+}
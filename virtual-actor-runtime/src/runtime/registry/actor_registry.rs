@@ -1,17 +1,31 @@
-use std::sync::{Arc, Weak};
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Weak},
+};
 
 use dashmap::DashMap;
+use tokio::sync::broadcast;
 use virtual_actor::{
     actor::{Actor, ActorContext, ActorFactory, ActorName},
-    virtual_actor::{VirtualActor, VirtualActorFactory},
+    virtual_actor::{VirtualActor, VirtualActorFactory, VirtualActorPreferences},
 };
 
 use crate::{
-    address::VirtualAddr, context::ActorContextFactory, executor::errors::LocalExecutorError,
-    runtime::runtime_preferences::RuntimePreferences, ExecutorHandle, LocalAddr,
+    address::VirtualAddr,
+    cluster::{Member, MembershipEvent},
+    context::ActorContextFactory,
+    executor::errors::LocalExecutorError,
+    runtime::runtime_preferences::RuntimePreferences,
+    ExecutorHandle, LocalAddr,
+};
+
+use super::{
+    activation_limit::ActivationLimit, actor_activator::ActorActivator,
+    actor_preferences::ActorPreferences, errors::ActivateActorError,
 };
 
-use super::{actor_activator::ActorActivator, errors::ActivateActorError};
+/// Membership events buffered for slow subscribers
+const MEMBERSHIP_EVENTS_CAPACITY: usize = 1024;
 
 pub struct ActorRegistry {
     inner: Arc<Inner>,
@@ -35,22 +49,53 @@ impl WeakActorRegistry {
         let reg = ActorRegistry { inner };
         reg.get_or_create(id)
     }
+
+    /// Virtual actor has activation which is not stopped yet,
+    /// `false` if actor is not registered or registry is dropped
+    pub fn is_active<A: VirtualActor>(&self, id: &A::ActorId) -> bool {
+        self.inner
+            .upgrade()
+            .is_some_and(|inner| ActorRegistry { inner }.is_active::<A>(id))
+    }
+
+    /// Applies membership change to cluster view and notifies subscribers
+    pub fn publish_membership_event(&self, event: MembershipEvent) {
+        if let Some(inner) = self.inner.upgrade() {
+            ActorRegistry { inner }.publish_membership_event(event);
+        }
+    }
 }
 
 struct Inner {
     activators: DashMap<ActorName, Box<dyn std::any::Any + Send + Sync>>,
     housekeeping_executor: ExecutorHandle,
+    activation_limit: Arc<ActivationLimit>,
+    /// Cluster members by transport address, including local node
+    members: DashMap<SocketAddr, Member>,
+    /// Subscribers of membership changes
+    membership_events: broadcast::Sender<MembershipEvent>,
 }
 
 impl ActorRegistry {
-    pub fn new(housekeeping_executor: &ExecutorHandle) -> Self {
+    pub fn new(
+        housekeeping_executor: &ExecutorHandle,
+        preferences: &RuntimePreferences,
+    ) -> Result<Self, LocalExecutorError> {
+        let activation_limit = ActivationLimit::new(
+            preferences.max_active_actors,
+            preferences.memory_pressure.as_ref(),
+            housekeeping_executor,
+        )?;
         let inner = Inner {
             activators: DashMap::new(),
             housekeeping_executor: housekeeping_executor.clone(),
+            activation_limit: Arc::new(activation_limit),
+            members: DashMap::new(),
+            membership_events: broadcast::channel(MEMBERSHIP_EVENTS_CAPACITY).0,
         };
-        Self {
+        Ok(Self {
             inner: Arc::new(inner),
-        }
+        })
     }
 
     pub fn weak_ref(&self) -> WeakActorRegistry {
@@ -59,12 +104,16 @@ impl ActorRegistry {
         }
     }
 
+    /// Registers virtual actor type.
+    /// Preferences overrides are taken from registration first, then from factory,
+    /// then from runtime preferences.
     pub fn register_actor<AF, CF>(
         &self,
         factory: AF,
         context_factory: Arc<CF>,
         executor: &ExecutorHandle,
-        preferences: Arc<RuntimePreferences>,
+        runtime_preferences: &RuntimePreferences,
+        overrides: VirtualActorPreferences,
     ) -> Result<(), LocalExecutorError>
     where
         <<AF as ActorFactory>::Actor as Actor>::ActorContext: ActorContext<
@@ -76,13 +125,19 @@ impl ActorRegistry {
         CF: ActorContextFactory<<AF as ActorFactory>::Actor> + 'static,
     {
         let name = <AF as ActorFactory>::Actor::name();
+        let overrides = overrides.or(&factory.virtual_actor_preferences());
+        let preferences = Arc::new(ActorPreferences::new(runtime_preferences, &overrides));
         let activator = ActorActivator::new(
             factory,
             context_factory,
             executor,
             &self.inner.housekeeping_executor,
             preferences,
+            &self.inner.activation_limit,
         )?;
+        self.inner
+            .activation_limit
+            .register(name, Arc::new(activator.weak_ref()));
         self.inner.activators.insert(name, Box::new(activator));
         Ok(())
     }
@@ -105,4 +160,40 @@ impl ActorRegistry {
         let addr = VirtualAddr::new(id, activator);
         Ok(addr)
     }
+
+    /// Virtual actor has activation which is not stopped yet,
+    /// `false` if actor is not registered
+    pub fn is_active<A: VirtualActor>(&self, id: &A::ActorId) -> bool {
+        self.inner
+            .activators
+            .get(A::name())
+            .and_then(|activator| {
+                activator
+                    .downcast_ref::<ActorActivator<A>>()
+                    .map(|activator| activator.is_active(id))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Applies membership change to cluster view and notifies subscribers
+    pub fn publish_membership_event(&self, event: MembershipEvent) {
+        let member = event.member();
+        self.inner.members.insert(member.address, member.clone());
+        // no subscribers is not an error
+        let _ = self.inner.membership_events.send(event);
+    }
+
+    /// Cluster members known by local node
+    pub fn members(&self) -> Vec<Member> {
+        self.inner
+            .members
+            .iter()
+            .map(|member| member.value().clone())
+            .collect()
+    }
+
+    /// Receiver of membership changes published after subscription
+    pub fn subscribe_membership_events(&self) -> broadcast::Receiver<MembershipEvent> {
+        self.inner.membership_events.subscribe()
+    }
 }
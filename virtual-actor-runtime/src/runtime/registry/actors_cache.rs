@@ -1,6 +1,13 @@
-use std::sync::Arc;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
 
 use dashmap::{mapref::multiple::RefMulti, DashMap};
+use tokio::sync::Mutex;
 use virtual_actor::virtual_actor::VirtualActor;
 
 use crate::address::ActorHandle;
@@ -9,38 +16,134 @@ pub struct ActorsCache<A: VirtualActor> {
     inner: Arc<Inner<A>>,
 }
 
+/// Cached actor handle with time of last access through cache
+pub struct CachedActor<A: VirtualActor> {
+    pub handle: ActorHandle<A>,
+    pub last_access: Instant,
+}
+
 struct Inner<A: VirtualActor> {
-    cache: DashMap<A::ActorId, ActorHandle<A>>,
+    cache: DashMap<A::ActorId, CachedActor<A>>,
+    /// Activations detached on actor request, which are not stopped yet
+    deactivating: DashMap<A::ActorId, ActorHandle<A>>,
+    /// Locks of actors which are being activated, only one activation of actor runs at a time
+    activating: DashMap<A::ActorId, Arc<Mutex<()>>>,
+    /// Number of active actors of all types in registry
+    total_active: Arc<AtomicUsize>,
 }
 
 impl<A: VirtualActor> ActorsCache<A> {
-    pub fn new() -> Self {
+    pub fn new(total_active: &Arc<AtomicUsize>) -> Self {
         let inner = Inner {
             cache: DashMap::new(),
+            deactivating: DashMap::new(),
+            activating: DashMap::new(),
+            total_active: total_active.clone(),
         };
         Self {
             inner: Arc::new(inner),
         }
     }
 
+    /// Returns cached handle and marks actor as recently used
     pub fn get(&self, actor_id: &A::ActorId) -> Option<ActorHandle<A>> {
+        self.inner.cache.get_mut(actor_id).map(|mut cached| {
+            cached.last_access = Instant::now();
+            cached.handle.clone()
+        })
+    }
+
+    pub fn insert(&self, actor_id: A::ActorId, handle: ActorHandle<A>) {
+        let cached = CachedActor {
+            handle,
+            last_access: Instant::now(),
+        };
+        if self.inner.cache.insert(actor_id, cached).is_none() {
+            self.inner.total_active.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Lock held while actor is activated, shared by concurrent activations of actor
+    pub fn activation_lock(&self, actor_id: &A::ActorId) -> Arc<Mutex<()>> {
         self.inner
+            .activating
+            .entry(actor_id.clone())
+            .or_default()
+            .clone()
+    }
+
+    /// Forgets activation lock once activation is finished
+    pub fn remove_activation_lock(&self, actor_id: &A::ActorId, lock: &Arc<Mutex<()>>) {
+        self.inner
+            .activating
+            .remove_if(actor_id, |_, activating| Arc::ptr_eq(activating, lock));
+    }
+
+    pub fn remove(&self, actor_id: &A::ActorId) -> Option<ActorHandle<A>> {
+        let removed = self.inner.cache.remove(actor_id).map(|kv| kv.1.handle);
+        if removed.is_some() {
+            self.inner.total_active.fetch_sub(1, Ordering::Relaxed);
+        }
+        removed
+    }
+
+    /// Removes activation if it is still cached, returns `false` if another activation is cached
+    pub fn remove_stale(&self, actor_id: &A::ActorId, handle: &ActorHandle<A>) -> bool {
+        let removed = self
+            .inner
             .cache
+            .remove_if(actor_id, |_, cached| cached.handle.is_same(handle));
+        if removed.is_some() {
+            self.inner.total_active.fetch_sub(1, Ordering::Relaxed);
+        }
+        removed.is_some()
+    }
+
+    pub fn contains(&self, actor_id: &A::ActorId) -> bool {
+        self.inner.cache.contains_key(actor_id)
+    }
+
+    /// Moves activation to deactivating ones, if it is still cached
+    pub fn detach(&self, actor_id: &A::ActorId, handle: &ActorHandle<A>) {
+        let removed = self
+            .inner
+            .cache
+            .remove_if(actor_id, |_, cached| cached.handle.is_same(handle));
+        if let Some((actor_id, cached)) = removed {
+            self.inner.total_active.fetch_sub(1, Ordering::Relaxed);
+            self.inner.deactivating.insert(actor_id, cached.handle);
+        }
+    }
+
+    /// Detached activation of actor which may be not stopped yet
+    pub fn deactivating(&self, actor_id: &A::ActorId) -> Option<ActorHandle<A>> {
+        self.inner
+            .deactivating
             .get(actor_id)
             .map(|handle| handle.value().clone())
     }
 
-    pub fn insert(&self, actor_id: A::ActorId, handle: ActorHandle<A>) {
-        self.inner.cache.insert(actor_id, handle);
+    /// Forgets stopped detached activation
+    pub fn remove_deactivated(&self, actor_id: &A::ActorId, handle: &ActorHandle<A>) {
+        self.inner
+            .deactivating
+            .remove_if(actor_id, |_, deactivating| deactivating.is_same(handle));
     }
 
-    pub fn remove(&self, actor_id: &A::ActorId) -> Option<ActorHandle<A>> {
-        self.inner.cache.remove(actor_id).map(|kv| kv.1)
+    /// Forgets all stopped detached activations
+    pub fn purge_deactivated(&self) {
+        self.inner
+            .deactivating
+            .retain(|_, handle| !handle.is_finished());
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.cache.len()
     }
 
     pub fn iter(
         &self,
-    ) -> impl Iterator<Item = RefMulti<'_, <A as VirtualActor>::ActorId, ActorHandle<A>>> {
+    ) -> impl Iterator<Item = RefMulti<'_, <A as VirtualActor>::ActorId, CachedActor<A>>> {
         self.inner.cache.iter()
     }
 }
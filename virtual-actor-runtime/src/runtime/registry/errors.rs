@@ -35,6 +35,9 @@ pub enum RuntimeSpawnError {
     /// Actor start error
     #[error("Actor start error {0:?}")]
     ActorStartError(#[from] ActorStartError),
+    /// Eviction of activations error
+    #[error("EvictionError {0:?}")]
+    Eviction(#[from] LocalAddrError),
 }
 
 /// Actor activation error
@@ -5,9 +5,7 @@ use virtual_actor::{
     actor::Actor, local_actor::LocalActor, message::MessageHandler, virtual_actor::VirtualActor,
 };
 
-use crate::runtime::{
-    registry::actors_cache::ActorsCache, runtime_preferences::RuntimePreferences,
-};
+use crate::runtime::registry::{actor_preferences::ActorPreferences, actors_cache::ActorsCache};
 
 use super::{
     actor_counters_map::ActorCountersMap, context::HousekeepingContext,
@@ -17,7 +15,7 @@ use super::{
 pub struct HousekeepingActor<A: VirtualActor> {
     pub(super) graceful_cancellation: CancellationToken,
     pub(super) cache: ActorsCache<A>,
-    pub(super) preferences: Arc<RuntimePreferences>,
+    pub(super) preferences: Arc<ActorPreferences>,
     pub(super) actor_counters: ActorCountersMap<A>,
 }
 
@@ -40,6 +38,20 @@ impl<A: VirtualActor> Actor for HousekeepingActor<A> {
                 self.handle(msg, ctx).await;
                 Ok(())
             }
+            InnerMessageEnvelope::EvictActors(msg, responder) => {
+                let result = self.handle(msg, ctx).await;
+                if let Some(mut responder) = responder {
+                    responder.respond(Ok(result))?;
+                }
+                Ok(())
+            }
+            InnerMessageEnvelope::FindEvictionCandidate(msg, responder) => {
+                let result = self.handle(msg, ctx).await;
+                if let Some(mut responder) = responder {
+                    responder.respond(Ok(result))?;
+                }
+                Ok(())
+            }
         }
     }
 }
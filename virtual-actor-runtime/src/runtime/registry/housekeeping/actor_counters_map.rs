@@ -6,12 +6,14 @@ use std::{
 
 use virtual_actor::virtual_actor::VirtualActor;
 
-use crate::address::ActorHandle;
+use crate::runtime::registry::actors_cache::CachedActor;
 
 pub struct CountersInfo {
     dispatched: usize,
     processed: usize,
     timestamp: Instant,
+    last_access: Instant,
+    held: bool,
 }
 
 impl fmt::Debug for CountersInfo {
@@ -20,10 +22,34 @@ impl fmt::Debug for CountersInfo {
             .field("dispatched", &self.dispatched)
             .field("processed", &self.processed)
             .field("timestamp", &self.timestamp.elapsed())
+            .field("last_access", &self.last_access.elapsed())
+            .field("held", &self.held)
             .finish()
     }
 }
 
+/// Candidate for eviction, ordered from the best candidate:
+/// idle actors go first, then least recently used, then least frequently used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EvictionCandidate {
+    /// Actor has unprocessed messages or is held active
+    pub busy: bool,
+    /// Time when actor was accessed through cache last time
+    pub last_access: Instant,
+    /// Number of processed messages
+    pub processed: usize,
+}
+
+impl From<&CountersInfo> for EvictionCandidate {
+    fn from(counters_info: &CountersInfo) -> Self {
+        Self {
+            busy: counters_info.dispatched != counters_info.processed || counters_info.held,
+            last_access: counters_info.last_access,
+            processed: counters_info.processed,
+        }
+    }
+}
+
 pub struct ActorCountersMap<A: VirtualActor> {
     map: HashMap<A::ActorId, CountersInfo>,
 }
@@ -38,13 +64,19 @@ impl<A: VirtualActor> ActorCountersMap<A> {
     /// Updates counters and timestamp for actor with given id.
     /// If actor with given id is not present in map, it will be added.
     /// If actor with given id is present in map, but counters are the same, nothing will be changed.
-    pub fn update(&mut self, actor_id: &A::ActorId, handle: &ActorHandle<A>) {
+    /// Time of last access is always updated.
+    pub fn update(&mut self, actor_id: &A::ActorId, cached: &CachedActor<A>) {
+        let handle = &cached.handle;
+        let last_access = cached.last_access;
         let dispatched = handle.dispatched_msg_counter().get();
         let processed = handle.processed_msg_counter().get();
+        let held = handle.deactivation().is_held();
 
         self.map
             .entry(actor_id.clone())
             .and_modify(|counters_info| {
+                counters_info.last_access = last_access;
+                counters_info.held = held;
                 if counters_info.dispatched == dispatched && counters_info.processed == processed {
                     return;
                 }
@@ -56,14 +88,33 @@ impl<A: VirtualActor> ActorCountersMap<A> {
                 dispatched,
                 processed,
                 timestamp: Instant::now(),
+                last_access,
+                held,
             });
     }
 
+    /// Keeps only actors with ids matching predicate.
+    pub fn retain(&mut self, mut predicate: impl FnMut(&A::ActorId) -> bool) {
+        self.map.retain(|actor_id, _| predicate(actor_id));
+    }
+
     /// Removes actor with given id from map.
     pub fn remove(&mut self, actor_id: &A::ActorId) {
         self.map.remove(actor_id);
     }
 
+    /// Returns up to `count` best candidates for eviction, best candidate goes first.
+    pub fn eviction_candidates(&self, count: usize) -> Vec<(A::ActorId, EvictionCandidate)> {
+        let mut candidates = self
+            .map
+            .iter()
+            .map(|(id, counters_info)| (id.clone(), EvictionCandidate::from(counters_info)))
+            .collect::<Vec<_>>();
+        candidates.sort_unstable_by_key(|(_, candidate)| *candidate);
+        candidates.truncate(count);
+        candidates
+    }
+
     /// Returns true if actor with given id is idle for given `idle_time`.
     pub fn is_idle(&self, id: &A::ActorId, idle_time: Duration) -> bool {
         if let Some(counters_info) = self.map.get(id) {
@@ -5,23 +5,21 @@ use virtual_actor::{
     actor::ActorFactory, local_actor::LocalActorFactory, virtual_actor::VirtualActor,
 };
 
-use crate::runtime::{
-    registry::actors_cache::ActorsCache, runtime_preferences::RuntimePreferences,
-};
+use crate::runtime::registry::{actor_preferences::ActorPreferences, actors_cache::ActorsCache};
 
 use super::{actor_counters_map::ActorCountersMap, HousekeepingActor};
 
 pub struct HousekeepingActorFactory<A: VirtualActor> {
     graceful_cancellation: CancellationToken,
     cache: ActorsCache<A>,
-    preferences: Arc<RuntimePreferences>,
+    preferences: Arc<ActorPreferences>,
 }
 
 impl<A: VirtualActor> HousekeepingActorFactory<A> {
     pub fn new(
         graceful_cancellation: CancellationToken,
         cache: ActorsCache<A>,
-        preferences: &Arc<RuntimePreferences>,
+        preferences: &Arc<ActorPreferences>,
     ) -> Self {
         Self {
             graceful_cancellation,
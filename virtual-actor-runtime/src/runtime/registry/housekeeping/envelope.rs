@@ -3,11 +3,29 @@ use virtual_actor::{
     virtual_actor::VirtualActor,
 };
 
-use super::{gc_actors::GarbageCollectActors, HousekeepingActor};
+use super::{
+    evict_actors::{EvictActors, FindEvictionCandidate},
+    gc_actors::GarbageCollectActors,
+    HousekeepingActor,
+};
 
-#[derive(Debug)]
 pub enum InnerMessageEnvelope {
     GarbageCollectActors(GarbageCollectActors),
+    EvictActors(EvictActors, Option<Box<dyn Responder<EvictActors>>>),
+    FindEvictionCandidate(
+        FindEvictionCandidate,
+        Option<Box<dyn Responder<FindEvictionCandidate>>>,
+    ),
+}
+
+impl std::fmt::Debug for InnerMessageEnvelope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GarbageCollectActors(msg) => msg.fmt(f),
+            Self::EvictActors(msg, _) => msg.fmt(f),
+            Self::FindEvictionCandidate(msg, _) => msg.fmt(f),
+        }
+    }
 }
 
 impl<A: VirtualActor> MessageEnvelopeFactory<HousekeepingActor<A>, GarbageCollectActors>
@@ -21,4 +39,40 @@ impl<A: VirtualActor> MessageEnvelopeFactory<HousekeepingActor<A>, GarbageCollec
     }
 }
 
-impl<A: VirtualActor> MessageEnvelope<HousekeepingActor<A>> for InnerMessageEnvelope {}
+impl<A: VirtualActor> MessageEnvelopeFactory<HousekeepingActor<A>, EvictActors>
+    for InnerMessageEnvelope
+{
+    fn from_message<R: Responder<EvictActors> + Sized + 'static>(
+        msg: EvictActors,
+        responder: Option<R>,
+    ) -> Self {
+        Self::EvictActors(
+            msg,
+            responder.map(|r| Box::new(r) as Box<dyn Responder<EvictActors>>),
+        )
+    }
+}
+
+impl<A: VirtualActor> MessageEnvelopeFactory<HousekeepingActor<A>, FindEvictionCandidate>
+    for InnerMessageEnvelope
+{
+    fn from_message<R: Responder<FindEvictionCandidate> + Sized + 'static>(
+        msg: FindEvictionCandidate,
+        responder: Option<R>,
+    ) -> Self {
+        Self::FindEvictionCandidate(
+            msg,
+            responder.map(|r| Box::new(r) as Box<dyn Responder<FindEvictionCandidate>>),
+        )
+    }
+}
+
+impl<A: VirtualActor> MessageEnvelope<HousekeepingActor<A>> for InnerMessageEnvelope {
+    fn message_name(&self) -> virtual_actor::message::MessageName {
+        match self {
+            Self::GarbageCollectActors(_) => stringify!(GarbageCollectActors),
+            Self::EvictActors(_, _) => stringify!(EvictActors),
+            Self::FindEvictionCandidate(_, _) => stringify!(FindEvictionCandidate),
+        }
+    }
+}
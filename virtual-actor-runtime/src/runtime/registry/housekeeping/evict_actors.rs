@@ -0,0 +1,87 @@
+use virtual_actor::{
+    message::{Message, MessageHandler},
+    virtual_actor::VirtualActor,
+};
+
+use crate::GracefulShutdown;
+
+use super::{actor_counters_map::EvictionCandidate, HousekeepingActor};
+
+/// Deactivates up to `count` least recently used idle actors, returns ids of deactivated actors.
+/// Busy actors are never evicted, their graceful shutdown may cancel in-flight handlers.
+#[derive(Debug)]
+pub struct EvictActors {
+    pub count: usize,
+}
+
+impl Message for EvictActors {
+    type Result = Vec<String>;
+}
+
+/// Finds best candidate for eviction
+#[derive(Debug)]
+pub struct FindEvictionCandidate;
+
+impl Message for FindEvictionCandidate {
+    type Result = Option<EvictionCandidate>;
+}
+
+impl<A: VirtualActor> HousekeepingActor<A> {
+    /// Refreshes activity counters of cached actors
+    fn refresh_counters(&mut self) {
+        // forget actors detached from cache on their request
+        let cache = &self.cache;
+        self.actor_counters
+            .retain(|actor_id| cache.contains(actor_id));
+        for e in self.cache.iter() {
+            let (actor_id, cached) = e.pair();
+            self.actor_counters.update(actor_id, cached);
+        }
+    }
+}
+
+impl<A: VirtualActor> MessageHandler<FindEvictionCandidate> for HousekeepingActor<A> {
+    async fn handle(
+        &mut self,
+        _msg: FindEvictionCandidate,
+        _ctx: &Self::ActorContext,
+    ) -> <FindEvictionCandidate as Message>::Result {
+        self.refresh_counters();
+        self.actor_counters
+            .eviction_candidates(1)
+            .into_iter()
+            .map(|(_, candidate)| candidate)
+            .next()
+    }
+}
+
+impl<A: VirtualActor> MessageHandler<EvictActors> for HousekeepingActor<A> {
+    async fn handle(
+        &mut self,
+        msg: EvictActors,
+        _ctx: &Self::ActorContext,
+    ) -> <EvictActors as Message>::Result {
+        self.refresh_counters();
+        let actor_name = A::name();
+        let mut evicted = Vec::new();
+        for (actor_id, _) in self
+            .actor_counters
+            .eviction_candidates(msg.count)
+            .into_iter()
+            .filter(|(_, candidate)| !candidate.busy)
+        {
+            println!("Evicting actor {actor_name}::{actor_id}");
+            if let Some(handle) = self.cache.remove(&actor_id) {
+                evicted.push(actor_id.to_string());
+                let shutdown = handle
+                    .graceful_shutdown(self.preferences.actor_shutdown_interval)
+                    .await;
+                if let Err(e) = shutdown {
+                    eprintln!("Failed to gracefully shutdown actor {actor_id}: {e:?}");
+                }
+            }
+            self.actor_counters.remove(&actor_id);
+        }
+        evicted
+    }
+}
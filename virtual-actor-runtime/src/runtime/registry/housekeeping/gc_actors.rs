@@ -1,13 +1,13 @@
-use std::time::Duration;
-
 use virtual_actor::{
     actor::{ActorAddr, ActorContext, WeakActorAddr},
     message::{Message, MessageHandler},
-    utils::CancellationToken,
     virtual_actor::VirtualActor,
 };
 
-use crate::{utils::cancellation_token_wrapper::CancellationTokenWrapper, GracefulShutdown};
+use crate::{
+    utils::sleep::{sleep_with_cancel, SleepWaitError},
+    GracefulShutdown,
+};
 
 use super::HousekeepingActor;
 
@@ -28,50 +28,54 @@ impl<A: VirtualActor> MessageHandler<GarbageCollectActors> for HousekeepingActor
         let mut idle_actors = Vec::new();
         let mut finished_actors = Vec::new();
 
+        self.cache.purge_deactivated();
+
         // find all finished or idle actors
         for e in self.cache.iter() {
-            let (actor_id, handle) = e.pair();
-            self.actor_counters.update(actor_id, handle);
+            let (actor_id, cached) = e.pair();
+            let handle = &cached.handle;
+            self.actor_counters.update(actor_id, cached);
             let is_idle = self
                 .actor_counters
                 .is_idle(actor_id, self.preferences.actor_idle_timeout);
             if handle.is_finished() {
-                finished_actors.push(actor_id.clone());
+                finished_actors.push((actor_id.clone(), handle.clone()));
                 continue;
             }
-            if is_idle {
-                idle_actors.push(actor_id.clone());
+            let deactivation = handle.deactivation();
+            if is_idle && !deactivation.is_delayed() && !deactivation.is_held() {
+                idle_actors.push((actor_id.clone(), handle.clone()));
             }
         }
 
         let actor_name = A::name();
 
-        // remove finished actors
-        for actor_id in finished_actors {
+        // remove finished actors,
+        // fresh activation may be already cached under the same id, so only found ones are removed
+        for (actor_id, handle) in finished_actors {
             println!("Actor {actor_name}::{actor_id} is finished");
-            self.cache.remove(&actor_id);
-            self.actor_counters.remove(&actor_id);
+            if self.cache.remove_stale(&actor_id, &handle) {
+                self.actor_counters.remove(&actor_id);
+            }
         }
 
         // remove idle actors
-        for actor_id in idle_actors {
-            println!("Shutting down actor {actor_name}::{actor_id}");
-            let handle = self.cache.remove(&actor_id);
-            if let Some(handle) = handle {
-                let shutdown = handle
-                    .graceful_shutdown(self.preferences.actor_shutdown_interval)
-                    .await;
-                if let Err(e) = shutdown {
-                    eprintln!("Failed to gracefully shutdown actor {actor_id}: {e:?}");
-                }
+        for (actor_id, handle) in idle_actors {
+            // activation is removed before shutdown, so new messages activate fresh instance
+            if !self.cache.remove_stale(&actor_id, &handle) {
+                continue;
+            }
+            self.actor_counters.remove(&actor_id);
 
-                println!(
-                    "Actor {actor_name}::{actor_id} is idle and has been successfully shutdown"
-                );
+            println!("Shutting down actor {actor_name}::{actor_id}");
+            let shutdown = handle
+                .graceful_shutdown(self.preferences.actor_shutdown_interval)
+                .await;
+            if let Err(e) = shutdown {
+                eprintln!("Failed to gracefully shutdown actor {actor_id}: {e:?}");
             }
 
-            self.cache.remove(&actor_id);
-            self.actor_counters.remove(&actor_id);
+            println!("Actor {actor_name}::{actor_id} is idle and has been successfully shutdown");
         }
 
         // schedule next garbage collection
@@ -98,22 +102,3 @@ impl<A: VirtualActor> MessageHandler<GarbageCollectActors> for HousekeepingActor
         });
     }
 }
-
-#[derive(Debug)]
-enum SleepWaitError {
-    GracefullyCancelled,
-    Cancelled,
-}
-
-async fn sleep_with_cancel(
-    duration: Duration,
-    graceful_cancellation: &tokio_util::sync::CancellationToken,
-    cancellation: &CancellationTokenWrapper,
-) -> Result<(), SleepWaitError> {
-    tokio::select! {
-        biased;
-        () = graceful_cancellation.cancelled() => Err(SleepWaitError::GracefullyCancelled),
-        () = cancellation.cancelled()  => Err(SleepWaitError::Cancelled),
-        () = tokio::time::sleep(duration) => Ok(()),
-    }
-}
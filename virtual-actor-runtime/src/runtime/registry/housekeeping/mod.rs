@@ -4,9 +4,12 @@ mod actor_factory;
 mod context;
 mod context_factory;
 mod envelope;
+mod evict_actors;
 mod gc_actors;
 
 pub use actor::HousekeepingActor;
+pub use actor_counters_map::EvictionCandidate;
 pub use actor_factory::HousekeepingActorFactory;
 pub use context_factory::HousekeepingContextFactory;
+pub use evict_actors::{EvictActors, FindEvictionCandidate};
 pub use gc_actors::GarbageCollectActors;
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+use virtual_actor::{actor::Actor, local_actor::LocalActor, message::MessageHandler};
+
+use crate::runtime::{
+    periodic_actor::PeriodicActorContext, registry::activation_limit::ActivationEvictors,
+};
+
+use super::{envelope::InnerMessageEnvelope, MemoryPressurePreferences};
+
+/// Deactivates idle virtual actors of all types while memory is under pressure
+pub struct MemoryPressureActor {
+    pub(super) graceful_cancellation: CancellationToken,
+    pub(super) evictors: ActivationEvictors,
+    pub(super) preferences: Arc<MemoryPressurePreferences>,
+}
+
+impl Actor for MemoryPressureActor {
+    type ActorContext = PeriodicActorContext<Self>;
+
+    type MessagesEnvelope = InnerMessageEnvelope;
+
+    fn name() -> virtual_actor::actor::ActorName {
+        stringify!(MemoryPressureActor)
+    }
+
+    async fn handle_envelope(
+        &mut self,
+        envelope: Self::MessagesEnvelope,
+        ctx: &Self::ActorContext,
+    ) -> Result<(), virtual_actor::errors::ResponderError> {
+        match envelope {
+            InnerMessageEnvelope::CheckMemoryPressure(msg) => {
+                self.handle(msg, ctx).await;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl LocalActor for MemoryPressureActor {}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+use virtual_actor::{actor::ActorFactory, local_actor::LocalActorFactory};
+
+use crate::runtime::registry::activation_limit::ActivationEvictors;
+
+use super::{MemoryPressureActor, MemoryPressurePreferences};
+
+pub struct MemoryPressureActorFactory {
+    graceful_cancellation: CancellationToken,
+    evictors: ActivationEvictors,
+    preferences: Arc<MemoryPressurePreferences>,
+}
+
+impl MemoryPressureActorFactory {
+    pub fn new(
+        graceful_cancellation: CancellationToken,
+        evictors: &ActivationEvictors,
+        preferences: MemoryPressurePreferences,
+    ) -> Self {
+        Self {
+            graceful_cancellation,
+            evictors: evictors.clone(),
+            preferences: Arc::new(preferences),
+        }
+    }
+}
+
+impl ActorFactory for MemoryPressureActorFactory {
+    type Actor = MemoryPressureActor;
+}
+
+impl LocalActorFactory for MemoryPressureActorFactory {
+    type Error = std::convert::Infallible;
+
+    async fn create_actor(&self) -> Result<MemoryPressureActor, Self::Error> {
+        Ok(MemoryPressureActor {
+            graceful_cancellation: self.graceful_cancellation.clone(),
+            evictors: self.evictors.clone(),
+            preferences: self.preferences.clone(),
+        })
+    }
+}
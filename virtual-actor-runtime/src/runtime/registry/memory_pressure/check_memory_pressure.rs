@@ -0,0 +1,102 @@
+use virtual_actor::message::{Message, MessageHandler};
+
+use crate::{
+    address::errors::LocalAddrError, runtime::registry::activation_limit::find_eviction_candidate,
+};
+
+use super::{
+    process_memory::resident_set_size, MemoryPressure, MemoryPressureActor, MemoryPressureEvent,
+};
+
+#[derive(Debug)]
+pub struct CheckMemoryPressure;
+
+impl Message for CheckMemoryPressure {
+    type Result = ();
+}
+
+impl MemoryPressureActor {
+    /// Returns first crossed memory threshold
+    fn measure(&self) -> Option<MemoryPressure> {
+        let preferences = &self.preferences;
+        if let Some(max_rss_bytes) = preferences.max_rss_bytes {
+            if let Some(rss_bytes) = resident_set_size() {
+                if rss_bytes > max_rss_bytes {
+                    return Some(MemoryPressure::Rss {
+                        rss_bytes,
+                        max_rss_bytes,
+                    });
+                }
+            }
+        }
+        if let Some(max_state_bytes) = preferences.max_state_bytes {
+            let state_bytes = self
+                .evictors
+                .iter()
+                .map(|e| e.value().memory_usage())
+                .sum::<usize>();
+            if state_bytes > max_state_bytes {
+                return Some(MemoryPressure::StateSize {
+                    state_bytes,
+                    max_state_bytes,
+                });
+            }
+        }
+        None
+    }
+
+    /// Deactivates longest idle actors until pressure is gone
+    /// or deactivations limit per check is reached
+    async fn relieve_pressure(&self) -> Result<(), LocalAddrError> {
+        let Some(mut pressure) = self.measure() else {
+            return Ok(());
+        };
+        let preferences = &self.preferences;
+        (preferences.reporter)(&MemoryPressureEvent::PressureDetected(pressure.clone()));
+
+        for _ in 0..preferences.max_deactivations_per_check {
+            let Some((actor_name, evictor, candidate)) =
+                find_eviction_candidate(&self.evictors).await?
+            else {
+                break;
+            };
+            // candidates are ordered, so all other actors are busy or recently used too
+            let idle_for = candidate.last_access.elapsed();
+            if candidate.busy || idle_for < preferences.min_idle_time {
+                break;
+            }
+            for actor_id in evictor.evict(1).await? {
+                (preferences.reporter)(&MemoryPressureEvent::ActorDeactivated {
+                    pressure: pressure.clone(),
+                    actor_name,
+                    actor_id,
+                    idle_for,
+                });
+            }
+            match self.measure() {
+                Some(p) => pressure = p,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MessageHandler<CheckMemoryPressure> for MemoryPressureActor {
+    async fn handle(
+        &mut self,
+        _msg: CheckMemoryPressure,
+        ctx: &Self::ActorContext,
+    ) -> <CheckMemoryPressure as Message>::Result {
+        if let Err(e) = self.relieve_pressure().await {
+            eprintln!("Failed to deactivate actors under memory pressure: {e:?}");
+        }
+
+        // schedule next check
+        ctx.schedule(
+            CheckMemoryPressure,
+            self.preferences.check_interval,
+            &self.graceful_cancellation,
+        );
+    }
+}
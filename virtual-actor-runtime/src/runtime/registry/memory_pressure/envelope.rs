@@ -0,0 +1,25 @@
+use virtual_actor::message::{MessageEnvelope, MessageEnvelopeFactory, MessageName, Responder};
+
+use super::{check_memory_pressure::CheckMemoryPressure, MemoryPressureActor};
+
+#[derive(Debug)]
+pub enum InnerMessageEnvelope {
+    CheckMemoryPressure(CheckMemoryPressure),
+}
+
+impl MessageEnvelopeFactory<MemoryPressureActor, CheckMemoryPressure> for InnerMessageEnvelope {
+    fn from_message<R: Responder<CheckMemoryPressure> + Sized + 'static>(
+        msg: CheckMemoryPressure,
+        _responder: Option<R>,
+    ) -> Self {
+        Self::CheckMemoryPressure(msg)
+    }
+}
+
+impl MessageEnvelope<MemoryPressureActor> for InnerMessageEnvelope {
+    fn message_name(&self) -> MessageName {
+        match self {
+            Self::CheckMemoryPressure(_) => stringify!(CheckMemoryPressure),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::{fmt, time::Duration};
+
+use virtual_actor::actor::ActorName;
+
+/// Memory threshold which is crossed
+#[derive(Debug, Clone)]
+pub enum MemoryPressure {
+    /// Resident set size of the process exceeds limit
+    Rss {
+        /// Resident set size in bytes
+        rss_bytes: usize,
+        /// Configured limit in bytes
+        max_rss_bytes: usize,
+    },
+    /// Sum of state sizes reported by actors exceeds limit
+    StateSize {
+        /// Sum of reported state sizes in bytes
+        state_bytes: usize,
+        /// Configured limit in bytes
+        max_state_bytes: usize,
+    },
+}
+
+impl fmt::Display for MemoryPressure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rss {
+                rss_bytes,
+                max_rss_bytes,
+            } => write!(f, "RSS {rss_bytes} bytes exceeds {max_rss_bytes} bytes"),
+            Self::StateSize {
+                state_bytes,
+                max_state_bytes,
+            } => write!(
+                f,
+                "actors state {state_bytes} bytes exceeds {max_state_bytes} bytes"
+            ),
+        }
+    }
+}
+
+/// Event of deactivation under memory pressure
+#[derive(Debug, Clone)]
+pub enum MemoryPressureEvent {
+    /// Memory threshold is crossed, idle actors are going to be deactivated
+    PressureDetected(MemoryPressure),
+    /// Idle actor is deactivated because of memory pressure
+    ActorDeactivated {
+        /// Pressure observed before deactivation
+        pressure: MemoryPressure,
+        /// Name of the actor
+        actor_name: ActorName,
+        /// Id of the actor
+        actor_id: String,
+        /// Time actor was idle
+        idle_for: Duration,
+    },
+}
+
+impl fmt::Display for MemoryPressureEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PressureDetected(pressure) => write!(f, "Memory pressure: {pressure}"),
+            Self::ActorDeactivated {
+                pressure,
+                actor_name,
+                actor_id,
+                idle_for,
+            } => write!(
+                f,
+                "Actor {actor_name}::{actor_id} idle for {idle_for:?} is deactivated, {pressure}"
+            ),
+        }
+    }
+}
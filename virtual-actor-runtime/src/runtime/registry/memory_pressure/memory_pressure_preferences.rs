@@ -0,0 +1,37 @@
+use std::{sync::Arc, time::Duration};
+
+use super::MemoryPressureEvent;
+
+/// Callback receiving memory pressure events
+pub type MemoryPressureReporter = Arc<dyn Fn(&MemoryPressureEvent) + Send + Sync>;
+
+/// Settings of deactivation of idle actors under memory pressure
+#[derive(Clone)]
+pub struct MemoryPressurePreferences {
+    /// Interval between memory checks
+    pub check_interval: Duration,
+    /// Resident set size of the process in bytes considered as pressure, Linux only
+    pub max_rss_bytes: Option<usize>,
+    /// Sum of state sizes reported by active actors considered as pressure,
+    /// see `RuntimeContext::report_memory_usage`
+    pub max_state_bytes: Option<usize>,
+    /// Time actor should be idle before it can be deactivated under pressure
+    pub min_idle_time: Duration,
+    /// Maximal number of actors deactivated by single check
+    pub max_deactivations_per_check: usize,
+    /// Receives memory pressure events, prints them to stderr by default
+    pub reporter: MemoryPressureReporter,
+}
+
+impl Default for MemoryPressurePreferences {
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::from_secs(1),
+            max_rss_bytes: None,
+            max_state_bytes: None,
+            min_idle_time: Duration::from_secs(1),
+            max_deactivations_per_check: 64,
+            reporter: Arc::new(|event| eprintln!("{event}")),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+mod actor;
+mod actor_factory;
+mod check_memory_pressure;
+mod envelope;
+mod memory_pressure_event;
+mod memory_pressure_preferences;
+mod monitor;
+mod process_memory;
+
+pub use actor::MemoryPressureActor;
+pub use actor_factory::MemoryPressureActorFactory;
+pub use check_memory_pressure::CheckMemoryPressure;
+pub use memory_pressure_event::{MemoryPressure, MemoryPressureEvent};
+pub use memory_pressure_preferences::MemoryPressurePreferences;
+pub use monitor::MemoryPressureMonitor;
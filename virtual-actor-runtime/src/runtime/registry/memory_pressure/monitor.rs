@@ -0,0 +1,80 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::Mutex;
+use virtual_actor::actor::ActorAddr;
+
+use crate::{
+    executor::{errors::LocalExecutorError, Handle},
+    runtime::{
+        periodic_actor::PeriodicActorContextFactory,
+        registry::{activation_limit::ActivationEvictors, errors::StartHousekeepingError},
+    },
+    LocalAddr,
+};
+
+use super::{
+    CheckMemoryPressure, MemoryPressureActor, MemoryPressureActorFactory, MemoryPressurePreferences,
+};
+
+/// Memory pressure actor, lazy started when first virtual actor is activated
+pub struct MemoryPressureMonitor {
+    actor: LocalAddr<MemoryPressureActor>,
+    /// Indicates that checks have started
+    started: AtomicBool,
+    /// Sync primitive, to prevent starting multiple checks
+    lock: Mutex<bool>,
+}
+
+impl MemoryPressureMonitor {
+    pub fn new(
+        housekeeping_executor: &Handle,
+        evictors: &ActivationEvictors,
+        preferences: MemoryPressurePreferences,
+    ) -> Result<Self, LocalExecutorError> {
+        let actor_factory = Arc::new(MemoryPressureActorFactory::new(
+            housekeeping_executor.mailbox_cancellation().child_token(),
+            evictors,
+            preferences,
+        ));
+        let context_factory = Arc::new(PeriodicActorContextFactory::new(
+            housekeeping_executor.executor_cancellation().child_token(),
+        ));
+        let actor = housekeeping_executor
+            .spawn_local_actor_no_wait(&actor_factory, &context_factory)?
+            .addr();
+        Ok(Self {
+            actor,
+            started: AtomicBool::new(false),
+            lock: Mutex::new(false),
+        })
+    }
+
+    /// Starts periodic memory checks if they are not started yet
+    pub async fn start(&self, timeout: Duration) -> Result<(), StartHousekeepingError> {
+        if self.started.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let mut lock = self.lock.lock().await;
+
+        // double check
+        if *lock {
+            return Ok(());
+        }
+
+        self.actor.wait_for_ready(timeout).await?;
+        self.actor.dispatch(CheckMemoryPressure).await?;
+
+        self.started.store(true, Ordering::Relaxed);
+        *lock = true;
+        drop(lock);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+/// Resident set size of the process in bytes, `None` if it is not available
+pub fn resident_set_size() -> Option<usize> {
+    platform::resident_set_size()
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    pub fn resident_set_size() -> Option<usize> {
+        // second field is number of resident pages
+        let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+        let resident_pages = statm.split_whitespace().nth(1)?.parse::<usize>().ok()?;
+        // SAFETY: sysconf has no preconditions
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        let page_size = usize::try_from(page_size).ok()?;
+        Some(resident_pages * page_size)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    pub fn resident_set_size() -> Option<usize> {
+        None
+    }
+}
@@ -1,9 +1,13 @@
+mod activation_limit;
 mod actor_activator;
+mod actor_preferences;
 mod actor_registry;
 mod actors_cache;
 pub mod errors;
 mod housekeeping;
+mod memory_pressure;
 mod virtual_actor_registration;
 
 pub use actor_activator::{ActorActivator, WeakActorActivator};
 pub use actor_registry::{ActorRegistry, WeakActorRegistry};
+pub use memory_pressure::{MemoryPressure, MemoryPressureEvent, MemoryPressurePreferences};
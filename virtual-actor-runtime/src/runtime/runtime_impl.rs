@@ -1,28 +1,58 @@
-use std::sync::Arc;
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::broadcast;
 
 use virtual_actor::{
     actor::{Actor, ActorFactory},
     local_actor::{DefaultLocalActorFactory, LocalActor, LocalActorConstructor, LocalActorFactory},
+    message::{MessageEnvelopeFactory, MessageHandler},
     virtual_actor::{
         DefaultVirtualActorFactory, VirtualActor, VirtualActorConstructor, VirtualActorFactory,
+        VirtualActorPreferences, VirtualMessage,
     },
 };
 
 use crate::{
     address::VirtualAddr,
-    errors::{ActivateActorError, WaitError},
+    cluster::Membership,
+    errors::{ActivateActorError, MembershipError, StartWatchdogError, TransportError, WaitError},
     executor::{errors::LocalExecutorError, LocalExecutor},
-    ExecutorHandle, ExecutorPreferences, GracefulShutdown, LocalAddr, RuntimeContext,
-    RuntimeContextFactory, TokioRuntimePreferences,
+    transport::{RemoteMessageRegistry, Transport},
+    ExecutorHandle, ExecutorPreferences, ExecutorThreadInfo, GracefulShutdown, LocalAddr, Member,
+    MembershipEvent, MembershipPreferences, RemoteAddr, RuntimeContext, RuntimeContextFactory,
+    TokioRuntimePreferences, TransportPreferences,
 };
 
-use super::{registry::ActorRegistry, runtime_preferences::RuntimePreferences};
+use super::{
+    offload::BlockingPools,
+    periodic_actor::PeriodicActorContextFactory,
+    registry::ActorRegistry,
+    runtime_preferences::RuntimePreferences,
+    watchdog::{CheckExecutors, MonitoredExecutors, WatchdogActorFactory, WatchdogPreferences},
+};
 
 /// Virtual actor runtime
 pub struct Runtime {
     preferences: Arc<RuntimePreferences>,
     registry: ActorRegistry,
     executors: Vec<LocalExecutor>,
+    housekeeping_executor: ExecutorHandle,
+    /// Executors observed by watchdog, housekeeping executor is not monitored
+    monitored_executors: MonitoredExecutors,
+    /// Watchdog is started by `start_watchdog`
+    watchdog_started: bool,
+    /// Pools for work offloaded by actors
+    blocking_pools: BlockingPools,
+    /// Messages accepted from remote runtimes
+    remote_messages: RemoteMessageRegistry,
+    /// Network transport, started by `start_transport`
+    transport: Option<Transport>,
+    /// Cluster membership, started by `start_membership`
+    membership: Option<Membership>,
 }
 
 impl Runtime {
@@ -47,14 +77,23 @@ impl Runtime {
                 enable_time: true,
             },
             thread_name: "housekeeping-executor".to_string(),
+            cpu_affinity: preferences.housekeeping_cpu_affinity.clone(),
             ..Default::default()
         })?;
 
-        let registry = ActorRegistry::new(housekeeping_executor.handle());
+        let registry = ActorRegistry::new(housekeeping_executor.handle(), &preferences)?;
+        let blocking_pools = BlockingPools::new(&preferences);
         Ok(Self {
             preferences: Arc::new(preferences),
             registry,
+            housekeeping_executor: housekeeping_executor.handle().clone(),
             executors: vec![housekeeping_executor],
+            monitored_executors: Arc::new(Mutex::new(Vec::new())),
+            watchdog_started: false,
+            blocking_pools,
+            remote_messages: RemoteMessageRegistry::default(),
+            transport: None,
+            membership: None,
         })
     }
 
@@ -76,16 +115,80 @@ impl Runtime {
     /// # Errors
     ///
     /// Returns error if was not able to create executor
+    ///
+    /// # Panics
+    ///
+    /// Panics if monitored executors lock is poisoned
     pub fn create_executor_with_preferences(
         &mut self,
         preferences: &ExecutorPreferences,
     ) -> Result<ExecutorHandle, LocalExecutorError> {
         let executor = LocalExecutor::new(preferences)?;
         let handle = executor.handle().clone();
+        self.monitored_executors
+            .lock()
+            .expect("Monitored executors lock poisoned")
+            .push(handle.monitor().clone());
         self.executors.push(executor);
         Ok(handle)
     }
 
+    /// Settings applied to executor threads, housekeeping executor goes first
+    #[must_use]
+    pub fn executors_info(&self) -> Vec<ExecutorThreadInfo> {
+        self.executors
+            .iter()
+            .map(|e| e.handle().thread_info().clone())
+            .collect()
+    }
+
+    /// Executor running runtime housekeeping,
+    /// background maintenance actors can be spawned on it
+    #[must_use]
+    pub fn housekeeping_executor(&self) -> &ExecutorHandle {
+        &self.housekeeping_executor
+    }
+
+    /// Starts watchdog on housekeeping executor.
+    /// Watchdog reports executors not responding to heartbeat
+    /// and message handlers exceeding budget, optionally cancelling their actors.
+    ///
+    /// Housekeeping executor itself is not monitored: garbage collection waits for shutdown
+    /// of collected actors longer than handler budget, and cancelling housekeeping actors
+    /// would stop garbage collection. Blocked housekeeping thread would also stall watchdog.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if watchdog is already started
+    /// Returns error if watchdog actor is not started
+    pub async fn start_watchdog(
+        &mut self,
+        preferences: WatchdogPreferences,
+    ) -> Result<(), StartWatchdogError> {
+        if self.watchdog_started {
+            return Err(StartWatchdogError::AlreadyStarted);
+        }
+        let executor = &self.housekeeping_executor;
+        let actor_factory = Arc::new(WatchdogActorFactory::new(
+            executor.mailbox_cancellation().child_token(),
+            &self.monitored_executors,
+            preferences,
+        ));
+        let context_factory = Arc::new(PeriodicActorContextFactory::new(
+            executor.executor_cancellation().child_token(),
+        ));
+        let watchdog = executor
+            .spawn_local_actor(
+                &actor_factory,
+                &context_factory,
+                self.preferences.actor_activation_timeout,
+            )
+            .await?;
+        watchdog.dispatch(CheckExecutors)?;
+        self.watchdog_started = true;
+        Ok(())
+    }
+
     /// Spawns local actor on executor
     ///
     /// # Errors
@@ -124,6 +227,7 @@ impl Runtime {
     {
         let context_factory = Arc::new(RuntimeContextFactory::<<AF as ActorFactory>::Actor>::new(
             self.registry.weak_ref(),
+            self.blocking_pools.clone(),
         ));
         let handle = executor
             .spawn_local_actor(
@@ -159,6 +263,31 @@ impl Runtime {
         factory: AF,
         executor: &ExecutorHandle,
     ) -> Result<(), LocalExecutorError>
+    where
+        <AF as ActorFactory>::Actor:
+            Actor<ActorContext = RuntimeContext<<AF as ActorFactory>::Actor>>,
+        AF: VirtualActorFactory,
+        <AF as ActorFactory>::Actor: VirtualActor,
+    {
+        self.register_actor_with_factory_and_preferences(
+            factory,
+            executor,
+            VirtualActorPreferences::default(),
+        )
+    }
+
+    /// Registers virtual actor with overrides of runtime preferences for this actor type.
+    /// Overrides take precedence over `VirtualActorFactory::virtual_actor_preferences`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if was not able to register actor
+    pub fn register_actor_with_factory_and_preferences<AF>(
+        &self,
+        factory: AF,
+        executor: &ExecutorHandle,
+        preferences: VirtualActorPreferences,
+    ) -> Result<(), LocalExecutorError>
     where
         <AF as ActorFactory>::Actor:
             Actor<ActorContext = RuntimeContext<<AF as ActorFactory>::Actor>>,
@@ -167,9 +296,15 @@ impl Runtime {
     {
         let context_factory = Arc::new(RuntimeContextFactory::<<AF as ActorFactory>::Actor>::new(
             self.registry.weak_ref(),
+            self.blocking_pools.clone(),
         ));
-        self.registry
-            .register_actor(factory, context_factory, executor, self.preferences.clone())
+        self.registry.register_actor(
+            factory,
+            context_factory,
+            executor,
+            &self.preferences,
+            preferences,
+        )
     }
 
     /// Spawns virtual actor on executor
@@ -188,13 +323,118 @@ impl Runtime {
     {
         self.registry.get_or_create(id)
     }
+
+    /// Starts network transport on dedicated thread,
+    /// remote runtimes can send registered messages to virtual actors of this runtime.
+    /// Returns address transport listens on.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if transport is already started
+    /// Returns error if listener can not be bound
+    pub fn start_transport(
+        &mut self,
+        preferences: &TransportPreferences,
+    ) -> Result<SocketAddr, TransportError> {
+        if self.transport.is_some() {
+            return Err(TransportError::AlreadyStarted);
+        }
+        let transport = Transport::start(
+            preferences,
+            self.remote_messages.clone(),
+            self.registry.weak_ref(),
+        )?;
+        let address = transport.local_address();
+        self.transport = Some(transport);
+        Ok(address)
+    }
+
+    /// Address transport listens on, `None` if transport is not started
+    #[must_use]
+    pub fn transport_address(&self) -> Option<SocketAddr> {
+        self.transport.as_ref().map(Transport::local_address)
+    }
+
+    /// Allows remote runtimes to send message `M` to virtual actor `A` of this runtime
+    pub fn register_remote_message<A, M>(&self)
+    where
+        A: VirtualActor + MessageHandler<M>,
+        A::MessagesEnvelope: MessageEnvelopeFactory<A, M>,
+        M: VirtualMessage,
+        M::Result: Serialize + DeserializeOwned,
+    {
+        self.remote_messages.register::<A, M>();
+    }
+
+    /// Address of virtual actor owned by runtime with transport listening on `peer`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if transport is not started
+    pub fn remote_addr<A: VirtualActor>(
+        &self,
+        peer: SocketAddr,
+        id: &A::ActorId,
+    ) -> Result<RemoteAddr<A>, TransportError> {
+        let transport = self.transport.as_ref().ok_or(TransportError::NotStarted)?;
+        Ok(RemoteAddr::new(id, peer, transport.handle()))
+    }
+
+    /// Joins cluster through seed nodes and starts failure detection of members.
+    /// Membership changes are published to actor registry,
+    /// node leaves cluster on graceful shutdown.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if membership is already started
+    /// Returns error if transport is not started
+    pub fn start_membership(
+        &mut self,
+        preferences: MembershipPreferences,
+    ) -> Result<(), MembershipError> {
+        if self.membership.is_some() {
+            return Err(MembershipError::AlreadyStarted);
+        }
+        let transport = self
+            .transport
+            .as_ref()
+            .ok_or(MembershipError::TransportNotStarted)?;
+        self.membership = Some(Membership::start(
+            transport.local_address(),
+            preferences,
+            transport.handle(),
+            &self.remote_messages,
+            self.registry.weak_ref(),
+        ));
+        Ok(())
+    }
+
+    /// Cluster members known by this runtime, including itself
+    #[must_use]
+    pub fn cluster_members(&self) -> Vec<Member> {
+        self.registry.members()
+    }
+
+    /// Receiver of membership changes published after subscription
+    #[must_use]
+    pub fn subscribe_membership_events(&self) -> broadcast::Receiver<MembershipEvent> {
+        self.registry.subscribe_membership_events()
+    }
 }
 
 impl GracefulShutdown for Runtime {
     async fn graceful_shutdown(mut self, timeout: std::time::Duration) -> Result<(), WaitError> {
+        if let Some(membership) = self.membership.take() {
+            membership.leave().await;
+        }
+        // remote runtimes can not reach actors anymore
+        if let Some(transport) = self.transport.take() {
+            transport.graceful_shutdown(timeout).await?;
+        }
         for executor in self.executors.drain(..) {
             executor.graceful_shutdown(timeout).await?;
         }
+        self.blocking_pools.close();
 
         println!("Runtime is stopped");
         Ok(())
@@ -1,5 +1,9 @@
 use std::time::Duration;
 
+use crate::RetryPolicy;
+
+use super::registry::MemoryPressurePreferences;
+
 /// Runtime settings
 pub struct RuntimePreferences {
     /// Interval for actors garbage collection
@@ -10,6 +14,22 @@ pub struct RuntimePreferences {
     pub actor_activation_timeout: Duration,
     /// Timeout for actor to wait for shutdown before during graceful shutdown
     pub actor_shutdown_interval: Duration,
+    /// Maximal number of threads for blocking work offloaded by actors
+    pub blocking_pool_size: usize,
+    /// Maximal number of threads for CPU-heavy work offloaded by actors
+    pub compute_pool_size: usize,
+    /// Cores housekeeping executor thread is pinned to, Linux only.
+    /// Allows to isolate housekeeping from latency-sensitive executors.
+    pub housekeeping_cpu_affinity: Option<Vec<usize>>,
+    /// Maximal number of active virtual actors of all types, unlimited if `None`.
+    /// Least recently used actors are deactivated to make room for new activations.
+    pub max_active_actors: Option<usize>,
+    /// Maximal number of active virtual actors of each type, unlimited if `None`
+    pub max_active_actors_per_type: Option<usize>,
+    /// Deactivation of idle actors when memory thresholds are crossed, disabled if `None`
+    pub memory_pressure: Option<MemoryPressurePreferences>,
+    /// Redelivery of messages to virtual actors racing with deactivation
+    pub retry_policy: RetryPolicy,
 }
 
 impl Default for RuntimePreferences {
@@ -19,6 +39,13 @@ impl Default for RuntimePreferences {
             actor_idle_timeout: Duration::from_secs(1),
             actor_activation_timeout: Duration::from_secs(1),
             actor_shutdown_interval: Duration::from_secs(1),
+            blocking_pool_size: 64,
+            compute_pool_size: std::thread::available_parallelism().map_or(1, usize::from),
+            housekeeping_cpu_affinity: None,
+            max_active_actors: None,
+            max_active_actors_per_type: None,
+            memory_pressure: None,
+            retry_policy: RetryPolicy::default(),
         }
     }
 }
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+use virtual_actor::{actor::Actor, local_actor::LocalActor, message::MessageHandler};
+
+use crate::runtime::periodic_actor::PeriodicActorContext;
+
+use super::{envelope::InnerMessageEnvelope, MonitoredExecutors, WatchdogPreferences};
+
+/// Watchdog checking responsiveness of executors and duration of message handlers
+pub struct WatchdogActor {
+    pub(super) graceful_cancellation: CancellationToken,
+    pub(super) executors: MonitoredExecutors,
+    pub(super) preferences: Arc<WatchdogPreferences>,
+}
+
+impl Actor for WatchdogActor {
+    type ActorContext = PeriodicActorContext<Self>;
+
+    type MessagesEnvelope = InnerMessageEnvelope;
+
+    fn name() -> virtual_actor::actor::ActorName {
+        stringify!(WatchdogActor)
+    }
+
+    async fn handle_envelope(
+        &mut self,
+        envelope: Self::MessagesEnvelope,
+        ctx: &Self::ActorContext,
+    ) -> Result<(), virtual_actor::errors::ResponderError> {
+        match envelope {
+            InnerMessageEnvelope::CheckExecutors(msg) => {
+                self.handle(msg, ctx).await;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl LocalActor for WatchdogActor {}
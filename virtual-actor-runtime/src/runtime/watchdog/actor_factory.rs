@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+use virtual_actor::{actor::ActorFactory, local_actor::LocalActorFactory};
+
+use super::{MonitoredExecutors, WatchdogActor, WatchdogPreferences};
+
+pub struct WatchdogActorFactory {
+    graceful_cancellation: CancellationToken,
+    executors: MonitoredExecutors,
+    preferences: Arc<WatchdogPreferences>,
+}
+
+impl WatchdogActorFactory {
+    pub fn new(
+        graceful_cancellation: CancellationToken,
+        executors: &MonitoredExecutors,
+        preferences: WatchdogPreferences,
+    ) -> Self {
+        Self {
+            graceful_cancellation,
+            executors: executors.clone(),
+            preferences: Arc::new(preferences),
+        }
+    }
+}
+
+impl ActorFactory for WatchdogActorFactory {
+    type Actor = WatchdogActor;
+}
+
+impl LocalActorFactory for WatchdogActorFactory {
+    type Error = std::convert::Infallible;
+
+    async fn create_actor(&self) -> Result<WatchdogActor, Self::Error> {
+        Ok(WatchdogActor {
+            graceful_cancellation: self.graceful_cancellation.clone(),
+            executors: self.executors.clone(),
+            preferences: self.preferences.clone(),
+        })
+    }
+}
@@ -0,0 +1,55 @@
+use virtual_actor::message::{Message, MessageHandler};
+
+use super::{WatchdogActor, WatchdogReport};
+
+#[derive(Debug)]
+pub struct CheckExecutors;
+
+impl Message for CheckExecutors {
+    type Result = ();
+}
+
+impl MessageHandler<CheckExecutors> for WatchdogActor {
+    async fn handle(
+        &mut self,
+        _msg: CheckExecutors,
+        ctx: &Self::ActorContext,
+    ) -> <CheckExecutors as Message>::Result {
+        let preferences = &self.preferences;
+        let executors = self
+            .executors
+            .lock()
+            .expect("Monitored executors lock poisoned")
+            .clone();
+
+        for executor in executors {
+            if let Some(blocked_for) = executor.check_heartbeat(preferences.heartbeat_budget) {
+                (preferences.reporter)(&WatchdogReport::ExecutorBlocked {
+                    executor_name: executor.name().to_owned(),
+                    blocked_for,
+                });
+            }
+
+            for handler in executor.stuck_handlers(preferences.handler_budget) {
+                (preferences.reporter)(&WatchdogReport::HandlerBudgetExceeded {
+                    executor_name: executor.name().to_owned(),
+                    actor_name: handler.actor.actor_name(),
+                    actor_id: handler.actor.actor_id().to_owned(),
+                    message_name: handler.message_name,
+                    running_for: handler.running_for,
+                    cancelled: preferences.cancel_stuck_actors,
+                });
+                if preferences.cancel_stuck_actors {
+                    handler.execution_cancellation.cancel();
+                }
+            }
+        }
+
+        // schedule next check
+        ctx.schedule(
+            CheckExecutors,
+            preferences.check_interval,
+            &self.graceful_cancellation,
+        );
+    }
+}
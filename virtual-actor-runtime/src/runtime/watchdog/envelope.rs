@@ -0,0 +1,25 @@
+use virtual_actor::message::{MessageEnvelope, MessageEnvelopeFactory, MessageName, Responder};
+
+use super::{check_executors::CheckExecutors, WatchdogActor};
+
+#[derive(Debug)]
+pub enum InnerMessageEnvelope {
+    CheckExecutors(CheckExecutors),
+}
+
+impl MessageEnvelopeFactory<WatchdogActor, CheckExecutors> for InnerMessageEnvelope {
+    fn from_message<R: Responder<CheckExecutors> + Sized + 'static>(
+        msg: CheckExecutors,
+        _responder: Option<R>,
+    ) -> Self {
+        Self::CheckExecutors(msg)
+    }
+}
+
+impl MessageEnvelope<WatchdogActor> for InnerMessageEnvelope {
+    fn message_name(&self) -> MessageName {
+        match self {
+            Self::CheckExecutors(_) => stringify!(CheckExecutors),
+        }
+    }
+}
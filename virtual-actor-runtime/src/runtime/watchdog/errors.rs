@@ -0,0 +1,15 @@
+use crate::{address::errors::LocalAddrError, executor::errors::LocalExecutorError};
+
+/// Watchdog start error
+#[derive(Debug, thiserror::Error)]
+pub enum StartWatchdogError {
+    /// Watchdog is started once per runtime
+    #[error("Watchdog is already started")]
+    AlreadyStarted,
+    /// Watchdog actor spawn error
+    #[error("Watchdog spawn error {0:?}")]
+    Spawn(#[from] LocalExecutorError),
+    /// Send message error
+    #[error("StartCheckExecutorsError {0:?}")]
+    StartCheckExecutors(#[from] LocalAddrError),
+}
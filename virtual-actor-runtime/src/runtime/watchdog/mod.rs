@@ -0,0 +1,20 @@
+mod actor;
+mod actor_factory;
+mod check_executors;
+mod envelope;
+pub mod errors;
+mod watchdog_preferences;
+mod watchdog_report;
+
+use std::sync::{Arc, Mutex};
+
+use crate::executor::ExecutorMonitor;
+
+pub use actor::WatchdogActor;
+pub use actor_factory::WatchdogActorFactory;
+pub use check_executors::CheckExecutors;
+pub use watchdog_preferences::WatchdogPreferences;
+pub use watchdog_report::WatchdogReport;
+
+/// Monitors of executors observed by watchdog, shared with runtime
+pub type MonitoredExecutors = Arc<Mutex<Vec<ExecutorMonitor>>>;
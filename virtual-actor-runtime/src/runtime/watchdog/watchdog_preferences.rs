@@ -0,0 +1,32 @@
+use std::{sync::Arc, time::Duration};
+
+use super::WatchdogReport;
+
+/// Callback receiving watchdog reports
+pub type WatchdogReporter = Arc<dyn Fn(&WatchdogReport) + Send + Sync>;
+
+/// Watchdog settings
+pub struct WatchdogPreferences {
+    /// Interval between checks of executors
+    pub check_interval: Duration,
+    /// Time message handler may run before it is reported as stuck
+    pub handler_budget: Duration,
+    /// Time executor thread may not respond to heartbeat before it is reported as blocked
+    pub heartbeat_budget: Duration,
+    /// Cancel execution of actor which handler exceeded budget
+    pub cancel_stuck_actors: bool,
+    /// Receives watchdog reports, prints them to stderr by default
+    pub reporter: WatchdogReporter,
+}
+
+impl Default for WatchdogPreferences {
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::from_millis(100),
+            handler_budget: Duration::from_secs(1),
+            heartbeat_budget: Duration::from_secs(1),
+            cancel_stuck_actors: false,
+            reporter: Arc::new(|report| eprintln!("{report}")),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::{fmt, time::Duration};
+
+use virtual_actor::{actor::ActorName, message::MessageName};
+
+/// Problem found by watchdog
+#[derive(Debug, Clone)]
+pub enum WatchdogReport {
+    /// Executor thread doesn't respond to heartbeat
+    ExecutorBlocked {
+        /// Executor thread name
+        executor_name: String,
+        /// Time executor thread doesn't respond
+        blocked_for: Duration,
+    },
+    /// Message handler exceeded budget
+    HandlerBudgetExceeded {
+        /// Executor thread name
+        executor_name: String,
+        /// Name of the actor
+        actor_name: ActorName,
+        /// Id of the actor, instance number for local actors
+        actor_id: String,
+        /// Name of message being processed
+        message_name: MessageName,
+        /// Time since handler started
+        running_for: Duration,
+        /// Actor execution was cancelled
+        cancelled: bool,
+    },
+}
+
+impl fmt::Display for WatchdogReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ExecutorBlocked {
+                executor_name,
+                blocked_for,
+            } => write!(f, "Executor {executor_name} is blocked for {blocked_for:?}"),
+            Self::HandlerBudgetExceeded {
+                executor_name,
+                actor_name,
+                actor_id,
+                message_name,
+                running_for,
+                cancelled,
+            } => {
+                write!(
+                    f,
+                    "Actor {actor_name}::{actor_id} handles {message_name} for {running_for:?} on {executor_name}"
+                )?;
+                if *cancelled {
+                    write!(f, ", actor execution is cancelled")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
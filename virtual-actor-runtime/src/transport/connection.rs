@@ -0,0 +1,171 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use tokio::{
+    io::BufReader,
+    net::{tcp::OwnedReadHalf, TcpStream},
+    sync::{mpsc, oneshot},
+};
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    errors::{RemoteAddrError, RemoteError},
+    frame::{write_frames, Frame, FrameCodec, Request},
+};
+
+/// Sender of response to pending request
+type PendingResponse = oneshot::Sender<Result<Vec<u8>, RemoteError>>;
+
+/// Outgoing connection to remote runtime, requests are multiplexed by correlation id
+#[derive(Clone)]
+pub struct Connection {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    codec: FrameCodec,
+    /// Encoded frames written to connection
+    frames: mpsc::Sender<Vec<u8>>,
+    /// Requests waiting for response
+    pending: Mutex<HashMap<u64, PendingResponse>>,
+    next_correlation_id: AtomicU64,
+    /// Cancelled once connection is closed
+    closed: CancellationToken,
+}
+
+impl Connection {
+    /// Connects to remote runtime, has to be called on transport runtime.
+    /// At most `max_queued_frames` frames wait to be written, senders wait for free slot.
+    pub async fn connect(
+        peer: SocketAddr,
+        codec: FrameCodec,
+        max_queued_frames: usize,
+    ) -> Result<Self, RemoteAddrError> {
+        let stream = TcpStream::connect(peer)
+            .await
+            .map_err(RemoteAddrError::Connect)?;
+        stream.set_nodelay(true).map_err(RemoteAddrError::Connect)?;
+        let (reader, writer) = stream.into_split();
+        let (frames, frames_rx) = mpsc::channel(max_queued_frames);
+        let connection = Self {
+            inner: Arc::new(Inner {
+                codec,
+                frames,
+                pending: Mutex::new(HashMap::new()),
+                next_correlation_id: AtomicU64::new(0),
+                closed: CancellationToken::new(),
+            }),
+        };
+        let writing = connection.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                () = write_frames(writer, frames_rx) => {},
+                () = writing.inner.closed.cancelled() => {},
+            }
+            // connection is not reused once frames can not be written
+            writing.close();
+        });
+        tokio::spawn(connection.clone().read_responses(reader));
+        Ok(connection)
+    }
+
+    /// Connection is closed and can not be used anymore
+    pub fn is_closed(&self) -> bool {
+        self.inner.closed.is_cancelled()
+    }
+
+    /// Sends request and waits for serialized result
+    pub async fn request(&self, mut request: Request) -> Result<Vec<u8>, RemoteAddrError> {
+        let correlation_id = self
+            .inner
+            .next_correlation_id
+            .fetch_add(1, Ordering::Relaxed);
+        request.correlation_id = Some(correlation_id);
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut pending = self.pending();
+            // checked under lock, pending requests are dropped once connection is closed
+            if self.is_closed() {
+                return Err(RemoteAddrError::ConnectionClosed);
+            }
+            pending.insert(correlation_id, tx);
+        }
+        if let Err(e) = self.send(&Frame::Request(request)).await {
+            self.pending().remove(&correlation_id);
+            return Err(e);
+        }
+        Ok(rx.await.map_err(|_| RemoteAddrError::ConnectionClosed)??)
+    }
+
+    /// Sends request without waiting for response
+    pub async fn dispatch(&self, mut request: Request) -> Result<(), RemoteAddrError> {
+        request.correlation_id = None;
+        self.send(&Frame::Request(request)).await
+    }
+
+    /// Queues frame for writing, waits while connection has too many queued frames
+    async fn send(&self, frame: &Frame) -> Result<(), RemoteAddrError> {
+        let frame = self
+            .inner
+            .codec
+            .encode(frame)
+            .map_err(RemoteAddrError::Frame)?;
+        self.inner
+            .frames
+            .send(frame)
+            .await
+            .map_err(|_| RemoteAddrError::ConnectionClosed)
+    }
+
+    fn pending(&self) -> std::sync::MutexGuard<'_, HashMap<u64, PendingResponse>> {
+        self.inner
+            .pending
+            .lock()
+            .expect("Pending requests lock poisoned")
+    }
+
+    /// Completes pending requests with received responses until connection is closed
+    async fn read_responses(self, reader: OwnedReadHalf) {
+        let mut reader = BufReader::new(reader);
+        loop {
+            let frame = tokio::select! {
+                frame = self.inner.codec.read(&mut reader) => frame,
+                () = self.inner.closed.cancelled() => break,
+            };
+            match frame {
+                Ok(Some(Frame::Response {
+                    correlation_id,
+                    result,
+                })) => {
+                    if let Some(tx) = self.pending().remove(&correlation_id) {
+                        // requester may be gone after timeout
+                        let _ = tx.send(result);
+                    }
+                }
+                Ok(Some(Frame::Request(_))) => {
+                    eprintln!("Transport connection received unexpected request");
+                    break;
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    eprintln!("Transport connection read error: {e}");
+                    break;
+                }
+            }
+        }
+        self.close();
+    }
+
+    /// Marks connection closed and drops pending requests
+    fn close(&self) {
+        let mut pending = self.pending();
+        self.inner.closed.cancel();
+        pending.clear();
+    }
+}
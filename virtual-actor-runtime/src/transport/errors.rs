@@ -0,0 +1,87 @@
+use std::{io, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// Error of processing message received from remote runtime, returned to sender
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, Serialize, Deserialize)]
+pub enum RemoteError {
+    /// Message is not registered in remote message registry of receiving runtime
+    #[error("Message {message_name} of actor {actor_name} is not registered")]
+    UnknownMessage {
+        /// Name of actor
+        actor_name: String,
+        /// Name of message
+        message_name: String,
+    },
+    /// Actor id or message can not be deserialized
+    #[error("Deserialize error: {0}")]
+    Deserialize(String),
+    /// Message result can not be serialized
+    #[error("Serialize error: {0}")]
+    Serialize(String),
+    /// Message is not delivered to local actor
+    #[error("Delivery error: {0}")]
+    Delivery(String),
+}
+
+/// Frame encoding or decoding error
+#[derive(Debug, thiserror::Error)]
+pub enum FrameError {
+    /// Connection error
+    #[error("Frame IO error: {0:?}")]
+    Io(#[from] io::Error),
+    /// Frame exceeds `TransportPreferences::max_frame_length`
+    #[error("Frame length {length} exceeds {max_length}")]
+    TooLong {
+        /// Length of frame
+        length: usize,
+        /// Maximal length of frame
+        max_length: usize,
+    },
+    /// Frame serialization error
+    #[error("Frame codec error: {0:?}")]
+    Codec(#[from] bincode::Error),
+}
+
+/// Transport error
+#[derive(Debug, thiserror::Error)]
+pub enum TransportError {
+    /// Transport is started once per runtime
+    #[error("Transport is already started")]
+    AlreadyStarted,
+    /// `Runtime::start_transport` is not called
+    #[error("Transport is not started")]
+    NotStarted,
+    /// Listener bind or transport thread start error
+    #[error("Transport IO error: {0:?}")]
+    Io(#[from] io::Error),
+}
+
+/// Error of message sent through `RemoteAddr`
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteAddrError {
+    /// Connection to remote runtime is not established
+    #[error("Connect error: {0:?}")]
+    Connect(io::Error),
+    /// Actor id or message can not be serialized
+    #[error("Encode error: {0:?}")]
+    Encode(bincode::Error),
+    /// Request frame can not be encoded
+    #[error("Frame error: {0:?}")]
+    Frame(FrameError),
+    /// Message result can not be deserialized
+    #[error("Decode error: {0:?}")]
+    Decode(bincode::Error),
+    /// Connection is closed before response is received
+    #[error("Connection closed")]
+    ConnectionClosed,
+    /// Response is not received in time
+    #[error("Response timeout {0:?}")]
+    Timeout(Duration),
+    /// Remote runtime failed to process message
+    #[error("Remote error: {0}")]
+    Remote(#[from] RemoteError),
+    /// Transport of local runtime is stopped
+    #[error("Transport stopped")]
+    TransportStopped,
+}
@@ -0,0 +1,102 @@
+//! Framed codec of transport connections
+
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    sync::mpsc,
+};
+
+use super::errors::{FrameError, RemoteError};
+
+/// Unit of transport protocol
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Frame {
+    /// Message sent to virtual actor
+    Request(Request),
+    /// Result of message processing
+    Response {
+        /// Correlation id of request
+        correlation_id: u64,
+        /// Serialized result of message
+        result: Result<Vec<u8>, RemoteError>,
+    },
+}
+
+/// Message sent to virtual actor of remote runtime
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Request {
+    /// Matches response with request, `None` if response is not expected
+    pub correlation_id: Option<u64>,
+    /// Name of actor
+    pub actor_name: String,
+    /// Name of message
+    pub message_name: String,
+    /// Serialized id of actor
+    pub actor_id: Vec<u8>,
+    /// Serialized message
+    pub payload: Vec<u8>,
+}
+
+/// Frames prefixed with big endian `u32` length, serialized with bincode
+#[derive(Debug, Clone, Copy)]
+pub struct FrameCodec {
+    max_frame_length: usize,
+}
+
+impl FrameCodec {
+    pub fn new(max_frame_length: usize) -> Self {
+        Self {
+            max_frame_length: max_frame_length.min(u32::MAX as usize),
+        }
+    }
+
+    /// Reads next frame, `None` if connection is closed by peer
+    pub async fn read<R: AsyncRead + Unpin>(
+        self,
+        reader: &mut R,
+    ) -> Result<Option<Frame>, FrameError> {
+        let length = match reader.read_u32().await {
+            Ok(length) => length as usize,
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        self.check_length(length)?;
+        let mut buf = vec![0; length];
+        reader.read_exact(&mut buf).await?;
+        Ok(Some(bincode::deserialize(&buf)?))
+    }
+
+    /// Encodes frame with length prefix
+    pub fn encode(self, frame: &Frame) -> Result<Vec<u8>, FrameError> {
+        let body = bincode::serialize(frame)?;
+        self.check_length(body.len())?;
+        let mut buf = Vec::with_capacity(body.len() + 4);
+        #[allow(clippy::cast_possible_truncation)] // checked against max frame length
+        buf.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&body);
+        Ok(buf)
+    }
+
+    fn check_length(self, length: usize) -> Result<(), FrameError> {
+        if length > self.max_frame_length {
+            return Err(FrameError::TooLong {
+                length,
+                max_length: self.max_frame_length,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Writes encoded frames received from channel until channel or connection is closed
+pub async fn write_frames<W: AsyncWrite + Unpin>(
+    mut writer: W,
+    mut frames: mpsc::Receiver<Vec<u8>>,
+) {
+    while let Some(frame) = frames.recv().await {
+        if let Err(e) = writer.write_all(&frame).await {
+            eprintln!("Transport connection write error: {e}");
+            return;
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use futures::future::{self, BoxFuture};
+use serde::{de::DeserializeOwned, Serialize};
+use virtual_actor::{
+    actor::{ActorAddr, ActorName},
+    message::{MessageEnvelopeFactory, MessageHandler, MessageName},
+    virtual_actor::{VirtualActor, VirtualMessage},
+};
+
+use crate::runtime::WeakActorRegistry;
+
+use super::{errors::RemoteError, frame::Request};
+
+/// Delivers serialized message to local virtual actor with serialized id,
+/// returns serialized result, empty if `reply` is not requested
+pub(crate) type RemoteHandler = Arc<
+    dyn Fn(
+            &WeakActorRegistry,
+            &[u8],
+            &[u8],
+            bool,
+        ) -> BoxFuture<'static, Result<Vec<u8>, RemoteError>>
+        + Send
+        + Sync,
+>;
+
+/// Messages which can be received from remote runtimes,
+/// maps pair of actor and message names to deserializer of message
+#[derive(Clone, Default)]
+pub struct RemoteMessageRegistry {
+    handlers: Arc<DashMap<(String, String), RemoteHandler>>,
+}
+
+impl RemoteMessageRegistry {
+    /// Registers message `M` of virtual actor `A`
+    pub fn register<A, M>(&self)
+    where
+        A: VirtualActor + MessageHandler<M>,
+        A::MessagesEnvelope: MessageEnvelopeFactory<A, M>,
+        M: VirtualMessage,
+        M::Result: Serialize + DeserializeOwned,
+    {
+        let handler: RemoteHandler = Arc::new(|registry, actor_id, payload, reply| {
+            let delivery = decode::<A::ActorId>(actor_id).and_then(|id| {
+                let addr = registry
+                    .get_or_create::<A>(&id)
+                    .map_err(|e| RemoteError::Delivery(e.to_string()))?;
+                Ok((addr, decode::<M>(payload)?))
+            });
+            let (addr, msg) = match delivery {
+                Ok(delivery) => delivery,
+                Err(e) => return Box::pin(future::ready(Err(e))),
+            };
+            Box::pin(async move {
+                if !reply {
+                    addr.dispatch(msg)
+                        .await
+                        .map_err(|e| RemoteError::Delivery(e.to_string()))?;
+                    return Ok(Vec::new());
+                }
+                let result = addr
+                    .send(msg)
+                    .await
+                    .map_err(|e| RemoteError::Delivery(e.to_string()))?;
+                bincode::serialize(&result).map_err(|e| RemoteError::Serialize(e.to_string()))
+            })
+        });
+        self.register_handler(A::name(), M::name(), handler);
+    }
+
+    /// Registers handler of message addressed to runtime service instead of virtual actor
+    pub(crate) fn register_handler(
+        &self,
+        actor_name: ActorName,
+        message_name: MessageName,
+        handler: RemoteHandler,
+    ) {
+        self.handlers.insert(key(actor_name, message_name), handler);
+    }
+
+    /// Delivers request to local virtual actor
+    pub(crate) fn handle(
+        &self,
+        registry: &WeakActorRegistry,
+        request: Request,
+    ) -> BoxFuture<'static, Result<Vec<u8>, RemoteError>> {
+        let Request {
+            correlation_id,
+            actor_name,
+            message_name,
+            actor_id,
+            payload,
+        } = request;
+        let key = (actor_name, message_name);
+        if let Some(handler) = self.handlers.get(&key) {
+            return handler(registry, &actor_id, &payload, correlation_id.is_some());
+        }
+        let (actor_name, message_name) = key;
+        Box::pin(future::ready(Err(RemoteError::UnknownMessage {
+            actor_name,
+            message_name,
+        })))
+    }
+}
+
+fn key(actor_name: ActorName, message_name: MessageName) -> (String, String) {
+    (actor_name.to_owned(), message_name.to_owned())
+}
+
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, RemoteError> {
+    bincode::deserialize(bytes).map_err(|e| RemoteError::Deserialize(e.to_string()))
+}
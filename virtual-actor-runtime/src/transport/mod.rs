@@ -0,0 +1,18 @@
+//! Network transport delivering virtual messages between runtimes
+
+mod connection;
+pub mod errors;
+mod frame;
+mod message_registry;
+mod remote_addr;
+mod server;
+mod transport_impl;
+mod transport_preferences;
+
+pub(crate) use frame::Request;
+pub(crate) use message_registry::RemoteHandler;
+pub use message_registry::RemoteMessageRegistry;
+pub use remote_addr::RemoteAddr;
+pub use transport_impl::Transport;
+pub(crate) use transport_impl::TransportHandle;
+pub use transport_preferences::TransportPreferences;
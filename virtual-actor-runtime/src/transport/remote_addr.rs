@@ -0,0 +1,96 @@
+use std::net::SocketAddr;
+
+use serde::{de::DeserializeOwned, Serialize};
+use virtual_actor::{
+    message::MessageHandler,
+    virtual_actor::{VirtualActor, VirtualMessage},
+};
+
+use super::{errors::RemoteAddrError, frame::Request, transport_impl::TransportHandle};
+
+/// Address of virtual actor owned by remote runtime.
+/// Message is serialized and sent to remote runtime, which delivers it to local actor
+/// and replies with serialized result.
+/// Message has to be registered by `Runtime::register_remote_message` of remote runtime.
+///
+/// `RemoteAddr` is separate from `VirtualAddr`, because runtime does not place actors:
+/// caller chooses runtime owning actor, and delivery fails with network errors
+/// instead of activation errors. `VirtualAddr` always activates actor locally.
+pub struct RemoteAddr<A: VirtualActor> {
+    id: A::ActorId,
+    peer: SocketAddr,
+    transport: TransportHandle,
+}
+
+impl<A: VirtualActor> RemoteAddr<A> {
+    pub(crate) fn new(id: &A::ActorId, peer: SocketAddr, transport: &TransportHandle) -> Self {
+        Self {
+            id: id.clone(),
+            peer,
+            transport: transport.clone(),
+        }
+    }
+
+    /// Id of addressed actor
+    pub fn id(&self) -> &A::ActorId {
+        &self.id
+    }
+
+    /// Transport address of runtime owning actor
+    #[must_use]
+    pub fn peer(&self) -> SocketAddr {
+        self.peer
+    }
+
+    /// Sends message and waits for result
+    ///
+    /// # Errors
+    ///
+    /// Returns error if message is not delivered or result is not received
+    pub async fn send<M>(&self, msg: M) -> Result<M::Result, RemoteAddrError>
+    where
+        A: MessageHandler<M>,
+        M: VirtualMessage,
+        M::Result: Serialize + DeserializeOwned,
+    {
+        let request = self.request(&msg)?;
+        let result = self.transport.request(self.peer, request).await?;
+        bincode::deserialize(&result).map_err(RemoteAddrError::Decode)
+    }
+
+    /// Sends message without waiting for result.
+    /// Message is queued for writing to connection, its delivery is not confirmed.
+    /// Waits while connection has `TransportPreferences::max_queued_frames` queued frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if connection to remote runtime is not established
+    pub async fn dispatch<M>(&self, msg: M) -> Result<(), RemoteAddrError>
+    where
+        A: MessageHandler<M>,
+        M: VirtualMessage,
+        M::Result: Serialize + DeserializeOwned,
+    {
+        let request = self.request(&msg)?;
+        self.transport.dispatch(self.peer, request).await
+    }
+
+    fn request<M: VirtualMessage>(&self, msg: &M) -> Result<Request, RemoteAddrError>
+    where
+        M::Result: Serialize + DeserializeOwned,
+    {
+        Ok(Request {
+            correlation_id: None,
+            actor_name: A::name().to_owned(),
+            message_name: M::name().to_owned(),
+            actor_id: bincode::serialize(&self.id).map_err(RemoteAddrError::Encode)?,
+            payload: bincode::serialize(msg).map_err(RemoteAddrError::Encode)?,
+        })
+    }
+}
+
+impl<A: VirtualActor> Clone for RemoteAddr<A> {
+    fn clone(&self) -> Self {
+        Self::new(&self.id, self.peer, &self.transport)
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use tokio::{
+    io::BufReader,
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, Semaphore},
+};
+
+use crate::runtime::WeakActorRegistry;
+
+use super::{
+    errors::RemoteError,
+    frame::{write_frames, Frame, FrameCodec},
+    RemoteMessageRegistry, TransportPreferences,
+};
+
+/// Limits of connection accepted from remote runtime
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionLimits {
+    /// Maximal number of responses waiting to be written
+    pub max_queued_frames: usize,
+    /// Maximal number of requests handled at once
+    pub max_concurrent_requests: usize,
+}
+
+impl From<&TransportPreferences> for ConnectionLimits {
+    fn from(preferences: &TransportPreferences) -> Self {
+        Self {
+            max_queued_frames: preferences.max_queued_frames.max(1),
+            max_concurrent_requests: preferences.max_concurrent_requests.max(1),
+        }
+    }
+}
+
+/// Accepts connections of remote runtimes
+pub async fn accept_connections(
+    listener: TcpListener,
+    codec: FrameCodec,
+    limits: ConnectionLimits,
+    messages: RemoteMessageRegistry,
+    registry: WeakActorRegistry,
+) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(serve_connection(
+                    stream,
+                    codec,
+                    limits,
+                    messages.clone(),
+                    registry.clone(),
+                ));
+            }
+            Err(e) => eprintln!("Transport accept error: {e}"),
+        }
+    }
+}
+
+/// Delivers requests received through connection to local actors,
+/// requests are processed concurrently and responses are written once ready.
+/// Connection is not read while `max_concurrent_requests` requests are handled,
+/// so remote runtime is slowed down by TCP flow control.
+async fn serve_connection(
+    stream: TcpStream,
+    codec: FrameCodec,
+    limits: ConnectionLimits,
+    messages: RemoteMessageRegistry,
+    registry: WeakActorRegistry,
+) {
+    if let Err(e) = stream.set_nodelay(true) {
+        eprintln!("Transport connection error: {e}");
+    }
+    let (reader, writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let (frames, frames_rx) = mpsc::channel(limits.max_queued_frames);
+    tokio::spawn(write_frames(writer, frames_rx));
+    let in_flight = Arc::new(Semaphore::new(limits.max_concurrent_requests));
+
+    loop {
+        let request = match codec.read(&mut reader).await {
+            Ok(Some(Frame::Request(request))) => request,
+            Ok(Some(Frame::Response { .. })) => {
+                eprintln!("Transport connection received unexpected response");
+                return;
+            }
+            Ok(None) => return,
+            Err(e) => {
+                eprintln!("Transport connection read error: {e}");
+                return;
+            }
+        };
+        let Ok(permit) = in_flight.clone().acquire_owned().await else {
+            return;
+        };
+        let correlation_id = request.correlation_id;
+        let delivery = messages.handle(&registry, request);
+        let frames = frames.clone();
+        tokio::spawn(async move {
+            let result = delivery.await;
+            let Some(correlation_id) = correlation_id else {
+                if let Err(e) = result {
+                    eprintln!("Transport dispatch error: {e}");
+                }
+                return;
+            };
+            let frame = codec
+                .encode(&Frame::Response {
+                    correlation_id,
+                    result,
+                })
+                .or_else(|e| {
+                    codec.encode(&Frame::Response {
+                        correlation_id,
+                        result: Err(RemoteError::Serialize(e.to_string())),
+                    })
+                });
+            match frame {
+                Ok(frame) => {
+                    // connection is closed if send fails
+                    let _ = frames.send(frame).await;
+                }
+                Err(e) => eprintln!("Transport response encode error: {e}"),
+            }
+            // request is handled once its response is queued
+            drop(permit);
+        });
+    }
+}
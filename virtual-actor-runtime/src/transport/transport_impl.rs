@@ -0,0 +1,192 @@
+use std::{future::Future, net::SocketAddr, sync::Arc, thread::JoinHandle, time::Duration};
+
+use dashmap::DashMap;
+use tokio::{
+    net::TcpListener,
+    sync::{Mutex, Notify},
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    runtime::WeakActorRegistry,
+    utils::{
+        waiter::{waiter, WaitError},
+        GracefulShutdown,
+    },
+};
+
+use super::{
+    connection::Connection,
+    errors::{RemoteAddrError, TransportError},
+    frame::{FrameCodec, Request},
+    server::{accept_connections, ConnectionLimits},
+    RemoteMessageRegistry, TransportPreferences,
+};
+
+/// Network transport of runtime.
+/// Listener and connections are served by dedicated thread.
+pub struct Transport {
+    handle: TransportHandle,
+    local_address: SocketAddr,
+    cancellation: CancellationToken,
+    thread_handle: JoinHandle<()>,
+    thread_stopped: Arc<Notify>,
+}
+
+/// Clonable handle sending requests through transport
+#[derive(Clone)]
+pub struct TransportHandle {
+    runtime: tokio::runtime::Handle,
+    codec: FrameCodec,
+    request_timeout: Duration,
+    max_queued_frames: usize,
+    /// Outgoing connections by address of remote runtime,
+    /// connection is locked while connecting, so concurrent requests share it
+    connections: Arc<DashMap<SocketAddr, Arc<Mutex<Option<Connection>>>>>,
+}
+
+impl Transport {
+    /// Binds listener and starts transport thread
+    pub fn start(
+        preferences: &TransportPreferences,
+        messages: RemoteMessageRegistry,
+        registry: WeakActorRegistry,
+    ) -> Result<Self, TransportError> {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let listener = std::net::TcpListener::bind(preferences.listen_address)?;
+        listener.set_nonblocking(true)?;
+        let local_address = listener.local_addr()?;
+        let codec = FrameCodec::new(preferences.max_frame_length);
+        let limits = ConnectionLimits::from(preferences);
+
+        let cancellation = CancellationToken::new();
+        let thread_stopped = Arc::new(Notify::new());
+        let handle = TransportHandle {
+            runtime: rt.handle().clone(),
+            codec,
+            request_timeout: preferences.request_timeout,
+            max_queued_frames: limits.max_queued_frames,
+            connections: Arc::new(DashMap::new()),
+        };
+        let thread_handle = std::thread::Builder::new()
+            .name("transport".to_string())
+            .spawn({
+                let cancellation = cancellation.clone();
+                let thread_stopped = thread_stopped.clone();
+                move || {
+                    rt.block_on(async {
+                        match TcpListener::from_std(listener) {
+                            Ok(listener) => {
+                                tokio::spawn(accept_connections(
+                                    listener, codec, limits, messages, registry,
+                                ));
+                            }
+                            Err(e) => eprintln!("Transport listener error: {e}"),
+                        }
+                        cancellation.cancelled().await;
+                    });
+                    // pending connections and requests are dropped with runtime
+                    drop(rt);
+                    thread_stopped.notify_one();
+                }
+            })?;
+
+        Ok(Self {
+            handle,
+            local_address,
+            cancellation,
+            thread_handle,
+            thread_stopped,
+        })
+    }
+
+    /// Address listening for remote runtimes
+    pub fn local_address(&self) -> SocketAddr {
+        self.local_address
+    }
+
+    pub fn handle(&self) -> &TransportHandle {
+        &self.handle
+    }
+}
+
+impl Drop for Transport {
+    fn drop(&mut self) {
+        // transport thread is stopped with runtime even without graceful shutdown
+        self.cancellation.cancel();
+    }
+}
+
+impl GracefulShutdown for Transport {
+    async fn graceful_shutdown(self, timeout: Duration) -> Result<(), WaitError> {
+        self.cancellation.cancel();
+        let res = waiter("transport", &self.thread_stopped, timeout, None).await;
+        if res.is_err() && !self.thread_handle.is_finished() {
+            eprintln!("Transport thread is not finished");
+        }
+        res
+    }
+}
+
+impl TransportHandle {
+    /// Spawns task on transport thread
+    pub fn spawn<F>(&self, future: F) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.runtime.spawn(future)
+    }
+
+    /// Sends request to remote runtime and waits for serialized result
+    pub async fn request(
+        &self,
+        peer: SocketAddr,
+        request: Request,
+    ) -> Result<Vec<u8>, RemoteAddrError> {
+        let this = self.clone();
+        let timeout = self.request_timeout;
+        self.runtime
+            .spawn(async move {
+                let request = async { this.connection(peer).await?.request(request).await };
+                tokio::time::timeout(timeout, request)
+                    .await
+                    .map_err(|_| RemoteAddrError::Timeout(timeout))?
+            })
+            .await
+            .map_err(|_| RemoteAddrError::TransportStopped)?
+    }
+
+    /// Sends request to remote runtime without waiting for response
+    pub async fn dispatch(
+        &self,
+        peer: SocketAddr,
+        request: Request,
+    ) -> Result<(), RemoteAddrError> {
+        let this = self.clone();
+        let timeout = self.request_timeout;
+        self.runtime
+            .spawn(async move {
+                let dispatch = async { this.connection(peer).await?.dispatch(request).await };
+                tokio::time::timeout(timeout, dispatch)
+                    .await
+                    .map_err(|_| RemoteAddrError::Timeout(timeout))?
+            })
+            .await
+            .map_err(|_| RemoteAddrError::TransportStopped)?
+    }
+
+    /// Open connection to remote runtime, connects if there is no one
+    async fn connection(&self, peer: SocketAddr) -> Result<Connection, RemoteAddrError> {
+        let slot = self.connections.entry(peer).or_default().clone();
+        let mut connection = slot.lock().await;
+        if let Some(connection) = connection.as_ref().filter(|c| !c.is_closed()) {
+            return Ok(connection.clone());
+        }
+        let connected = Connection::connect(peer, self.codec, self.max_queued_frames).await?;
+        *connection = Some(connected.clone());
+        Ok(connected)
+    }
+}
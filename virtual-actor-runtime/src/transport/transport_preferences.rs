@@ -0,0 +1,32 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+/// Transport settings
+pub struct TransportPreferences {
+    /// Address listening for remote runtimes, port `0` picks free port
+    pub listen_address: SocketAddr,
+    /// Maximal length of frame in bytes, larger messages and results are rejected
+    pub max_frame_length: usize,
+    /// Time to wait for remote runtime to connect and reply
+    pub request_timeout: Duration,
+    /// Maximal number of frames waiting to be written to connection,
+    /// senders wait until frames are written
+    pub max_queued_frames: usize,
+    /// Maximal number of requests received through connection which are handled at once,
+    /// connection is not read until one of them is finished
+    pub max_concurrent_requests: usize,
+}
+
+impl Default for TransportPreferences {
+    fn default() -> Self {
+        Self {
+            listen_address: SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+            max_frame_length: 16 * 1024 * 1024,
+            request_timeout: Duration::from_secs(5),
+            max_queued_frames: 1024,
+            max_concurrent_requests: 256,
+        }
+    }
+}
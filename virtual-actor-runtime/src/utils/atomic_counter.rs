@@ -16,6 +16,10 @@ impl AtomicCounter {
     pub fn increment(&self) -> usize {
         self.counter.fetch_add(1, Ordering::Relaxed)
     }
+
+    pub fn set(&self, value: usize) {
+        self.counter.store(value, Ordering::Relaxed);
+    }
 }
 
 impl Default for AtomicCounter {
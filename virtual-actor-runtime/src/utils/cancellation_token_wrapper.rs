@@ -13,6 +13,11 @@ impl CancellationTokenWrapper {
     pub fn new(token: CancellationToken) -> Self {
         Self { token }
     }
+
+    /// Wrapped token
+    pub fn inner(&self) -> &CancellationToken {
+        &self.token
+    }
 }
 
 impl CancellationTokenTrait for CancellationTokenWrapper {
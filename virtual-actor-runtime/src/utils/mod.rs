@@ -4,6 +4,7 @@ pub mod atomic_counter;
 pub mod cancellation_token_wrapper;
 mod graceful_shutdown;
 pub mod notify_once;
+pub mod sleep;
 pub mod waiter;
 
 pub use graceful_shutdown::GracefulShutdown;
@@ -0,0 +1,30 @@
+//! Cancellable sleep used by self-scheduling housekeeping actors
+
+use std::time::Duration;
+
+use virtual_actor::utils::CancellationToken;
+
+use super::cancellation_token_wrapper::CancellationTokenWrapper;
+
+/// Sleep interruption reason
+#[derive(Debug)]
+pub enum SleepWaitError {
+    /// Sleep is interrupted by graceful shutdown
+    GracefullyCancelled,
+    /// Sleep is interrupted by actor cancellation
+    Cancelled,
+}
+
+/// Sleeps for `duration` unless one of cancellation tokens is cancelled
+pub async fn sleep_with_cancel(
+    duration: Duration,
+    graceful_cancellation: &tokio_util::sync::CancellationToken,
+    cancellation: &CancellationTokenWrapper,
+) -> Result<(), SleepWaitError> {
+    tokio::select! {
+        biased;
+        () = graceful_cancellation.cancelled() => Err(SleepWaitError::GracefullyCancelled),
+        () = cancellation.cancelled()  => Err(SleepWaitError::Cancelled),
+        () = tokio::time::sleep(duration) => Ok(()),
+    }
+}
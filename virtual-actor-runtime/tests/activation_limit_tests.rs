@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::{
+    collectable_actor::{CollectableActor, GetCounter, Ping, SlowPing},
+    ping_pong_virtual_actor::{VirtualGetCounter, VirtualPong, VirtualPongActor},
+};
+
+mod actors {
+    pub mod collectable_actor;
+    // only pong actor is used
+    #[allow(dead_code)]
+    pub mod ping_pong_virtual_actor;
+}
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+const ACTIVITY_GAP: Duration = Duration::from_millis(20);
+
+#[tokio::test]
+async fn per_type_activation_limit_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+
+    runtime.register_actor_with_factory_and_preferences(
+        DefaultVirtualActorFactory::<CollectableActor>::default(),
+        &executor,
+        VirtualActorPreferences {
+            max_active_actors: Some(2),
+            ..Default::default()
+        },
+    )?;
+
+    for id in 1..=2 {
+        let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&id).await?;
+        addr.send(Ping).await?;
+        tokio::time::sleep(ACTIVITY_GAP).await;
+    }
+
+    // third activation evicts least recently used actor 1
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&3).await?;
+    addr.send(Ping).await?;
+
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&2).await?;
+    assert_eq!(addr.send(GetCounter).await?, 1, "Actor 2 should be kept");
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&3).await?;
+    assert_eq!(addr.send(GetCounter).await?, 1, "Actor 3 should be kept");
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&1).await?;
+    assert_eq!(addr.send(GetCounter).await?, 0, "Actor 1 should be evicted");
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn busy_actor_is_not_evicted_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+
+    runtime.register_actor_with_factory_and_preferences(
+        DefaultVirtualActorFactory::<CollectableActor>::default(),
+        &executor,
+        VirtualActorPreferences {
+            max_active_actors: Some(1),
+            ..Default::default()
+        },
+    )?;
+
+    // actor 1 is busy for longer than default actor shutdown interval
+    let busy: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&1).await?;
+    let slow_ping = tokio::spawn(async move { busy.send(SlowPing(Duration::from_secs(2))).await });
+    tokio::time::sleep(ACTIVITY_GAP).await;
+
+    // limit is exceeded instead of evicting busy actor
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&2).await?;
+    addr.send(Ping).await?;
+
+    slow_ping.await??;
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&1).await?;
+    assert_eq!(addr.send(GetCounter).await?, 1, "Busy actor should be kept");
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn global_activation_limit_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::with_preferences(RuntimePreferences {
+        max_active_actors: Some(2),
+        ..Default::default()
+    })?;
+    let executor = runtime.create_executor()?;
+
+    runtime.register_actor::<CollectableActor>(&executor)?;
+    runtime.register_actor::<VirtualPongActor>(&executor)?;
+
+    let collectable: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&1).await?;
+    collectable.send(Ping).await?;
+    tokio::time::sleep(ACTIVITY_GAP).await;
+    let pong: VirtualAddr<VirtualPongActor> = runtime.spawn_virtual(&1).await?;
+    pong.send(VirtualPong).await?;
+    tokio::time::sleep(ACTIVITY_GAP).await;
+
+    // activation of another type evicts least recently used actor across all types
+    let collectable: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&2).await?;
+    collectable.send(Ping).await?;
+
+    let pong: VirtualAddr<VirtualPongActor> = runtime.spawn_virtual(&1).await?;
+    assert_eq!(
+        pong.send(VirtualGetCounter).await?,
+        1,
+        "Pong actor should be kept"
+    );
+    let collectable: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&1).await?;
+    assert_eq!(
+        collectable.send(GetCounter).await?,
+        0,
+        "Collectable actor 1 should be evicted"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
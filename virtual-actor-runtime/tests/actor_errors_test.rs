@@ -13,7 +13,9 @@ use crate::actors::error_handling_virtual_actor::{
     ErrorHandlingActor, FactoryError, FactoryErrorActorFactory, PanicMessage, UnhandledMessage,
 };
 
-mod actors;
+mod actors {
+    pub mod error_handling_virtual_actor;
+}
 
 const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
 
@@ -2,9 +2,13 @@ use std::time::Duration;
 
 use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
 
-use crate::actors::collectable_actor::{CollectableActor, GetCounter, Ping};
+use crate::actors::collectable_actor::{
+    CollectableActor, CollectableActorFactory, GetCounter, Ping,
+};
 
-mod actors;
+mod actors {
+    pub mod collectable_actor;
+}
 
 const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
 
@@ -90,3 +94,74 @@ async fn actor_gc_refresh_test() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn actor_gc_registration_preferences_test() -> Result<(), Box<dyn std::error::Error>> {
+    let gc_interval = Duration::from_millis(100);
+    let idle = gc_interval * 5;
+    let mut runtime = Runtime::with_preferences(RuntimePreferences {
+        actor_idle_timeout: Duration::from_secs(3600),
+        garbage_collect_interval: Duration::from_secs(3600),
+        ..Default::default()
+    })?;
+    let executor = runtime.create_executor()?;
+
+    runtime.register_actor_with_factory_and_preferences(
+        DefaultVirtualActorFactory::<CollectableActor>::default(),
+        &executor,
+        VirtualActorPreferences {
+            actor_idle_timeout: Some(idle),
+            garbage_collect_interval: Some(gc_interval),
+            ..Default::default()
+        },
+    )?;
+
+    let id = 10;
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&id).await?;
+    addr.send(Ping).await?;
+
+    tokio::time::sleep(idle + gc_interval * 2).await;
+
+    let counter = addr.send(GetCounter).await?;
+    assert_eq!(
+        counter, 0,
+        "Counter should be 0 after gc with registration preferences"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn actor_gc_factory_preferences_test() -> Result<(), Box<dyn std::error::Error>> {
+    let gc_interval = Duration::from_millis(100);
+    let idle = gc_interval * 5;
+    let mut runtime = Runtime::with_preferences(RuntimePreferences {
+        actor_idle_timeout: idle,
+        garbage_collect_interval: gc_interval,
+        ..Default::default()
+    })?;
+    let executor = runtime.create_executor()?;
+
+    // factory keeps actors alive longer than runtime default
+    runtime.register_actor_with_factory(
+        CollectableActorFactory {
+            actor_idle_timeout: Duration::from_secs(3600),
+        },
+        &executor,
+    )?;
+
+    let id = 10;
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&id).await?;
+    addr.send(Ping).await?;
+
+    tokio::time::sleep(idle + gc_interval * 2).await;
+
+    let counter = addr.send(GetCounter).await?;
+    assert_eq!(counter, 1, "Actor should not be collected");
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
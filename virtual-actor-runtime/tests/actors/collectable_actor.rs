@@ -6,13 +6,40 @@ use virtual_actor_runtime::prelude::*;
 #[result(())]
 pub struct Ping;
 
-#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[derive(Message, VirtualMessage, Serialize, Deserialize, Clone)]
 #[result(u32)]
+#[idempotent]
 pub struct GetCounter;
 
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(())]
+pub struct SlowPing(pub std::time::Duration);
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(())]
+pub struct Stop;
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(())]
+pub struct DeactivateOnIdle;
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(())]
+pub struct DelayDeactivation(pub std::time::Duration);
+
+/// Handler never completes, until actor execution is cancelled
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(())]
+pub struct Hang;
+
 #[derive(Actor, VirtualActor)]
 #[message(Ping)]
 #[message(GetCounter)]
+#[message(DeactivateOnIdle)]
+#[message(DelayDeactivation)]
+#[message(SlowPing)]
+#[message(Stop)]
+#[message(Hang)]
 pub struct CollectableActor {
     id: u32,
     counter: u32,
@@ -34,6 +61,49 @@ impl MessageHandler<GetCounter> for CollectableActor {
     }
 }
 
+impl MessageHandler<SlowPing> for CollectableActor {
+    async fn handle(
+        &mut self,
+        msg: SlowPing,
+        _ctx: &Self::ActorContext,
+    ) -> <SlowPing as Message>::Result {
+        tokio::time::sleep(msg.0).await;
+        self.counter += 1;
+    }
+}
+
+impl MessageHandler<Stop> for CollectableActor {
+    async fn handle(&mut self, _msg: Stop, ctx: &Self::ActorContext) -> <Stop as Message>::Result {
+        ctx.stop();
+    }
+}
+
+impl MessageHandler<Hang> for CollectableActor {
+    async fn handle(&mut self, _msg: Hang, _ctx: &Self::ActorContext) -> <Hang as Message>::Result {
+        futures::future::pending::<()>().await;
+    }
+}
+
+impl MessageHandler<DeactivateOnIdle> for CollectableActor {
+    async fn handle(
+        &mut self,
+        _msg: DeactivateOnIdle,
+        ctx: &Self::ActorContext,
+    ) -> <DeactivateOnIdle as Message>::Result {
+        ctx.deactivate_on_idle();
+    }
+}
+
+impl MessageHandler<DelayDeactivation> for CollectableActor {
+    async fn handle(
+        &mut self,
+        msg: DelayDeactivation,
+        ctx: &Self::ActorContext,
+    ) -> <DelayDeactivation as Message>::Result {
+        ctx.delay_deactivation(msg.0);
+    }
+}
+
 impl VirtualActorConstructor for CollectableActor {
     fn new(id: &u32) -> Self {
         Self {
@@ -42,3 +112,50 @@ impl VirtualActorConstructor for CollectableActor {
         }
     }
 }
+
+/// Factory overriding idle timeout of created actors
+#[allow(dead_code)]
+pub struct CollectableActorFactory {
+    pub actor_idle_timeout: std::time::Duration,
+}
+
+impl ActorFactory for CollectableActorFactory {
+    type Actor = CollectableActor;
+}
+
+impl VirtualActorFactory for CollectableActorFactory {
+    type Error = std::convert::Infallible;
+
+    async fn create_actor(&self, id: &u32) -> Result<CollectableActor, Self::Error> {
+        Ok(<CollectableActor as VirtualActorConstructor>::new(id))
+    }
+
+    fn virtual_actor_preferences(&self) -> VirtualActorPreferences {
+        VirtualActorPreferences {
+            actor_idle_timeout: Some(self.actor_idle_timeout),
+            ..Default::default()
+        }
+    }
+}
+
+/// Factory counting created actors, creation takes `activation_delay`
+#[allow(dead_code)]
+pub struct CountingActorFactory {
+    pub created: std::sync::Arc<std::sync::atomic::AtomicU32>,
+    pub activation_delay: std::time::Duration,
+}
+
+impl ActorFactory for CountingActorFactory {
+    type Actor = CollectableActor;
+}
+
+impl VirtualActorFactory for CountingActorFactory {
+    type Error = std::convert::Infallible;
+
+    async fn create_actor(&self, id: &u32) -> Result<CollectableActor, Self::Error> {
+        self.created
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        tokio::time::sleep(self.activation_delay).await;
+        Ok(<CollectableActor as VirtualActorConstructor>::new(id))
+    }
+}
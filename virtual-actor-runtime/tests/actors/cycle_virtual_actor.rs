@@ -0,0 +1,55 @@
+use serde::Deserialize;
+use serde::Serialize;
+use virtual_actor_runtime::errors::{LocalAddrError, VirtualAddrError};
+use virtual_actor_runtime::prelude::*;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum ForwardError {
+    Deadlock(Vec<String>),
+    Other(String),
+}
+
+/// Forwards message through actors with ids from `path` waiting for each response
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(Result<(), ForwardError>)]
+pub struct Forward {
+    pub path: Vec<u32>,
+}
+
+#[derive(Actor, VirtualActor)]
+#[message(Forward)]
+pub struct CycleActor {
+    id: u32,
+}
+
+impl MessageHandler<Forward> for CycleActor {
+    async fn handle(
+        &mut self,
+        msg: Forward,
+        ctx: &Self::ActorContext,
+    ) -> <Forward as Message>::Result {
+        let Some((next, path)) = msg.path.split_first() else {
+            return Ok(());
+        };
+        let addr = ctx
+            .get_or_create::<CycleActor>(next)
+            .await
+            .map_err(|e| ForwardError::Other(e.to_string()))?;
+        let forward = Forward {
+            path: path.to_vec(),
+        };
+        match addr.send(forward).await {
+            Ok(res) => res,
+            Err(VirtualAddrError::LocalAddrError(LocalAddrError::DeadlockDetected(e))) => Err(
+                ForwardError::Deadlock(e.cycle().iter().map(ToString::to_string).collect()),
+            ),
+            Err(e) => Err(ForwardError::Other(e.to_string())),
+        }
+    }
+}
+
+impl VirtualActorConstructor for CycleActor {
+    fn new(id: &u32) -> Self {
+        Self { id: *id }
+    }
+}
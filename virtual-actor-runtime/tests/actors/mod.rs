@@ -1,5 +0,0 @@
-#![allow(clippy::no_effect_underscore_binding)]
-
-pub mod collectable_actor;
-pub mod error_handling_virtual_actor;
-pub mod ping_pong_virtual_actor;
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use virtual_actor_runtime::prelude::*;
+
+#[derive(Message)]
+#[result(Result<String, String>)]
+pub struct BlockingSleep(pub Duration);
+
+#[derive(Message)]
+#[result(Result<u64, String>)]
+pub struct Sum(pub u64);
+
+#[derive(Message)]
+#[result(Result<(), String>)]
+pub struct ComputePanic;
+
+#[derive(Message)]
+#[result(())]
+pub struct Ping;
+
+#[derive(Actor, LocalActor, Default)]
+#[message(BlockingSleep)]
+#[message(Sum)]
+#[message(ComputePanic)]
+#[message(Ping)]
+pub struct OffloadActor;
+
+impl MessageHandler<BlockingSleep> for OffloadActor {
+    async fn handle(
+        &mut self,
+        msg: BlockingSleep,
+        ctx: &Self::ActorContext,
+    ) -> <BlockingSleep as Message>::Result {
+        ctx.spawn_blocking(move || {
+            std::thread::sleep(msg.0);
+            std::thread::current().name().unwrap_or_default().to_owned()
+        })
+        .await
+        .map_err(|e| e.to_string())
+    }
+}
+
+impl MessageHandler<Sum> for OffloadActor {
+    async fn handle(&mut self, msg: Sum, ctx: &Self::ActorContext) -> <Sum as Message>::Result {
+        ctx.spawn_compute(move || (1..=msg.0).sum())
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl MessageHandler<ComputePanic> for OffloadActor {
+    async fn handle(
+        &mut self,
+        _msg: ComputePanic,
+        ctx: &Self::ActorContext,
+    ) -> <ComputePanic as Message>::Result {
+        ctx.spawn_compute(|| panic!("compute failed"))
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl MessageHandler<Ping> for OffloadActor {
+    async fn handle(&mut self, _msg: Ping, _ctx: &Self::ActorContext) -> <Ping as Message>::Result {
+    }
+}
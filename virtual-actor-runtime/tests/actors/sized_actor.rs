@@ -0,0 +1,47 @@
+use serde::Deserialize;
+use serde::Serialize;
+use virtual_actor_runtime::prelude::*;
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(())]
+pub struct SetSize(pub usize);
+
+#[derive(Message, VirtualMessage, Serialize, Deserialize)]
+#[result(usize)]
+pub struct GetSize;
+
+/// Actor reporting size of its state
+#[derive(Actor, VirtualActor)]
+#[message(SetSize)]
+#[message(GetSize)]
+pub struct SizedActor {
+    id: u32,
+    size: usize,
+}
+
+impl MessageHandler<SetSize> for SizedActor {
+    async fn handle(
+        &mut self,
+        msg: SetSize,
+        ctx: &Self::ActorContext,
+    ) -> <SetSize as Message>::Result {
+        self.size = msg.0;
+        ctx.report_memory_usage(self.size);
+    }
+}
+
+impl MessageHandler<GetSize> for SizedActor {
+    async fn handle(
+        &mut self,
+        _msg: GetSize,
+        _ctx: &Self::ActorContext,
+    ) -> <GetSize as Message>::Result {
+        self.size
+    }
+}
+
+impl VirtualActorConstructor for SizedActor {
+    fn new(id: &u32) -> Self {
+        Self { id: *id, size: 0 }
+    }
+}
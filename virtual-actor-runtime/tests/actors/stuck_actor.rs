@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use virtual_actor_runtime::prelude::*;
+
+#[derive(Message)]
+#[result(())]
+pub struct PendingTask;
+
+#[derive(Message)]
+#[result(())]
+pub struct ThreadSleepTask(pub Duration);
+
+#[derive(Message)]
+#[result(())]
+pub struct Ping;
+
+#[derive(Actor, LocalActor, Default)]
+#[message(PendingTask)]
+#[message(ThreadSleepTask)]
+#[message(Ping)]
+pub struct StuckActor;
+
+impl MessageHandler<PendingTask> for StuckActor {
+    async fn handle(
+        &mut self,
+        _msg: PendingTask,
+        _ctx: &Self::ActorContext,
+    ) -> <PendingTask as Message>::Result {
+        futures::future::pending::<()>().await;
+    }
+}
+
+impl MessageHandler<ThreadSleepTask> for StuckActor {
+    async fn handle(
+        &mut self,
+        msg: ThreadSleepTask,
+        _ctx: &Self::ActorContext,
+    ) -> <ThreadSleepTask as Message>::Result {
+        std::thread::sleep(msg.0);
+    }
+}
+
+impl MessageHandler<Ping> for StuckActor {
+    async fn handle(&mut self, _msg: Ping, _ctx: &Self::ActorContext) -> <Ping as Message>::Result {
+    }
+}
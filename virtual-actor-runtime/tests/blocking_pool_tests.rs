@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, LocalAddr};
+
+use crate::actors::offload_actor::{BlockingSleep, ComputePanic, OffloadActor, Ping, Sum};
+
+mod actors {
+    pub mod offload_actor;
+}
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+#[tokio::test]
+async fn spawn_blocking_does_not_stall_executor_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+
+    let sleeping: LocalAddr<OffloadActor> = runtime.spawn_local(&executor).await?;
+    let neighbour: LocalAddr<OffloadActor> = runtime.spawn_local(&executor).await?;
+
+    let (sleep_res, ping_res) = tokio::join!(
+        sleeping.send(BlockingSleep(Duration::from_millis(500))),
+        tokio::time::timeout(Duration::from_millis(250), async {
+            // give blocking task time to start
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            neighbour.send(Ping).await
+        })
+    );
+
+    ping_res.expect("Co-located actor should not be stalled by blocking work")?;
+    let thread_name = sleep_res??;
+    assert!(
+        thread_name.starts_with("blocking-pool"),
+        "Blocking work should run on blocking pool, not on {thread_name}"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn spawn_compute_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::with_preferences(RuntimePreferences {
+        compute_pool_size: 2,
+        ..Default::default()
+    })?;
+    let executor = runtime.create_executor()?;
+
+    let addr: LocalAddr<OffloadActor> = runtime.spawn_local(&executor).await?;
+
+    let sum = addr.send(Sum(1000)).await??;
+    assert_eq!(sum, 500_500);
+
+    let res = addr.send(ComputePanic).await?;
+    assert!(
+        res.is_err_and(|e| e.contains("compute failed")),
+        "Panic in compute task should be returned as error"
+    );
+
+    // actor keeps working after panic in offloaded task
+    let sum = addr.send(Sum(10)).await??;
+    assert_eq!(sum, 55);
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
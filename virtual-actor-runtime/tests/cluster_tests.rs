@@ -0,0 +1,200 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::broadcast;
+use virtual_actor_runtime::{
+    errors::MembershipError, prelude::*, GracefulShutdown, Member, MemberStatus, MembershipEvent,
+    MembershipPreferences, TransportPreferences,
+};
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+const WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn start_node(seeds: &[SocketAddr]) -> Result<(Runtime, SocketAddr), Box<dyn std::error::Error>> {
+    start_node_at(TransportPreferences::default().listen_address, seeds)
+}
+
+fn start_node_at(
+    listen_address: SocketAddr,
+    seeds: &[SocketAddr],
+) -> Result<(Runtime, SocketAddr), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let address = runtime.start_transport(&TransportPreferences {
+        listen_address,
+        request_timeout: Duration::from_millis(500),
+        ..Default::default()
+    })?;
+    runtime.start_membership(MembershipPreferences {
+        seeds: seeds.to_vec(),
+        heartbeat_interval: Duration::from_millis(50),
+        min_std_deviation: Duration::from_millis(20),
+        acceptable_heartbeat_pause: Duration::from_millis(100),
+        down_after: Duration::from_millis(200),
+        ..Default::default()
+    })?;
+    Ok((runtime, address))
+}
+
+/// Waits until members known by runtime satisfy `predicate`
+async fn wait_for_members(runtime: &Runtime, predicate: impl Fn(&[Member]) -> bool) -> Vec<Member> {
+    let start = Instant::now();
+    loop {
+        let members = runtime.cluster_members();
+        if predicate(&members) {
+            return members;
+        }
+        assert!(
+            start.elapsed() < WAIT_TIMEOUT,
+            "Unexpected members {members:?}"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// Waits for event about member with `address`
+async fn wait_for_event(
+    events: &mut broadcast::Receiver<MembershipEvent>,
+    address: SocketAddr,
+) -> Result<MembershipEvent, Box<dyn std::error::Error>> {
+    loop {
+        let event = tokio::time::timeout(WAIT_TIMEOUT, events.recv()).await??;
+        if event.member().address == address {
+            return Ok(event);
+        }
+    }
+}
+
+fn is_up(members: &[Member], address: SocketAddr) -> bool {
+    members
+        .iter()
+        .any(|m| m.address == address && m.status == MemberStatus::Up && m.reachable)
+}
+
+#[tokio::test]
+async fn cluster_join_test() -> Result<(), Box<dyn std::error::Error>> {
+    let (first, first_address) = start_node(&[])?;
+    let (second, second_address) = start_node(&[first_address])?;
+    let (third, third_address) = start_node(&[first_address])?;
+    let addresses = [first_address, second_address, third_address];
+
+    // third node learns about second one through gossip of seed
+    for node in [&first, &second, &third] {
+        let members = wait_for_members(node, |members| {
+            addresses.iter().all(|address| is_up(members, *address))
+        })
+        .await;
+        assert_eq!(members.len(), 3);
+    }
+
+    for node in [first, second, third] {
+        node.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn cluster_failure_detection_test() -> Result<(), Box<dyn std::error::Error>> {
+    let (first, first_address) = start_node(&[])?;
+    let mut events = first.subscribe_membership_events();
+    let (second, second_address) = start_node(&[first_address])?;
+
+    let up = wait_for_event(&mut events, second_address).await?;
+    assert!(matches!(up, MembershipEvent::MemberUp(_)), "{up:?}");
+    wait_for_members(&second, |members| is_up(members, first_address)).await;
+
+    // node crashes without leaving cluster
+    drop(second);
+
+    let unreachable = wait_for_event(&mut events, second_address).await?;
+    assert!(
+        matches!(unreachable, MembershipEvent::MemberUnreachable(ref m) if !m.reachable),
+        "{unreachable:?}"
+    );
+    let down = wait_for_event(&mut events, second_address).await?;
+    assert!(
+        matches!(down, MembershipEvent::MemberDown(ref m) if m.status == MemberStatus::Down),
+        "{down:?}"
+    );
+    let members = first.cluster_members();
+    assert!(members
+        .iter()
+        .any(|m| m.address == second_address && m.status == MemberStatus::Down));
+    assert!(is_up(&members, first_address));
+
+    first.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn cluster_down_member_rejoin_test() -> Result<(), Box<dyn std::error::Error>> {
+    let (first, first_address) = start_node(&[])?;
+    let mut events = first.subscribe_membership_events();
+    let (second, second_address) = start_node(&[first_address])?;
+    let up = wait_for_event(&mut events, second_address).await?;
+    let incarnation = up.member().incarnation;
+
+    drop(second);
+    loop {
+        let event = wait_for_event(&mut events, second_address).await?;
+        if matches!(event, MembershipEvent::MemberDown(_)) {
+            break;
+        }
+    }
+
+    // restarted node has no seeds, it is found by probing down member
+    let (second, _) = start_node_at(second_address, &[])?;
+    let up = wait_for_event(&mut events, second_address).await?;
+    assert!(
+        matches!(up, MembershipEvent::MemberUp(ref m) if m.incarnation > incarnation),
+        "{up:?}"
+    );
+    wait_for_members(&first, |members| is_up(members, second_address)).await;
+    wait_for_members(&second, |members| is_up(members, first_address)).await;
+
+    second.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    first.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn cluster_leave_test() -> Result<(), Box<dyn std::error::Error>> {
+    let (first, first_address) = start_node(&[])?;
+    let mut events = first.subscribe_membership_events();
+    let (second, second_address) = start_node(&[first_address])?;
+    wait_for_members(&first, |members| is_up(members, second_address)).await;
+
+    second.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    let left = loop {
+        let event = wait_for_event(&mut events, second_address).await?;
+        if !matches!(event, MembershipEvent::MemberUp(_)) {
+            break event;
+        }
+    };
+    assert!(matches!(left, MembershipEvent::MemberLeft(_)), "{left:?}");
+
+    first.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn membership_requires_transport_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    assert!(matches!(
+        runtime.start_membership(MembershipPreferences::default()),
+        Err(MembershipError::TransportNotStarted)
+    ));
+    runtime.start_transport(&TransportPreferences::default())?;
+    runtime.start_membership(MembershipPreferences::default())?;
+    assert!(matches!(
+        runtime.start_membership(MembershipPreferences::default()),
+        Err(MembershipError::AlreadyStarted)
+    ));
+    let members = runtime.cluster_members();
+    assert_eq!(members.len(), 1);
+    assert_eq!(Some(members[0].address), runtime.transport_address());
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
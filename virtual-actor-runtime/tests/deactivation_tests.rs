@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::collectable_actor::{
+    CollectableActor, DeactivateOnIdle, DelayDeactivation, GetCounter, Ping,
+};
+
+mod actors {
+    pub mod collectable_actor;
+}
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+#[tokio::test]
+async fn deactivate_on_idle_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor::<CollectableActor>(&executor)?;
+
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&1).await?;
+    for _ in 0..3 {
+        addr.send(Ping).await?;
+    }
+    addr.send(DeactivateOnIdle).await?;
+
+    // next message is handled by fresh instance without waiting for garbage collection
+    let counter = addr.send(GetCounter).await?;
+    assert_eq!(counter, 0, "Actor should be reactivated");
+
+    addr.send(Ping).await?;
+    let counter = addr.send(GetCounter).await?;
+    assert_eq!(counter, 1, "Reactivated actor should keep working");
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delay_deactivation_test() -> Result<(), Box<dyn std::error::Error>> {
+    let gc_interval = Duration::from_millis(50);
+    let idle = gc_interval * 2;
+    let mut runtime = Runtime::with_preferences(RuntimePreferences {
+        actor_idle_timeout: idle,
+        garbage_collect_interval: gc_interval,
+        ..Default::default()
+    })?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor::<CollectableActor>(&executor)?;
+
+    let delay = Duration::from_millis(600);
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&1).await?;
+    addr.send(Ping).await?;
+    addr.send(DelayDeactivation(delay)).await?;
+
+    tokio::time::sleep(idle * 3).await;
+    let counter = addr.send(GetCounter).await?;
+    assert_eq!(counter, 1, "Actor should not be collected while delayed");
+
+    tokio::time::sleep(delay).await;
+    let counter = addr.send(GetCounter).await?;
+    assert_eq!(counter, 0, "Actor should be collected after delay");
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::cycle_virtual_actor::{CycleActor, Forward, ForwardError};
+
+mod actors {
+    pub mod cycle_virtual_actor;
+}
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+const SEND_TIMEOUT: Duration = Duration::from_secs(1);
+
+async fn forward(
+    runtime: &Runtime,
+    id: u32,
+    path: Vec<u32>,
+) -> Result<Result<(), ForwardError>, Box<dyn std::error::Error>> {
+    let addr: VirtualAddr<CycleActor> = runtime.spawn_virtual(&id).await?;
+    let res = tokio::time::timeout(SEND_TIMEOUT, addr.send(Forward { path })).await??;
+    Ok(res)
+}
+
+#[tokio::test]
+async fn call_chain_without_cycle_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+
+    runtime.register_actor::<CycleActor>(&executor)?;
+
+    let res = forward(&runtime, 1, vec![2, 3, 4]).await?;
+    assert_eq!(res, Ok(()), "Chain without cycle should succeed");
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn call_chain_cycle_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+
+    runtime.register_actor::<CycleActor>(&executor)?;
+
+    let res = forward(&runtime, 1, vec![2, 3, 2]).await?;
+    let expected_cycle = vec![
+        "CycleActor::2".to_owned(),
+        "CycleActor::3".to_owned(),
+        "CycleActor::2".to_owned(),
+    ];
+    assert_eq!(res, Err(ForwardError::Deadlock(expected_cycle)));
+
+    // actors are not blocked after deadlock was detected
+    let res = forward(&runtime, 3, vec![2, 1]).await?;
+    assert_eq!(
+        res,
+        Ok(()),
+        "Actors should process messages after detected deadlock"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn call_chain_self_send_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+
+    runtime.register_actor::<CycleActor>(&executor)?;
+
+    let res = forward(&runtime, 1, vec![1]).await?;
+    let expected_cycle = vec!["CycleActor::1".to_owned(), "CycleActor::1".to_owned()];
+    assert_eq!(res, Err(ForwardError::Deadlock(expected_cycle)));
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
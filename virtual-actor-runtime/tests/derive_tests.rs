@@ -108,3 +108,15 @@ fn test_derive_virtual_message_result() {
     let s = <TestMessage as Message>::Result::Ok(42);
     assert_eq!(s, Ok(42));
 }
+
+#[derive(Message, Clone, Debug, PartialEq)]
+#[result(())]
+#[idempotent]
+struct TestIdempotentMessage(u32);
+
+#[test]
+fn test_derive_message_idempotent() {
+    let msg = TestIdempotentMessage(42);
+    assert_eq!(msg.idempotent_copy(), Some(TestIdempotentMessage(42)));
+    assert!(TestMessage.idempotent_copy().is_none());
+}
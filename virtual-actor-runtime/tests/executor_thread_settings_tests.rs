@@ -0,0 +1,90 @@
+#![cfg(target_os = "linux")]
+
+use std::time::Duration;
+
+use virtual_actor_runtime::{
+    prelude::*, ExecutorPreferences, GracefulShutdown, LocalAddr, ThreadPriority,
+};
+
+use crate::actors::stuck_actor::{Ping, StuckActor};
+
+mod actors {
+    pub mod stuck_actor;
+}
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+#[tokio::test]
+async fn pinned_executor_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let default_executor = runtime.create_executor()?;
+    let available_cores = default_executor
+        .thread_info()
+        .cpu_affinity
+        .clone()
+        .expect("Affinity should be reported on Linux");
+    let core = available_cores[0];
+
+    let executor = runtime.create_executor_with_preferences(&ExecutorPreferences {
+        thread_name: "pinned-executor".to_owned(),
+        cpu_affinity: Some(vec![core]),
+        thread_priority: Some(ThreadPriority::Nice(5)),
+        ..Default::default()
+    })?;
+
+    let info = executor.thread_info();
+    assert_eq!(info.thread_name, "pinned-executor");
+    assert_eq!(info.cpu_affinity, Some(vec![core]));
+    assert_eq!(info.thread_priority, Some(ThreadPriority::Nice(5)));
+    assert!(info.native_thread_id.is_some());
+
+    let executors_info = runtime.executors_info();
+    assert_eq!(executors_info.len(), 3);
+    assert_eq!(executors_info[0].thread_name, "housekeeping-executor");
+    assert_eq!(executors_info[2], *info);
+
+    // pinned executor runs actors
+    let addr: LocalAddr<StuckActor> = runtime.spawn_local(&executor).await?;
+    addr.send(Ping).await?;
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn clamped_priority_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+
+    // niceness above 19 is clamped by OS
+    let executor = runtime.create_executor_with_preferences(&ExecutorPreferences {
+        thread_priority: Some(ThreadPriority::Nice(25)),
+        ..Default::default()
+    })?;
+    assert_eq!(
+        executor.thread_info().thread_priority,
+        Some(ThreadPriority::Nice(19))
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_affinity_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+
+    let res = runtime.create_executor_with_preferences(&ExecutorPreferences {
+        cpu_affinity: Some(vec![usize::MAX]),
+        ..Default::default()
+    });
+    assert!(
+        res.is_err(),
+        "Executor should not start with invalid affinity"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
+
+use crate::actors::sized_actor::{GetSize, SetSize, SizedActor};
+
+mod actors {
+    pub mod sized_actor;
+}
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+fn memory_pressure_preferences(
+    max_rss_bytes: Option<usize>,
+    max_state_bytes: Option<usize>,
+) -> (
+    MemoryPressurePreferences,
+    Arc<Mutex<Vec<MemoryPressureEvent>>>,
+) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let collected = events.clone();
+    let preferences = MemoryPressurePreferences {
+        check_interval: Duration::from_millis(20),
+        max_rss_bytes,
+        max_state_bytes,
+        min_idle_time: Duration::from_millis(100),
+        reporter: Arc::new(move |event| collected.lock().unwrap().push(event.clone())),
+        ..Default::default()
+    };
+    (preferences, events)
+}
+
+#[tokio::test]
+async fn state_size_pressure_test() -> Result<(), Box<dyn std::error::Error>> {
+    let (memory_pressure, events) = memory_pressure_preferences(None, Some(1000));
+    let mut runtime = Runtime::with_preferences(RuntimePreferences {
+        memory_pressure: Some(memory_pressure),
+        ..Default::default()
+    })?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor::<SizedActor>(&executor)?;
+
+    let first: VirtualAddr<SizedActor> = runtime.spawn_virtual(&1).await?;
+    first.send(SetSize(800)).await?;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let second: VirtualAddr<SizedActor> = runtime.spawn_virtual(&2).await?;
+    second.send(SetSize(800)).await?;
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // longest idle actor is deactivated, pressure is gone after that
+    assert_eq!(second.send(GetSize).await?, 800, "Actor 2 should be kept");
+    assert_eq!(
+        first.send(GetSize).await?,
+        0,
+        "Actor 1 should be deactivated"
+    );
+
+    let events = events.lock().unwrap().clone();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        MemoryPressureEvent::PressureDetected(MemoryPressure::StateSize {
+            state_bytes: 1600,
+            max_state_bytes: 1000,
+        })
+    )));
+    let deactivated = events
+        .iter()
+        .filter_map(|e| match e {
+            MemoryPressureEvent::ActorDeactivated {
+                actor_name,
+                actor_id,
+                idle_for,
+                ..
+            } => Some((*actor_name, actor_id.clone(), *idle_for)),
+            MemoryPressureEvent::PressureDetected(_) => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(deactivated.len(), 1, "Only one actor should be deactivated");
+    let (actor_name, actor_id, idle_for) = &deactivated[0];
+    assert_eq!(*actor_name, "SizedActor");
+    assert_eq!(actor_id, "1");
+    assert!(*idle_for >= Duration::from_millis(100));
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn rss_pressure_test() -> Result<(), Box<dyn std::error::Error>> {
+    // any process exceeds one byte
+    let (memory_pressure, events) = memory_pressure_preferences(Some(1), None);
+    let mut runtime = Runtime::with_preferences(RuntimePreferences {
+        memory_pressure: Some(memory_pressure),
+        ..Default::default()
+    })?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor::<SizedActor>(&executor)?;
+
+    for id in 1..=3 {
+        let addr: VirtualAddr<SizedActor> = runtime.spawn_virtual(&id).await?;
+        addr.send(SetSize(10)).await?;
+    }
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let events = events.lock().unwrap().clone();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        MemoryPressureEvent::PressureDetected(MemoryPressure::Rss { .. })
+    )));
+    let deactivated = events
+        .iter()
+        .filter(|e| matches!(e, MemoryPressureEvent::ActorDeactivated { .. }))
+        .count();
+    assert_eq!(deactivated, 3, "All idle actors should be deactivated");
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
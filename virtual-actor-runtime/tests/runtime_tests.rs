@@ -1,12 +1,22 @@
-use std::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use virtual_actor_runtime::{prelude::*, GracefulShutdown, VirtualAddr};
 
-use crate::actors::ping_pong_virtual_actor::{
-    VirtualGetCounter, VirtualPing, VirtualPingActor, VirtualPongActor,
+use crate::actors::{
+    collectable_actor::{CollectableActor, CountingActorFactory, GetCounter, Ping},
+    ping_pong_virtual_actor::{VirtualGetCounter, VirtualPing, VirtualPingActor, VirtualPongActor},
 };
 
-mod actors;
+mod actors {
+    pub mod collectable_actor;
+    pub mod ping_pong_virtual_actor;
+}
 
 const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
 
@@ -44,3 +54,40 @@ async fn bench_virtual_ping_pong() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn concurrent_activation_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    let created = Arc::new(AtomicU32::new(0));
+    runtime.register_actor_with_factory(
+        CountingActorFactory {
+            created: created.clone(),
+            activation_delay: Duration::from_millis(50),
+        },
+        &executor,
+    )?;
+
+    // messages sent to inactive actor at once are handled by single activation
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&1).await?;
+    let sends: Vec<_> = (0..10)
+        .map(|_| {
+            let addr = addr.clone();
+            tokio::spawn(async move { addr.send(Ping).await })
+        })
+        .collect();
+    for send in sends {
+        send.await??;
+    }
+
+    assert_eq!(
+        created.load(Ordering::Relaxed),
+        1,
+        "Actor should be activated once"
+    );
+    assert_eq!(addr.send(GetCounter).await?, 10);
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
@@ -0,0 +1,218 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use virtual_actor_runtime::{
+    errors::{RemoteAddrError, RemoteError, TransportError},
+    prelude::*,
+    GracefulShutdown, TransportPreferences, VirtualAddr,
+};
+
+use crate::actors::{
+    collectable_actor::{CollectableActor, GetCounter, SlowPing},
+    ping_pong_virtual_actor::{
+        VirtualGetCounter, VirtualPing, VirtualPingActor, VirtualPong, VirtualPongActor,
+    },
+};
+
+mod actors {
+    pub mod collectable_actor;
+    pub mod ping_pong_virtual_actor;
+}
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+/// Runtime owning pong actors, reachable through transport
+fn start_owner() -> Result<Runtime, Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor::<VirtualPongActor>(&executor)?;
+    runtime.register_remote_message::<VirtualPongActor, VirtualPong>();
+    runtime.register_remote_message::<VirtualPongActor, VirtualGetCounter>();
+    runtime.start_transport(&TransportPreferences::default())?;
+    Ok(runtime)
+}
+
+fn start_client() -> Result<Runtime, Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    runtime.start_transport(&TransportPreferences {
+        request_timeout: Duration::from_secs(1),
+        ..Default::default()
+    })?;
+    Ok(runtime)
+}
+
+#[tokio::test]
+async fn remote_send_test() -> Result<(), Box<dyn std::error::Error>> {
+    let owner = start_owner()?;
+    let client = start_client()?;
+    let peer = owner.transport_address().expect("Transport is started");
+
+    let remote = client.remote_addr::<VirtualPongActor>(peer, &7)?;
+    remote.send(VirtualPong).await?;
+    remote.send(VirtualPong).await?;
+    assert_eq!(remote.send(VirtualGetCounter).await?, 2);
+
+    // message is delivered to actor of owning runtime
+    let local: VirtualAddr<VirtualPongActor> = owner.spawn_virtual(&7).await?;
+    assert_eq!(local.send(VirtualGetCounter).await?, 2);
+    let other = client.remote_addr::<VirtualPongActor>(peer, &8)?;
+    assert_eq!(other.send(VirtualGetCounter).await?, 0);
+
+    client.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    owner.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn remote_dispatch_test() -> Result<(), Box<dyn std::error::Error>> {
+    let owner = start_owner()?;
+    let client = start_client()?;
+    let peer = owner.transport_address().expect("Transport is started");
+
+    let remote = client.remote_addr::<VirtualPongActor>(peer, &1)?;
+    remote.dispatch(VirtualPong).await?;
+    let mut counter = 0;
+    for _ in 0..100 {
+        counter = remote.send(VirtualGetCounter).await?;
+        if counter == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(counter, 1);
+
+    client.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    owner.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn remote_unknown_message_test() -> Result<(), Box<dyn std::error::Error>> {
+    let owner = start_owner()?;
+    let client = start_client()?;
+    let peer = owner.transport_address().expect("Transport is started");
+
+    let remote = client.remote_addr::<VirtualPingActor>(peer, &1)?;
+    let res = remote.send(VirtualPing).await;
+    assert!(
+        matches!(
+            res,
+            Err(RemoteAddrError::Remote(RemoteError::UnknownMessage {
+                ref actor_name,
+                ref message_name,
+            })) if actor_name == "VirtualPingActor" && message_name == "VirtualPing"
+        ),
+        "{res:?}"
+    );
+
+    client.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    owner.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn remote_peer_stopped_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut client = Runtime::new()?;
+    assert!(matches!(
+        client.remote_addr::<VirtualPongActor>("127.0.0.1:1".parse()?, &1),
+        Err(TransportError::NotStarted)
+    ));
+    client.start_transport(&TransportPreferences::default())?;
+    assert!(matches!(
+        client.start_transport(&TransportPreferences::default()),
+        Err(TransportError::AlreadyStarted)
+    ));
+
+    let owner = start_owner()?;
+    let peer = owner.transport_address().expect("Transport is started");
+    let remote = client.remote_addr::<VirtualPongActor>(peer, &1)?;
+    remote.send(VirtualPong).await?;
+    owner.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    let res = remote.send(VirtualGetCounter).await;
+    assert!(
+        matches!(
+            res,
+            Err(RemoteAddrError::ConnectionClosed | RemoteAddrError::Connect(_))
+        ),
+        "{res:?}"
+    );
+
+    client.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn remote_concurrent_requests_limit_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut owner = Runtime::new()?;
+    let executor = owner.create_executor()?;
+    owner.register_actor::<CollectableActor>(&executor)?;
+    owner.register_remote_message::<CollectableActor, SlowPing>();
+    owner.register_remote_message::<CollectableActor, GetCounter>();
+    let peer = owner.start_transport(&TransportPreferences {
+        max_concurrent_requests: 1,
+        ..Default::default()
+    })?;
+    let client = start_client()?;
+
+    let slow = client.remote_addr::<CollectableActor>(peer, &1)?;
+    let other = client.remote_addr::<CollectableActor>(peer, &2)?;
+    assert_eq!(other.send(GetCounter).await?, 0);
+
+    // request of other actor is not read until slow request is handled
+    let slow_ping =
+        tokio::spawn(async move { slow.send(SlowPing(Duration::from_millis(300))).await });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let started = Instant::now();
+    assert_eq!(other.send(GetCounter).await?, 0);
+    assert!(
+        started.elapsed() >= Duration::from_millis(200),
+        "Request should wait for handled request"
+    );
+    slow_ping.await??;
+
+    client.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    owner.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn remote_single_connection_test() -> Result<(), Box<dyn std::error::Error>> {
+    // peer counting accepted connections without reading them
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let peer = listener.local_addr()?;
+    let accepted = Arc::new(AtomicUsize::new(0));
+    let accepting = tokio::spawn({
+        let accepted = accepted.clone();
+        async move {
+            let mut streams = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::Relaxed);
+                streams.push(stream);
+            }
+        }
+    });
+    let client = start_client()?;
+
+    // concurrent first requests share connection
+    let dispatches: Vec<_> = (0..10)
+        .map(|id| client.remote_addr::<VirtualPongActor>(peer, &id))
+        .map(|remote| {
+            remote.map(|remote| tokio::spawn(async move { remote.dispatch(VirtualPong).await }))
+        })
+        .collect::<Result<_, _>>()?;
+    for dispatch in dispatches {
+        dispatch.await??;
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(accepted.load(Ordering::Relaxed), 1);
+
+    accepting.abort();
+    client.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use std::{future::Future, pin::Pin, task::Poll, time::Duration};
+
+use virtual_actor_runtime::{
+    errors::{DispatcherError, LocalAddrError, VirtualAddrError},
+    prelude::*,
+    GracefulShutdown, RetryPolicy, VirtualAddr,
+};
+
+use crate::actors::collectable_actor::{CollectableActor, GetCounter, Hang, Ping, Stop};
+
+mod actors {
+    pub mod collectable_actor;
+}
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+#[tokio::test]
+async fn stopped_activation_retry_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor::<CollectableActor>(&executor)?;
+
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&1).await?;
+    addr.send(Ping).await?;
+    addr.send(Stop).await?;
+
+    // stopped activation is still cached until garbage collection
+    let counter = addr.send(GetCounter).await?;
+    assert_eq!(
+        counter, 0,
+        "Message should be delivered to fresh activation"
+    );
+
+    addr.send(Stop).await?;
+    addr.dispatch(Ping).await?;
+    let counter = addr.send(GetCounter).await?;
+    assert_eq!(counter, 1, "Dispatched message should be delivered");
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retry_disabled_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::with_preferences(RuntimePreferences {
+        retry_policy: RetryPolicy {
+            max_retries: 0,
+            ..Default::default()
+        },
+        // stopped activation is kept in cache, so it is not replaced by fresh one
+        garbage_collect_interval: Duration::from_secs(3600),
+        ..Default::default()
+    })?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor::<CollectableActor>(&executor)?;
+
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&1).await?;
+    addr.send(Ping).await?;
+    // first garbage collection runs once housekeeping is started
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    addr.send(Stop).await?;
+
+    let res = addr.send(GetCounter).await;
+    assert!(
+        matches!(
+            res,
+            Err(VirtualAddrError::LocalAddrError(LocalAddrError::Stopped))
+        ),
+        "Stopped activation should be observed without retries"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn idempotent_message_retry_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    runtime.register_actor::<CollectableActor>(&executor)?;
+
+    let addr: VirtualAddr<CollectableActor> = runtime.spawn_virtual(&1).await?;
+    addr.send(Ping).await?;
+    addr.dispatch(Hang).await?;
+
+    // messages are queued behind hanging handler and dropped with cancelled activation
+    let mut ping = Box::pin(addr.send(Ping));
+    enqueue(&mut ping).await;
+    let mut get_counter = Box::pin(addr.send(GetCounter));
+    enqueue(&mut get_counter).await;
+    runtime
+        .start_watchdog(WatchdogPreferences {
+            check_interval: Duration::from_millis(10),
+            handler_budget: Duration::from_millis(50),
+            cancel_stuck_actors: true,
+            ..Default::default()
+        })
+        .await?;
+
+    let ping = ping.await;
+    assert!(
+        matches!(
+            ping,
+            Err(VirtualAddrError::LocalAddrError(
+                LocalAddrError::DispatcherError(DispatcherError::ResponseReceiverError(_))
+            ))
+        ),
+        "Not idempotent message with lost response should not be retried: {ping:?}"
+    );
+    let counter = get_counter.await?;
+    assert_eq!(
+        counter, 0,
+        "Idempotent message should be retried on fresh activation"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+/// Polls send future once, so message is queued to actor mailbox
+async fn enqueue<F: Future + Unpin>(fut: &mut F) {
+    std::future::poll_fn(|cx| {
+        assert!(
+            Pin::new(&mut *fut).poll(cx).is_pending(),
+            "Response should not be ready before cancellation"
+        );
+        Poll::Ready(())
+    })
+    .await;
+}
@@ -0,0 +1,108 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use virtual_actor_runtime::{errors::StartWatchdogError, prelude::*, GracefulShutdown, LocalAddr};
+
+use crate::actors::stuck_actor::{PendingTask, Ping, StuckActor, ThreadSleepTask};
+
+mod actors {
+    pub mod stuck_actor;
+}
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(6);
+
+fn watchdog_preferences(
+    cancel_stuck_actors: bool,
+) -> (WatchdogPreferences, Arc<Mutex<Vec<WatchdogReport>>>) {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let collected = reports.clone();
+    let preferences = WatchdogPreferences {
+        check_interval: Duration::from_millis(10),
+        handler_budget: Duration::from_millis(100),
+        heartbeat_budget: Duration::from_millis(100),
+        cancel_stuck_actors,
+        reporter: Arc::new(move |report| collected.lock().unwrap().push(report.clone())),
+    };
+    (preferences, reports)
+}
+
+#[tokio::test]
+async fn watchdog_cancels_stuck_handler_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    let (preferences, reports) = watchdog_preferences(true);
+    runtime.start_watchdog(preferences).await?;
+    assert!(matches!(
+        runtime.start_watchdog(WatchdogPreferences::default()).await,
+        Err(StartWatchdogError::AlreadyStarted)
+    ));
+
+    let addr: LocalAddr<StuckActor> = runtime.spawn_local(&executor).await?;
+
+    let res = tokio::time::timeout(Duration::from_secs(2), addr.send(PendingTask)).await?;
+    assert!(res.is_err(), "Stuck handler should be cancelled");
+
+    let reports = reports.lock().unwrap().clone();
+    let report = reports
+        .iter()
+        .find(|r| matches!(r, WatchdogReport::HandlerBudgetExceeded { .. }))
+        .expect("Stuck handler should be reported");
+    if let WatchdogReport::HandlerBudgetExceeded {
+        actor_name,
+        message_name,
+        running_for,
+        cancelled,
+        ..
+    } = report
+    {
+        assert_eq!(*actor_name, "StuckActor");
+        assert_eq!(*message_name, "PendingTask");
+        assert!(*running_for > Duration::from_millis(100));
+        assert!(cancelled);
+    }
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn watchdog_reports_blocked_executor_test() -> Result<(), Box<dyn std::error::Error>> {
+    let mut runtime = Runtime::new()?;
+    let executor = runtime.create_executor()?;
+    let (preferences, reports) = watchdog_preferences(false);
+    runtime.start_watchdog(preferences).await?;
+
+    let addr: LocalAddr<StuckActor> = runtime.spawn_local(&executor).await?;
+
+    addr.send(ThreadSleepTask(Duration::from_millis(500)))
+        .await?;
+    // actor is not cancelled and continues processing
+    addr.send(Ping).await?;
+
+    let reports = reports.lock().unwrap().clone();
+    assert!(
+        reports.iter().any(|r| matches!(
+            r,
+            WatchdogReport::ExecutorBlocked { executor_name, .. } if executor_name == "local-executor-1"
+        )),
+        "Blocked executor should be reported: {reports:?}"
+    );
+    assert!(
+        reports.iter().any(|r| matches!(
+            r,
+            WatchdogReport::HandlerBudgetExceeded {
+                message_name: "ThreadSleepTask",
+                cancelled: false,
+                ..
+            }
+        )),
+        "Blocking handler should be reported: {reports:?}"
+    );
+
+    runtime.graceful_shutdown(SHUTDOWN_TIMEOUT).await?;
+
+    Ok(())
+}
@@ -12,6 +12,12 @@ impl BoxedActorError {
         Self(Box::new(e))
     }
 
+    /// Checks if inner error is of type `T`
+    #[must_use]
+    pub fn is<T: std::error::Error + 'static>(&self) -> bool {
+        self.0.is::<T>()
+    }
+
     /// Returns inner error
     ///
     /// # Errors
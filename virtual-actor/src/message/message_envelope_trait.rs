@@ -2,10 +2,15 @@
 
 use crate::actor::Actor;
 
-use super::{Message, MessageHandler, Responder};
+use super::{Message, MessageHandler, MessageName, Responder};
 
 /// Message envelope consumed by Actor
-pub trait MessageEnvelope<A: Actor>: Send + std::fmt::Debug + Sized {}
+pub trait MessageEnvelope<A: Actor>: Send + std::fmt::Debug + Sized {
+    /// Name of the message type wrapped into envelope
+    fn message_name(&self) -> MessageName {
+        "Unknown"
+    }
+}
 
 /// Factory trait for message envelope to construct it from message type
 pub trait MessageEnvelopeFactory<A, M>: MessageEnvelope<A>
@@ -155,10 +155,7 @@ mod tests {
             TestActor: MessageHandler<M>,
             TestMessagesEnvelope: MessageEnvelopeFactory<TestActor, M>,
         {
-            Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Not implemented",
-            ))
+            Err(std::io::Error::other("Not implemented"))
         }
 
         async fn dispatch<M>(&self, _msg: M) -> Result<(), Self::Error>
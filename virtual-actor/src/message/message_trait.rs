@@ -5,4 +5,15 @@
 pub trait Message: Send + 'static {
     /// Type of result returned by message handler
     type Result: Send + 'static;
+
+    /// Copy of message which may be delivered again when outcome of delivery is unknown,
+    /// e.g. actor was deactivated before replying. `None` if message is not idempotent.
+    ///
+    /// Can be generated by `#[idempotent]` attribute of `#[derive(Message)]`
+    fn idempotent_copy(&self) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
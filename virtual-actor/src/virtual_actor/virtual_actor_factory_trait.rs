@@ -1,6 +1,8 @@
 use std::future::Future;
 
-use super::VirtualActor;
+use futures::future;
+
+use super::{VirtualActor, VirtualActorPreferences};
 use crate::actor::ActorFactory;
 
 /// Factory trait for virtual actor
@@ -16,4 +18,18 @@ where
         &self,
         id: &<Self::Actor as VirtualActor>::ActorId,
     ) -> impl Future<Output = Result<Self::Actor, Self::Error>>;
+
+    /// Called when actor is gracefully deactivated, after all queued messages are processed.
+    /// Actor is dropped right after.
+    fn deactivate_actor(
+        &self,
+        _actor: &mut Self::Actor,
+    ) -> impl Future<Output = Result<(), Self::Error>> {
+        future::ready(Ok(()))
+    }
+
+    /// Overrides of runtime preferences for actors created by factory
+    fn virtual_actor_preferences(&self) -> VirtualActorPreferences {
+        VirtualActorPreferences::default()
+    }
 }
@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+/// Overrides of runtime preferences for virtual actor type.
+/// `None` keeps value from runtime preferences.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VirtualActorPreferences {
+    /// Interval for actors garbage collection
+    pub garbage_collect_interval: Option<Duration>,
+    /// Timeout for actor to be idle before it will be collected
+    pub actor_idle_timeout: Option<Duration>,
+    /// Timeout for actor to wait for activation before returning an error
+    pub actor_activation_timeout: Option<Duration>,
+    /// Timeout for actor to wait for shutdown during graceful shutdown
+    pub actor_shutdown_interval: Option<Duration>,
+    /// Maximal number of active actors of the type
+    pub max_active_actors: Option<usize>,
+}
+
+impl VirtualActorPreferences {
+    /// Takes values which are not set from `fallback`
+    #[must_use]
+    pub fn or(self, fallback: &Self) -> Self {
+        Self {
+            garbage_collect_interval: self
+                .garbage_collect_interval
+                .or(fallback.garbage_collect_interval),
+            actor_idle_timeout: self.actor_idle_timeout.or(fallback.actor_idle_timeout),
+            actor_activation_timeout: self
+                .actor_activation_timeout
+                .or(fallback.actor_activation_timeout),
+            actor_shutdown_interval: self
+                .actor_shutdown_interval
+                .or(fallback.actor_shutdown_interval),
+            max_active_actors: self.max_active_actors.or(fallback.max_active_actors),
+        }
+    }
+}
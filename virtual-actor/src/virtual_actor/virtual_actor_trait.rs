@@ -3,8 +3,10 @@
 use crate::actor::{Actor, ActorId};
 
 /// Virtual Actor trait
-/// Actor instance can be reached by name through network
-/// using pair `name` and `id` of the actor
+/// Actor instance is addressed by pair `name` and `id` of the actor,
+/// runtime owning actor activates it on first message.
+/// Actor of another runtime is reached through network by the same pair,
+/// when address of owning runtime is known
 pub trait VirtualActor: Actor + 'static {
     /// Type of actor id
     type ActorId: ActorId;